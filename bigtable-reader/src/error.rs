@@ -32,6 +32,9 @@ pub enum Error {
     #[error("Object is corrupt: {0}")]
     ObjectCorrupt(String),
 
+    #[error("Object checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+
     #[error("RPC: {0}")]
     Rpc(tonic::Status),
 
@@ -2,6 +2,7 @@ use {
     crate::bigtable,
     crate::bigtable::RowKey,
     crate::{
+        deserializer::deserialize_bincode_cell_data,
         storage_config::LedgerStorageConfig,
         storage_stats::LedgerStorageStats,
     },
@@ -11,7 +12,9 @@ use {
     async_trait::async_trait,
     log::*,
     solana_clock::{
+        Epoch,
         Slot,
+        UnixTimestamp,
     },
     solana_pubkey::{
         Pubkey,
@@ -24,6 +27,7 @@ use {
         ConfirmedBlock,
         ConfirmedTransactionStatusWithSignature,
         ConfirmedTransactionWithStatusMeta,
+        Reward,
         TransactionByAddrInfo,
     },
     solana_transaction_status_client_types::{
@@ -35,6 +39,33 @@ use {
         LegacyTransactionByAddrInfo,
     },
     solana_storage_utils::{
+        block_index::{
+            block_height_to_key,
+            blockhash_to_key,
+            slot_to_block_height_key,
+            BLOCK_INDEX_TABLE_NAME,
+        },
+        block_time::{
+            block_time_prefix,
+            block_time_prefix_end,
+            key_to_block_time,
+            slot_to_block_time_key,
+            BLOCK_TIME_TABLE_NAME,
+        },
+        leader_index::{
+            key_to_leader_slot,
+            leader_slot_key,
+            leader_slot_key_end,
+            slot_to_leader_key,
+            LEADER_INDEX_TABLE_NAME,
+        },
+        rewards::{
+            is_inflation_reward,
+            key_to_rewards,
+            rewards_epoch_prefix,
+            RewardCell,
+            REWARDS_TABLE_NAME,
+        },
         tx_info::TransactionInfo,
         slot_to_blocks_key,
         slot_to_tx_by_addr_key,
@@ -58,6 +89,9 @@ impl std::convert::From<bigtable::Error> for Error {
 
 
 
+/// Rows read per request when scanning the rewards of an address.
+const REWARDS_ROWS_BATCH_SIZE: i64 = 1000;
+
 #[derive(Clone)]
 pub struct LedgerStorage {
     connection: bigtable::BigTableConnection,
@@ -171,6 +205,17 @@ impl LedgerStorage {
         Ok(block_exists)
     }
 
+    /// Value of a `block-index` row, see `solana_storage_utils::block_index`.
+    async fn get_block_index_cell(&self, row_key: String) -> Result<Option<u64>> {
+        self.stats.increment_num_queries();
+        let mut bigtable = self.connection.client();
+        match bigtable.get_bincode_cell::<u64>(BLOCK_INDEX_TABLE_NAME, row_key).await {
+            Ok(value) => Ok(Some(value)),
+            Err(bigtable::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     // Fetches and gets a vector of confirmed transactions via a multirow fetch
     pub async fn get_confirmed_transactions(
         &self,
@@ -509,6 +554,59 @@ impl LedgerStorageAdapter for LedgerStorage {
         Ok(infos)
     }
 
+    async fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTimestamp>> {
+        self.stats.increment_num_queries();
+        let mut bigtable = self.connection.client();
+        match bigtable
+            .get_bincode_cell::<UnixTimestamp>(BLOCK_TIME_TABLE_NAME, slot_to_block_time_key(slot))
+            .await
+        {
+            Ok(block_time) => Ok(Some(block_time)),
+            Err(bigtable::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn get_slot_at_time(&self, timestamp: UnixTimestamp) -> Result<Option<Slot>> {
+        Ok(self
+            .get_blocks_in_time_range(UnixTimestamp::MIN, timestamp, 1)
+            .await?
+            .first()
+            .map(|(slot, _)| *slot))
+    }
+
+    async fn get_blocks_in_time_range(
+        &self,
+        start_time: UnixTimestamp,
+        end_time: UnixTimestamp,
+        limit: usize,
+    ) -> Result<Vec<(Slot, UnixTimestamp)>> {
+        if start_time > end_time {
+            return Ok(vec![]);
+        }
+        self.stats.increment_num_queries();
+        let mut bigtable = self.connection.client();
+        let row_keys = bigtable
+            .get_row_keys(
+                BLOCK_TIME_TABLE_NAME,
+                Some(block_time_prefix(end_time)),
+                Some(block_time_prefix_end(start_time)),
+                limit as i64,
+            )
+            .await?;
+
+        row_keys
+            .iter()
+            .map(|row_key| {
+                key_to_block_time(row_key)
+                    .map(|(block_time, slot)| (slot, block_time))
+                    .ok_or_else(|| {
+                        bigtable::Error::ObjectCorrupt(format!("{BLOCK_TIME_TABLE_NAME}/{row_key}")).into()
+                    })
+            })
+            .collect()
+    }
+
     async fn get_latest_stored_slot(&self) -> Result<Slot> {
         Err(Error::StorageBackendError(Box::new(std::io::Error::new(
             std::io::ErrorKind::Other,
@@ -516,6 +614,119 @@ impl LedgerStorageAdapter for LedgerStorage {
         ))))
     }
 
+    async fn get_block_by_hash(&self, blockhash: &str) -> Result<Option<(Slot, ConfirmedBlock)>> {
+        let Some(slot) = self.get_block_index_cell(blockhash_to_key(blockhash)).await? else {
+            return Ok(None);
+        };
+        match self.get_confirmed_block(slot).await {
+            Ok(block) => Ok(Some((slot, block))),
+            Err(Error::BlockNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn get_slot_by_block_height(&self, block_height: u64) -> Result<Option<Slot>> {
+        self.get_block_index_cell(block_height_to_key(block_height)).await
+    }
+
+    async fn get_block_height(&self, slot: Slot) -> Result<Option<u64>> {
+        self.get_block_index_cell(slot_to_block_height_key(slot)).await
+    }
+
+    async fn get_inflation_rewards(
+        &self,
+        address: &Pubkey,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<(Epoch, Slot, Reward)>> {
+        if start_epoch > end_epoch {
+            return Ok(vec![]);
+        }
+        self.stats.increment_num_queries();
+        let mut bigtable = self.connection.client();
+        let address = address.to_string();
+        // Row keys of an address have the same length, so the suffix resumes the scan after a
+        // row. The end is never a row key itself.
+        let mut start_key = rewards_epoch_prefix(&address, start_epoch);
+        let end_key = rewards_epoch_prefix(&address, end_epoch.saturating_add(1));
+
+        let mut rewards = vec![];
+        loop {
+            let row_data = bigtable
+                .get_row_data(
+                    REWARDS_TABLE_NAME,
+                    Some(start_key.clone()),
+                    Some(end_key.clone()),
+                    REWARDS_ROWS_BATCH_SIZE,
+                )
+                .await?;
+            let is_last_batch = row_data.len() < REWARDS_ROWS_BATCH_SIZE as usize;
+
+            for (row_key, data) in row_data {
+                let (_, epoch, slot) = key_to_rewards(&row_key).ok_or_else(|| {
+                    bigtable::Error::ObjectCorrupt(format!("{REWARDS_TABLE_NAME}/{row_key}"))
+                })?;
+                start_key = format!("{row_key}0");
+                let cells: Vec<RewardCell> =
+                    deserialize_bincode_cell_data(&data, REWARDS_TABLE_NAME, row_key)?;
+                rewards.extend(
+                    cells
+                        .into_iter()
+                        .filter(|cell| is_inflation_reward(cell.reward_type))
+                        .map(|cell| (epoch, slot, cell.into_reward(address.clone()))),
+                );
+            }
+
+            if is_last_batch {
+                return Ok(rewards);
+            }
+        }
+    }
+
+    async fn get_block_leader(&self, slot: Slot) -> Result<Option<Pubkey>> {
+        self.stats.increment_num_queries();
+        let mut bigtable = self.connection.client();
+        match bigtable
+            .get_bincode_cell::<Pubkey>(LEADER_INDEX_TABLE_NAME, slot_to_leader_key(slot))
+            .await
+        {
+            Ok(leader) => Ok(Some(leader)),
+            Err(bigtable::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn get_blocks_by_leader(
+        &self,
+        leader: &Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<Slot>> {
+        if start_slot > end_slot {
+            return Ok(vec![]);
+        }
+        self.stats.increment_num_queries();
+        let mut bigtable = self.connection.client();
+        let row_keys = bigtable
+            .get_row_keys(
+                LEADER_INDEX_TABLE_NAME,
+                Some(leader_slot_key(leader, start_slot)),
+                Some(leader_slot_key_end(leader, end_slot)),
+                limit as i64,
+            )
+            .await?;
+
+        row_keys
+            .iter()
+            .map(|row_key| {
+                key_to_leader_slot(row_key).ok_or_else(|| {
+                    bigtable::Error::ObjectCorrupt(format!("{LEADER_INDEX_TABLE_NAME}/{row_key}")).into()
+                })
+            })
+            .collect()
+    }
+
     fn clone_box(&self) -> Box<dyn LedgerStorageAdapter> {
         Box::new(self.clone())
     }
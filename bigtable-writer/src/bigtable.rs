@@ -11,16 +11,14 @@ use {
         str::FromStr,
         time::{
             Duration,
-            // Instant,
+            Instant,
         },
     },
     thiserror::Error,
     tonic::{codegen::InterceptedService, transport::ClientTlsConfig, Request, Status},
     solana_storage_utils::{
-        compression::{
-            compress_best,
-            // decompress,
-        },
+        cell_envelope::{unwrap_cell, wrap_cell, CellError, PayloadEncoding},
+        compression::compress_best,
     },
 };
 
@@ -28,7 +26,7 @@ use solana_bigtable_shared::google::bigtable::v2::*;
 
 pub type RowKey = String;
 pub type RowData = Vec<(CellName, CellValue)>;
-// pub type RowDataSlice<'a> = &'a [(CellName, CellValue)];
+pub type RowDataSlice<'a> = &'a [(CellName, CellValue)];
 pub type CellName = String;
 pub type CellValue = Vec<u8>;
 // pub enum CellData<B, P> {
@@ -62,11 +60,14 @@ pub enum Error {
     #[error("Row delete failed")]
     RowDeleteFailed,
 
-    // #[error("Object not found: {0}")]
-    // ObjectNotFound(String),
+    #[error("Object not found: {0}")]
+    ObjectNotFound(String),
+
+    #[error("Object is corrupt: {0}")]
+    ObjectCorrupt(String),
 
-    // #[error("Object is corrupt: {0}")]
-    // ObjectCorrupt(String),
+    #[error("Object checksum mismatch: {0}")]
+    ChecksumMismatch(String),
 
     #[error("RPC: {0}")]
     Rpc(tonic::Status),
@@ -246,17 +247,14 @@ impl BigTableConnection {
         }
     }
 
-    pub async fn put_bincode_cells_with_retry<T>(
+    pub async fn put_row_data_with_retry(
         &self,
         table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
+        row_data: &[(RowKey, RowData)],
+    ) -> Result<()> {
         retry(ExponentialBackoff::default(), || async {
             let mut client = self.client();
-            Ok(client.put_bincode_cells(table, cells).await?)
+            Ok(client.put_row_data(table, "x", row_data).await?)
         })
         .await
     }
@@ -269,6 +267,18 @@ impl BigTableConnection {
         .await
     }
 
+    pub async fn get_multi_row_data_with_retry(
+        &self,
+        table: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client();
+            Ok(client.get_multi_row_data(table, row_keys).await?)
+        })
+        .await
+    }
+
     // pub async fn get_bincode_cells_with_retry<T>(
     //     &self,
     //     table: &str,
@@ -283,21 +293,6 @@ impl BigTableConnection {
     //     })
     //     .await
     // }
-
-    pub async fn put_protobuf_cells_with_retry<T>(
-        &self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
-        retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            Ok(client.put_protobuf_cells(table, cells).await?)
-        })
-        .await
-    }
 }
 
 pub struct BigTable<F: FnMut(Request<()>) -> InterceptedRequestResult> {
@@ -309,86 +304,86 @@ pub struct BigTable<F: FnMut(Request<()>) -> InterceptedRequestResult> {
 }
 
 impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
-    // async fn decode_read_rows_response(
-    //     &self,
-    //     mut rrr: tonic::codec::Streaming<ReadRowsResponse>,
-    // ) -> Result<Vec<(RowKey, RowData)>> {
-    //     let mut rows: Vec<(RowKey, RowData)> = vec![];
-    //
-    //     let mut row_key = None;
-    //     let mut row_data = vec![];
-    //
-    //     let mut cell_name = None;
-    //     let mut cell_timestamp = 0;
-    //     let mut cell_value = vec![];
-    //     let mut cell_version_ok = true;
-    //     let started = Instant::now();
-    //
-    //     while let Some(res) = rrr.message().await? {
-    //         if let Some(timeout) = self.timeout {
-    //             if Instant::now().duration_since(started) > timeout {
-    //                 return Err(Error::Timeout);
-    //             }
-    //         }
-    //         for (i, mut chunk) in res.chunks.into_iter().enumerate() {
-    //             // The comments for `read_rows_response::CellChunk` provide essential details for
-    //             // understanding how the below decoding works...
-    //             trace!("chunk {}: {:?}", i, chunk);
-    //
-    //             // Starting a new row?
-    //             if !chunk.row_key.is_empty() {
-    //                 row_key = String::from_utf8(chunk.row_key).ok(); // Require UTF-8 for row keys
-    //             }
-    //
-    //             // Starting a new cell?
-    //             if let Some(qualifier) = chunk.qualifier {
-    //                 if let Some(cell_name) = cell_name {
-    //                     row_data.push((cell_name, cell_value));
-    //                     cell_value = vec![];
-    //                 }
-    //                 cell_name = String::from_utf8(qualifier).ok(); // Require UTF-8 for cell names
-    //                 cell_timestamp = chunk.timestamp_micros;
-    //                 cell_version_ok = true;
-    //             } else {
-    //                 // Continuing the existing cell.  Check if this is the start of another version of the cell
-    //                 if chunk.timestamp_micros != 0 {
-    //                     if chunk.timestamp_micros < cell_timestamp {
-    //                         cell_version_ok = false; // ignore older versions of the cell
-    //                     } else {
-    //                         // newer version of the cell, remove the older cell
-    //                         cell_version_ok = true;
-    //                         cell_value = vec![];
-    //                         cell_timestamp = chunk.timestamp_micros;
-    //                     }
-    //                 }
-    //             }
-    //             if cell_version_ok {
-    //                 cell_value.append(&mut chunk.value);
-    //             }
-    //
-    //             // End of a row?
-    //             if chunk.row_status.is_some() {
-    //                 if let Some(read_rows_response::cell_chunk::RowStatus::CommitRow(_)) =
-    //                     chunk.row_status
-    //                 {
-    //                     if let Some(cell_name) = cell_name {
-    //                         row_data.push((cell_name, cell_value));
-    //                     }
-    //
-    //                     if let Some(row_key) = row_key {
-    //                         rows.push((row_key, row_data))
-    //                     }
-    //                 }
-    //
-    //                 row_key = None;
-    //                 row_data = vec![];
-    //                 cell_value = vec![];
-    //                 cell_name = None;
-    //             }
-    //         }
-    //     }
-    //     Ok(rows)
-    // }
+    async fn decode_read_rows_response(
+        &self,
+        mut rrr: tonic::codec::Streaming<ReadRowsResponse>,
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let mut rows: Vec<(RowKey, RowData)> = vec![];
+
+        let mut row_key = None;
+        let mut row_data = vec![];
+
+        let mut cell_name = None;
+        let mut cell_timestamp = 0;
+        let mut cell_value = vec![];
+        let mut cell_version_ok = true;
+        let started = Instant::now();
+
+        while let Some(res) = rrr.message().await? {
+            if let Some(timeout) = self.timeout {
+                if Instant::now().duration_since(started) > timeout {
+                    return Err(Error::Timeout);
+                }
+            }
+            for (i, mut chunk) in res.chunks.into_iter().enumerate() {
+                // The comments for `read_rows_response::CellChunk` provide essential details for
+                // understanding how the below decoding works...
+                trace!("chunk {}: {:?}", i, chunk);
+
+                // Starting a new row?
+                if !chunk.row_key.is_empty() {
+                    row_key = String::from_utf8(chunk.row_key).ok(); // Require UTF-8 for row keys
+                }
+
+                // Starting a new cell?
+                if let Some(qualifier) = chunk.qualifier {
+                    if let Some(cell_name) = cell_name {
+                        row_data.push((cell_name, cell_value));
+                        cell_value = vec![];
+                    }
+                    cell_name = String::from_utf8(qualifier).ok(); // Require UTF-8 for cell names
+                    cell_timestamp = chunk.timestamp_micros;
+                    cell_version_ok = true;
+                } else {
+                    // Continuing the existing cell.  Check if this is the start of another version of the cell
+                    if chunk.timestamp_micros != 0 {
+                        if chunk.timestamp_micros < cell_timestamp {
+                            cell_version_ok = false; // ignore older versions of the cell
+                        } else {
+                            // newer version of the cell, remove the older cell
+                            cell_version_ok = true;
+                            cell_value = vec![];
+                            cell_timestamp = chunk.timestamp_micros;
+                        }
+                    }
+                }
+                if cell_version_ok {
+                    cell_value.append(&mut chunk.value);
+                }
+
+                // End of a row?
+                if chunk.row_status.is_some() {
+                    if let Some(read_rows_response::cell_chunk::RowStatus::CommitRow(_)) =
+                        chunk.row_status
+                    {
+                        if let Some(cell_name) = cell_name {
+                            row_data.push((cell_name, cell_value));
+                        }
+
+                        if let Some(row_key) = row_key {
+                            rows.push((row_key, row_data))
+                        }
+                    }
+
+                    row_key = None;
+                    row_data = vec![];
+                    cell_value = vec![];
+                    cell_name = None;
+                }
+            }
+        }
+        Ok(rows)
+    }
 
     async fn refresh_access_token(&self) {
         if let Some(ref access_token) = self.access_token {
@@ -532,38 +527,38 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
     //     self.decode_read_rows_response(response).await
     // }
 
-    // /// Get latest data from multiple rows of `table`, if those rows exist.
-    // pub async fn get_multi_row_data(
-    //     &mut self,
-    //     table_name: &str,
-    //     row_keys: &[RowKey],
-    // ) -> Result<Vec<(RowKey, RowData)>> {
-    //     self.refresh_access_token().await;
-    //
-    //     let response = self
-    //         .client
-    //         .read_rows(ReadRowsRequest {
-    //             table_name: format!("{}{}", self.table_prefix, table_name),
-    //             app_profile_id: self.app_profile_id.clone(),
-    //             rows_limit: 0, // return all existing rows
-    //             rows: Some(RowSet {
-    //                 row_keys: row_keys
-    //                     .iter()
-    //                     .map(|k| k.as_bytes().to_vec())
-    //                     .collect::<Vec<_>>(),
-    //                 row_ranges: vec![],
-    //             }),
-    //             filter: Some(RowFilter {
-    //                 // Only return the latest version of each cell
-    //                 filter: Some(row_filter::Filter::CellsPerColumnLimitFilter(1)),
-    //             }),
-    //         })
-    //         .await?
-    //         .into_inner();
-    //
-    //     self.decode_read_rows_response(response).await
-    // }
-    //
+    /// Get latest data from multiple rows of `table`, if those rows exist.
+    pub async fn get_multi_row_data(
+        &mut self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        self.refresh_access_token().await;
+
+        let response = self
+            .client
+            .read_rows(ReadRowsRequest {
+                table_name: format!("{}{}", self.table_prefix, table_name),
+                app_profile_id: self.app_profile_id.clone(),
+                rows_limit: 0, // return all existing rows
+                rows: Some(RowSet {
+                    row_keys: row_keys
+                        .iter()
+                        .map(|k| k.as_bytes().to_vec())
+                        .collect::<Vec<_>>(),
+                    row_ranges: vec![],
+                }),
+                filter: Some(RowFilter {
+                    // Only return the latest version of each cell
+                    filter: Some(row_filter::Filter::CellsPerColumnLimitFilter(1)),
+                }),
+            })
+            .await?
+            .into_inner();
+
+        self.decode_read_rows_response(response).await
+    }
+
     // /// Get latest data from a single row of `table`, if that row exists. Returns an error if that
     // /// row does not exist.
     // ///
@@ -646,7 +641,7 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
         &mut self,
         table_name: &str,
         family_name: &str,
-        row_data: &[(&RowKey, RowData)],
+        row_data: &[(RowKey, RowData)],
     ) -> Result<()> {
         self.refresh_access_token().await;
 
@@ -665,7 +660,7 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
                 .collect();
 
             entries.push(mutate_rows_request::Entry {
-                row_key: (*row_key).clone().into_bytes(),
+                row_key: row_key.clone().into_bytes(),
                 mutations,
             });
         }
@@ -761,50 +756,56 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
     //             )
     //         }))
     // }
+}
 
-    pub async fn put_bincode_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: serde::ser::Serialize,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let data = compress_best(&bincode::serialize(&data).unwrap())?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
-        }
-
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
+/// Serialize and compress `cells` into the `bin` cells of their rows, in the cell envelope if
+/// `use_envelope` is set.
+pub fn encode_bincode_cells<T>(cells: &[(RowKey, T)], use_envelope: bool) -> Result<Vec<(RowKey, RowData)>>
+where
+    T: serde::ser::Serialize,
+{
+    let mut row_data = vec![];
+    for (row_key, data) in cells {
+        let data = compress_best(&bincode::serialize(&data).unwrap())?;
+        let data = envelope(PayloadEncoding::Bincode, data, use_envelope);
+        row_data.push((row_key.clone(), vec![("bin".to_string(), data)]));
     }
+    Ok(row_data)
+}
 
-    pub async fn put_protobuf_cells<T>(
-        &mut self,
-        table: &str,
-        cells: &[(RowKey, T)],
-    ) -> Result<usize>
-    where
-        T: prost::Message,
-    {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let mut buf = Vec::with_capacity(data.encoded_len());
-            data.encode(&mut buf).unwrap();
-            let data = compress_best(&buf)?;
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
-        }
+/// Encode and compress `cells` into the `proto` cells of their rows, in the cell envelope if
+/// `use_envelope` is set.
+pub fn encode_protobuf_cells<T>(cells: &[(RowKey, T)], use_envelope: bool) -> Result<Vec<(RowKey, RowData)>>
+where
+    T: prost::Message,
+{
+    let mut row_data = vec![];
+    for (row_key, data) in cells {
+        let mut buf = Vec::with_capacity(data.encoded_len());
+        data.encode(&mut buf).unwrap();
+        let data = compress_best(&buf)?;
+        let data = envelope(PayloadEncoding::Protobuf, data, use_envelope);
+        row_data.push((row_key.clone(), vec![("proto".to_string(), data)]));
+    }
+    Ok(row_data)
+}
 
-        self.put_row_data(table, "x", &new_row_data).await?;
-        Ok(bytes_written)
+fn envelope(encoding: PayloadEncoding, compressed: Vec<u8>, use_envelope: bool) -> Vec<u8> {
+    if use_envelope {
+        wrap_cell(encoding, &compressed)
+    } else {
+        compressed
     }
 }
 
+/// Bytes of the cell values of `row_data`.
+pub fn row_data_size(row_data: &[(RowKey, RowData)]) -> usize {
+    row_data
+        .iter()
+        .flat_map(|(_, cells)| cells.iter().map(|(_, value)| value.len()))
+        .sum()
+}
+
 // pub(crate) fn deserialize_protobuf_or_bincode_cell_data<B, P>(
 //     row_data: RowDataSlice,
 //     table: &str,
@@ -823,45 +824,88 @@ impl<F: FnMut(Request<()>) -> InterceptedRequestResult> BigTable<F> {
 //     }
 //     deserialize_bincode_cell_data(row_data, table, key).map(CellData::Bincode)
 // }
-//
-// pub(crate) fn deserialize_protobuf_cell_data<T>(
-//     row_data: RowDataSlice,
-//     table: &str,
-//     key: RowKey,
-// ) -> Result<T>
-// where
-//     T: prost::Message + Default,
-// {
-//     let value = &row_data
-//         .iter()
-//         .find(|(name, _)| name == "proto")
-//         .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
-//         .1;
-//
-//     let data = decompress(value)?;
-//     T::decode(&data[..]).map_err(|err| {
-//         warn!("Failed to deserialize {}/{}: {}", table, key, err);
-//         Error::ObjectCorrupt(format!("{table}/{key}"))
-//     })
-// }
-//
-// pub(crate) fn deserialize_bincode_cell_data<T>(
-//     row_data: RowDataSlice,
-//     table: &str,
-//     key: RowKey,
-// ) -> Result<T>
-// where
-//     T: serde::de::DeserializeOwned,
-// {
-//     let value = &row_data
-//         .iter()
-//         .find(|(name, _)| name == "bin")
-//         .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
-//         .1;
-//
-//     let data = decompress(value)?;
-//     bincode::deserialize(&data).map_err(|err| {
-//         warn!("Failed to deserialize {}/{}: {}", table, key, err);
-//         Error::ObjectCorrupt(format!("{table}/{key}"))
-//     })
-// }
+
+pub(crate) fn deserialize_protobuf_cell_data<T>(
+    row_data: RowDataSlice,
+    table: &str,
+    key: RowKey,
+) -> Result<T>
+where
+    T: prost::Message + Default,
+{
+    let value = &row_data
+        .iter()
+        .find(|(name, _)| name == "proto")
+        .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
+        .1;
+
+    let data = unwrap_cell_data(value, table, &key)?;
+    T::decode(&data[..]).map_err(|err| {
+        warn!("Failed to deserialize {}/{}: {}", table, key, err);
+        Error::ObjectCorrupt(format!("{table}/{key}"))
+    })
+}
+
+pub(crate) fn deserialize_bincode_cell_data<T>(
+    row_data: RowDataSlice,
+    table: &str,
+    key: RowKey,
+) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let value = &row_data
+        .iter()
+        .find(|(name, _)| name == "bin")
+        .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
+        .1;
+
+    let data = unwrap_cell_data(value, table, &key)?;
+    bincode::deserialize(&data).map_err(|err| {
+        warn!("Failed to deserialize {}/{}: {}", table, key, err);
+        Error::ObjectCorrupt(format!("{table}/{key}"))
+    })
+}
+
+/// Verify and decompress a cell value, with or without the envelope.
+fn unwrap_cell_data(value: &[u8], table: &str, key: &str) -> Result<Vec<u8>> {
+    match unwrap_cell(value) {
+        Ok((_, data)) => Ok(data),
+        Err(CellError::Io(err)) => Err(Error::Io(err)),
+        Err(err @ CellError::ChecksumMismatch { .. }) => {
+            warn!("Failed to verify {}/{}: {}", table, key, err);
+            Err(Error::ChecksumMismatch(format!("{table}/{key}")))
+        }
+        Err(err) => {
+            warn!("Failed to unwrap {}/{}: {}", table, key, err);
+            Err(Error::ObjectCorrupt(format!("{table}/{key}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_encode_cells_with_envelope() {
+        let cells = vec![("key".to_string(), 42u64)];
+        for use_envelope in [false, true] {
+            let row_data = encode_bincode_cells(&cells, use_envelope).unwrap();
+            let (_, cell) = &row_data[0];
+            assert_eq!(cell[0].1.starts_with(b"SLCE"), use_envelope);
+            assert_eq!(
+                deserialize_bincode_cell_data::<u64>(cell, "tx", "key".to_string()).unwrap(),
+                42
+            );
+        }
+
+        let mut row_data = encode_bincode_cells(&cells, true).unwrap();
+        let value = &mut row_data[0].1[0].1;
+        *value.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            deserialize_bincode_cell_data::<u64>(&row_data[0].1, "tx", "key".to_string()),
+            Err(Error::ChecksumMismatch(_))
+        ));
+    }
+}
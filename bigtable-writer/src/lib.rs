@@ -10,6 +10,8 @@ use {
     log::*,
     solana_clock::{
         Slot,
+        UnixTimestamp,
+        DEFAULT_SLOTS_PER_EPOCH,
     },
     solana_pubkey::{
         Pubkey,
@@ -18,28 +20,40 @@ use {
     solana_storage_proto::convert::{generated, tx_by_addr},
     solana_transaction_status::{
         extract_and_fmt_memos,
+        ConfirmedBlock,
         TransactionByAddrInfo,
         VersionedConfirmedBlock,
         VersionedTransactionWithStatusMeta,
     },
     solana_storage_writer::{
-        Error, Result, LedgerStorageAdapter,
+        DeleteReport, Error, Result, LedgerStorageAdapter, TableUploadStats, UploadReport,
     },
     solana_storage_utils::{
+        block_rows::block_row_keys,
+        block_index::{block_index_cells, BLOCK_INDEX_TABLE_NAME},
+        block_time::{block_time_to_key, slot_to_block_time_key, BLOCK_TIME_TABLE_NAME},
+        leader_index::{leader_index_cells, LEADER_INDEX_TABLE_NAME},
+        rewards::{rewards_cells, RewardCell, REWARDS_TABLE_NAME},
+        token_index::{token_balance_accounts, TX_BY_MINT_TABLE_NAME, TX_BY_OWNER_TABLE_NAME},
         tx_info::TransactionInfo,
         slot_to_blocks_key,
         slot_to_tx_by_addr_key,
     },
     std::{
         collections::{
+            BTreeMap,
+            BTreeSet,
             HashMap,
         },
-        time::Duration,
+        time::{Duration, Instant},
     },
+    tokio::task::JoinHandle,
 };
 
 mod bigtable;
 
+/// Rows read or deleted per request when deleting a block.
+const ROWS_BATCH_SIZE: usize = 1000;
 
 impl std::convert::From<bigtable::Error> for Error {
     fn from(err: bigtable::Error) -> Self {
@@ -55,6 +69,25 @@ pub struct LedgerStorageConfig {
     pub credential_type: CredentialType,
     pub instance_name: String,
     pub app_profile_id: String,
+    /// Index transactions under the owners of the token accounts in their token balances.
+    pub index_token_owners: bool,
+    /// Index transactions under the mints in their token balances.
+    pub index_token_mints: bool,
+    /// Write the token owner and mint rows to `tx-by-addr` instead of `tx-by-owner` and
+    /// `tx-by-mint`.
+    pub token_index_in_tx_by_addr: bool,
+    /// Index blocks by their block time in `block-time`.
+    pub enable_block_time_index: bool,
+    /// Index blocks by blockhash and block height in `block-index`.
+    pub enable_block_index: bool,
+    /// Index block rewards by recipient and epoch in `rewards`.
+    pub enable_rewards_index: bool,
+    /// Slots per epoch of the cluster, to key the rewards by epoch.
+    pub slots_per_epoch: u64,
+    /// Index blocks by their leader in `leader-index`.
+    pub enable_leader_index: bool,
+    /// Write cells in the checksummed envelope of `solana_storage_utils::cell_envelope`.
+    pub use_cell_envelope: bool,
 }
 
 impl Default for LedgerStorageConfig {
@@ -65,6 +98,15 @@ impl Default for LedgerStorageConfig {
             credential_type: CredentialType::Filepath(None),
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
+            index_token_owners: false,
+            index_token_mints: false,
+            token_index_in_tx_by_addr: false,
+            enable_block_time_index: false,
+            enable_block_index: false,
+            enable_rewards_index: false,
+            slots_per_epoch: DEFAULT_SLOTS_PER_EPOCH,
+            enable_leader_index: false,
+            use_cell_envelope: false,
         }
     }
 }
@@ -73,6 +115,15 @@ impl Default for LedgerStorageConfig {
 #[derive(Clone)]
 pub struct LedgerStorage {
     connection: bigtable::BigTableConnection,
+    index_token_owners: bool,
+    index_token_mints: bool,
+    token_index_in_tx_by_addr: bool,
+    enable_block_time_index: bool,
+    enable_block_index: bool,
+    enable_rewards_index: bool,
+    slots_per_epoch: u64,
+    enable_leader_index: bool,
+    use_cell_envelope: bool,
     // stats: Arc<LedgerStorageStats>,
 }
 
@@ -105,6 +156,15 @@ impl LedgerStorage {
                 endpoint,
                 timeout,
             )?,
+            index_token_owners: false,
+            index_token_mints: false,
+            token_index_in_tx_by_addr: false,
+            enable_block_time_index: false,
+            enable_block_index: false,
+            enable_rewards_index: false,
+            slots_per_epoch: DEFAULT_SLOTS_PER_EPOCH,
+            enable_leader_index: false,
+            use_cell_envelope: false,
             // stats,
         })
     }
@@ -117,6 +177,15 @@ impl LedgerStorage {
             instance_name,
             app_profile_id,
             credential_type,
+            index_token_owners,
+            index_token_mints,
+            token_index_in_tx_by_addr,
+            enable_block_time_index,
+            enable_block_index,
+            enable_rewards_index,
+            slots_per_epoch,
+            enable_leader_index,
+            use_cell_envelope,
         } = config;
         let connection = bigtable::BigTableConnection::new(
             instance_name.as_str(),
@@ -126,7 +195,19 @@ impl LedgerStorage {
             credential_type,
         )
             .await?;
-        Ok(Self { /*stats,*/ connection })
+        Ok(Self {
+            /*stats,*/
+            connection,
+            index_token_owners,
+            index_token_mints,
+            token_index_in_tx_by_addr,
+            enable_block_time_index,
+            enable_block_index,
+            enable_rewards_index,
+            slots_per_epoch,
+            enable_leader_index,
+            use_cell_envelope,
+        })
     }
 
     pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
@@ -136,21 +217,13 @@ impl LedgerStorage {
         })
             .await
     }
-}
 
-#[async_trait]
-impl LedgerStorageAdapter for LedgerStorage {
-    /// Upload a new confirmed block and associated meta data.
-    async fn upload_confirmed_block(
-        &self,
-        slot: Slot,
-        confirmed_block: VersionedConfirmedBlock,
-    ) -> Result<()> {
-        trace!(
-            "LedgerStorage::upload_confirmed_block request received: {:?}",
-            slot
-        );
-        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+    /// Rows derived from the block at `slot`.
+    fn block_cells(&self, slot: Slot, confirmed_block: VersionedConfirmedBlock) -> BlockCells {
+        let mut by_addr: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let mut by_owner: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let mut by_mint: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let report = UploadReport::new(slot, confirmed_block.transactions.len());
 
         let reserved_account_keys = ReservedAccountKeys::new_all_activated();
         let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
@@ -165,7 +238,7 @@ impl LedgerStorageAdapter for LedgerStorage {
                 // if !is_sysvar_id(address) {
                 if !reserved_account_keys.is_reserved(address) {
                     by_addr
-                        .entry(address)
+                        .entry(*address)
                         .or_default()
                         .push(TransactionByAddrInfo {
                             signature,
@@ -177,6 +250,25 @@ impl LedgerStorageAdapter for LedgerStorage {
                 }
             }
 
+            if self.index_token_owners || self.index_token_mints {
+                let token_accounts = token_balance_accounts(meta);
+                let info = TransactionByAddrInfo {
+                    signature,
+                    err: err.clone(),
+                    index,
+                    memo: memo.clone(),
+                    block_time: confirmed_block.block_time,
+                };
+                if self.index_token_owners {
+                    let rows = if self.token_index_in_tx_by_addr { &mut by_addr } else { &mut by_owner };
+                    index_token_accounts(token_accounts.owners, &info, rows);
+                }
+                if self.index_token_mints {
+                    let rows = if self.token_index_in_tx_by_addr { &mut by_addr } else { &mut by_mint };
+                    index_token_accounts(token_accounts.mints, &info, rows);
+                }
+            }
+
             tx_cells.push((
                 signature.to_string(),
                 TransactionInfo {
@@ -188,43 +280,262 @@ impl LedgerStorageAdapter for LedgerStorage {
             ));
         }
 
-        let tx_by_addr_cells: Vec<_> = by_addr
+        let block_time_cells = match confirmed_block.block_time {
+            Some(block_time) if self.enable_block_time_index => vec![
+                (slot_to_block_time_key(slot), block_time),
+                (block_time_to_key(block_time, slot), block_time),
+            ],
+            _ => vec![],
+        };
+        let block_index_cells = if self.enable_block_index {
+            block_index_cells(slot, &confirmed_block.blockhash, confirmed_block.block_height)
+        } else {
+            vec![]
+        };
+        let rewards_cells = if self.enable_rewards_index {
+            rewards_cells(slot, self.slots_per_epoch, &confirmed_block.rewards)
+        } else {
+            vec![]
+        };
+        let leader_index_cells = if self.enable_leader_index {
+            leader_index_cells(slot, &confirmed_block.rewards)
+        } else {
+            vec![]
+        };
+
+        BlockCells {
+            report,
+            tx_cells,
+            tx_by_addr_cells: tx_by_addr_cells(slot, by_addr),
+            tx_by_owner_cells: tx_by_addr_cells(slot, by_owner),
+            tx_by_mint_cells: tx_by_addr_cells(slot, by_mint),
+            block_time_cells,
+            block_index_cells,
+            rewards_cells,
+            leader_index_cells,
+            block_cell: (slot_to_blocks_key(slot, false), confirmed_block.into()),
+        }
+    }
+}
+
+/// Rows derived from one block, before they are encoded.
+struct BlockCells {
+    report: UploadReport,
+    tx_cells: Vec<(bigtable::RowKey, TransactionInfo)>,
+    tx_by_addr_cells: Vec<(bigtable::RowKey, tx_by_addr::TransactionByAddr)>,
+    tx_by_owner_cells: Vec<(bigtable::RowKey, tx_by_addr::TransactionByAddr)>,
+    tx_by_mint_cells: Vec<(bigtable::RowKey, tx_by_addr::TransactionByAddr)>,
+    block_time_cells: Vec<(bigtable::RowKey, UnixTimestamp)>,
+    block_index_cells: Vec<(bigtable::RowKey, u64)>,
+    rewards_cells: Vec<(bigtable::RowKey, Vec<RewardCell>)>,
+    leader_index_cells: Vec<(bigtable::RowKey, Pubkey)>,
+    block_cell: (bigtable::RowKey, generated::ConfirmedBlock),
+}
+
+/// Add the transaction described by `info` to the rows of the token owners or mints in
+/// `addresses`, unless it is already listed there as one of the transaction's accounts.
+fn index_token_accounts(
+    addresses: BTreeSet<Pubkey>,
+    info: &TransactionByAddrInfo,
+    rows: &mut HashMap<Pubkey, Vec<TransactionByAddrInfo>>,
+) {
+    for address in addresses {
+        let infos = rows.entry(address).or_default();
+        if infos.last().map(|last| last.index) != Some(info.index) {
+            infos.push(info.clone());
+        }
+    }
+}
+
+/// `tx-by-addr` rows of the block at `slot`, one per address.
+fn tx_by_addr_cells(
+    slot: Slot,
+    by_addr: HashMap<Pubkey, Vec<TransactionByAddrInfo>>,
+) -> Vec<(bigtable::RowKey, tx_by_addr::TransactionByAddr)> {
+    by_addr
+        .into_iter()
+        .map(|(address, transaction_info_by_addr)| {
+            (
+                format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
+                tx_by_addr::TransactionByAddr {
+                    tx_by_addrs: transaction_info_by_addr
+                        .into_iter()
+                        .map(|by_addr| by_addr.into())
+                        .collect(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Write the encoded rows of several blocks to `table` together. The stats of each block share
+/// the latency of the whole write, which starts at `start` to include the encoding.
+async fn put_blocks_row_data(
+    conn: &bigtable::BigTableConnection,
+    table: &str,
+    blocks_row_data: Vec<Vec<(bigtable::RowKey, bigtable::RowData)>>,
+    start: Instant,
+) -> bigtable::Result<Vec<TableUploadStats>> {
+    let mut stats: Vec<_> = blocks_row_data
+        .iter()
+        .map(|row_data| TableUploadStats {
+            rows: row_data.len(),
+            bytes: bigtable::row_data_size(row_data),
+            latency: Duration::ZERO,
+        })
+        .collect();
+
+    let row_data: Vec<_> = blocks_row_data.into_iter().flatten().collect();
+    for row_data in row_data.chunks(ROWS_BATCH_SIZE) {
+        conn.put_row_data_with_retry(table, row_data).await?;
+    }
+
+    let latency = start.elapsed();
+    for stats in &mut stats {
+        stats.latency = latency;
+    }
+    Ok(stats)
+}
+
+/// Spawn the write of the bincode rows of several blocks to `table`.
+fn spawn_bincode_upload<T>(
+    conn: &bigtable::BigTableConnection,
+    table: &'static str,
+    cells: Vec<Vec<(bigtable::RowKey, T)>>,
+    use_cell_envelope: bool,
+) -> JoinHandle<bigtable::Result<(&'static str, Vec<TableUploadStats>)>>
+where
+    T: serde::Serialize + Send + 'static,
+{
+    let conn = conn.clone();
+    tokio::spawn(async move {
+        let start = Instant::now();
+        let row_data = cells
+            .iter()
+            .map(|cells| bigtable::encode_bincode_cells(cells, use_cell_envelope))
+            .collect::<bigtable::Result<Vec<_>>>()?;
+        put_blocks_row_data(&conn, table, row_data, start)
+            .await
+            .map(|stats| (table, stats))
+    })
+}
+
+#[async_trait]
+impl LedgerStorageAdapter for LedgerStorage {
+    /// Upload a new confirmed block and associated meta data.
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<UploadReport> {
+        trace!(
+            "LedgerStorage::upload_confirmed_block request received: {:?}",
+            slot
+        );
+        let mut reports = self
+            .upload_confirmed_blocks(vec![(slot, confirmed_block)])
+            .await?;
+        Ok(reports.remove(0))
+    }
+
+    /// Rows of all the blocks are written together, one table at a time. The blocks themselves
+    /// are only written once everything else was.
+    async fn upload_confirmed_blocks(
+        &self,
+        blocks: Vec<(Slot, VersionedConfirmedBlock)>,
+    ) -> Result<Vec<UploadReport>> {
+        let mut blocks_cells: Vec<_> = blocks
             .into_iter()
-            .map(|(address, transaction_info_by_addr)| {
-                (
-                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
-                    tx_by_addr::TransactionByAddr {
-                        tx_by_addrs: transaction_info_by_addr
-                            .into_iter()
-                            .map(|by_addr| by_addr.into())
-                            .collect(),
-                    },
-                )
-            })
+            .map(|(slot, confirmed_block)| self.block_cells(slot, confirmed_block))
             .collect();
 
         let mut tasks = vec![];
+        let use_cell_envelope = self.use_cell_envelope;
 
-        if !tx_cells.is_empty() {
+        let tx_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_cells))
+            .collect();
+        if tx_cells.iter().any(|cells| !cells.is_empty()) {
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_bincode_cells_with_retry::<TransactionInfo>("tx", &tx_cells)
+                let start = Instant::now();
+                let row_data = tx_cells
+                    .iter()
+                    .map(|cells| bigtable::encode_bincode_cells(cells, use_cell_envelope))
+                    .collect::<bigtable::Result<Vec<_>>>()?;
+                put_blocks_row_data(&conn, "tx", row_data, start)
                     .await
+                    .map(|stats| ("tx", stats))
             }));
         }
 
-        if !tx_by_addr_cells.is_empty() {
+        let tx_by_addr_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_addr_cells))
+            .collect();
+        let tx_by_owner_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_owner_cells))
+            .collect();
+        let tx_by_mint_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_mint_cells))
+            .collect();
+        for (table, cells) in [
+            ("tx-by-addr", tx_by_addr_cells),
+            (TX_BY_OWNER_TABLE_NAME, tx_by_owner_cells),
+            (TX_BY_MINT_TABLE_NAME, tx_by_mint_cells),
+        ] {
+            if cells.iter().all(|cells| cells.is_empty()) {
+                continue;
+            }
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
-                    "tx-by-addr",
-                    &tx_by_addr_cells,
-                )
-                .await
+                let start = Instant::now();
+                let row_data = cells
+                    .iter()
+                    .map(|cells| bigtable::encode_protobuf_cells(cells, use_cell_envelope))
+                    .collect::<bigtable::Result<Vec<_>>>()?;
+                put_blocks_row_data(&conn, table, row_data, start)
+                    .await
+                    .map(|stats| (table, stats))
             }));
         }
 
-        let mut _bytes_written = 0;
+        let block_time_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.block_time_cells))
+            .collect();
+        let block_index_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.block_index_cells))
+            .collect();
+        let rewards_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.rewards_cells))
+            .collect();
+        let leader_index_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.leader_index_cells))
+            .collect();
+        if block_time_cells.iter().any(|cells| !cells.is_empty()) {
+            tasks.push(spawn_bincode_upload(&self.connection, BLOCK_TIME_TABLE_NAME, block_time_cells, self.use_cell_envelope));
+        }
+        if block_index_cells.iter().any(|cells| !cells.is_empty()) {
+            tasks.push(spawn_bincode_upload(&self.connection, BLOCK_INDEX_TABLE_NAME, block_index_cells, self.use_cell_envelope));
+        }
+        if rewards_cells.iter().any(|cells| !cells.is_empty()) {
+            tasks.push(spawn_bincode_upload(&self.connection, REWARDS_TABLE_NAME, rewards_cells, self.use_cell_envelope));
+        }
+        if leader_index_cells.iter().any(|cells| !cells.is_empty()) {
+            tasks.push(spawn_bincode_upload(&self.connection, LEADER_INDEX_TABLE_NAME, leader_index_cells, self.use_cell_envelope));
+        }
+
+        let mut reports: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.report))
+            .collect();
         let mut maybe_first_err: Option<Error> = None;
 
         let results = futures::future::join_all(tasks).await;
@@ -240,8 +551,12 @@ impl LedgerStorageAdapter for LedgerStorage {
                         maybe_first_err = Some(Error::StorageBackendError(Box::new(err)));
                     }
                 }
-                Ok(Ok(bytes)) => {
-                    _bytes_written += bytes;
+                Ok(Ok((table, stats))) => {
+                    for (report, stats) in reports.iter_mut().zip(stats) {
+                        if stats.rows > 0 {
+                            report.tables.insert(table.to_string(), stats);
+                        }
+                    }
                 }
             }
         }
@@ -250,23 +565,150 @@ impl LedgerStorageAdapter for LedgerStorage {
             return Err(err);
         }
 
-        let _num_transactions = confirmed_block.transactions.len();
-
         // Store the block itself last, after all other metadata about the block has been
         // successfully stored.  This avoids partial uploaded blocks from becoming visible to
         // `get_confirmed_block()` and `get_confirmed_blocks()`
-        let blocks_cells = [(slot_to_blocks_key(slot, false), confirmed_block.into())];
-        _bytes_written += self
-            .connection
-            .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
-            .await?;
+        let start = Instant::now();
+        let row_data = blocks_cells
+            .iter()
+            .map(|block_cells| {
+                bigtable::encode_protobuf_cells(std::slice::from_ref(&block_cells.block_cell), self.use_cell_envelope)
+            })
+            .collect::<bigtable::Result<Vec<_>>>()?;
+        let stats = put_blocks_row_data(&self.connection, "blocks", row_data, start).await?;
+        for (report, stats) in reports.iter_mut().zip(stats) {
+            report.tables.insert("blocks".to_string(), stats);
+        }
         // datapoint_info!(
         //     "storage-bigtable-upload-block",
         //     ("slot", slot, i64),
         //     ("transactions", num_transactions, i64),
         //     ("bytes", bytes_written, i64),
         // );
-        Ok(())
+        Ok(reports)
+    }
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<DeleteReport> {
+        let block_key = slot_to_blocks_key(slot, false);
+        let block_rows = self
+            .connection
+            .get_multi_row_data_with_retry("blocks", &[block_key.clone()])
+            .await?;
+        let Some((_, block_row)) = block_rows.first() else {
+            return Err(Error::BlockNotFound(slot));
+        };
+        let block: ConfirmedBlock = bigtable::deserialize_protobuf_cell_data::<generated::ConfirmedBlock>(
+            block_row,
+            "blocks",
+            block_key.clone(),
+        )?
+        .try_into()
+        .map_err(|_err| bigtable::Error::ObjectCorrupt(format!("blocks/{block_key}")))?;
+
+        let row_keys = block_row_keys(slot, self.slots_per_epoch, &block);
+
+        // Keep the `tx` rows of transactions that also landed in another slot
+        let mut tx_rows = vec![];
+        let mut other_slot_tx_rows = vec![];
+        for signatures in row_keys.signatures.chunks(ROWS_BATCH_SIZE) {
+            for (row_key, row_data) in self.connection.get_multi_row_data_with_retry("tx", signatures).await? {
+                match bigtable::deserialize_bincode_cell_data::<TransactionInfo>(&row_data, "tx", row_key.clone()) {
+                    Ok(tx_info) if tx_info.slot == slot => tx_rows.push(row_key),
+                    Ok(_) => other_slot_tx_rows.push(row_key),
+                    Err(err) => {
+                        warn!("Keeping tx/{}, its slot is unknown: {}", row_key, err);
+                        other_slot_tx_rows.push(row_key);
+                    }
+                }
+            }
+        }
+
+        let mut tx_by_addr_rows = vec![];
+        for row_keys in row_keys.tx_by_addr.chunks(ROWS_BATCH_SIZE) {
+            tx_by_addr_rows.extend(
+                self.connection
+                    .get_multi_row_data_with_retry("tx-by-addr", row_keys)
+                    .await?
+                    .into_iter()
+                    .map(|(row_key, _)| row_key),
+            );
+        }
+
+        let mut rows = BTreeMap::new();
+        rows.insert("tx".to_string(), tx_rows);
+        rows.insert("tx-by-addr".to_string(), tx_by_addr_rows);
+
+        for (enabled, table, row_keys) in [
+            (self.enable_block_time_index, BLOCK_TIME_TABLE_NAME, &row_keys.block_time),
+            (self.enable_block_index, BLOCK_INDEX_TABLE_NAME, &row_keys.block_index),
+            (self.enable_rewards_index, REWARDS_TABLE_NAME, &row_keys.rewards),
+            (self.enable_leader_index, LEADER_INDEX_TABLE_NAME, &row_keys.leader_index),
+        ] {
+            if !enabled {
+                continue;
+            }
+            // An epoch boundary block can pay many rewards
+            let mut index_rows = vec![];
+            for row_keys in row_keys.chunks(ROWS_BATCH_SIZE) {
+                index_rows.extend(
+                    self.connection
+                        .get_multi_row_data_with_retry(table, row_keys)
+                        .await?
+                        .into_iter()
+                        .map(|(row_key, _)| row_key),
+                );
+            }
+            rows.insert(table.to_string(), index_rows);
+        }
+
+        for (enabled, table, row_keys) in [
+            (self.index_token_owners, TX_BY_OWNER_TABLE_NAME, &row_keys.tx_by_owner),
+            (self.index_token_mints, TX_BY_MINT_TABLE_NAME, &row_keys.tx_by_mint),
+        ] {
+            if !enabled {
+                continue;
+            }
+            let table = if self.token_index_in_tx_by_addr { "tx-by-addr" } else { table };
+            let table_rows: &mut Vec<String> = rows.entry(table.to_string()).or_default();
+            for row_keys in row_keys.chunks(ROWS_BATCH_SIZE) {
+                table_rows.extend(
+                    self.connection
+                        .get_multi_row_data_with_retry(table, row_keys)
+                        .await?
+                        .into_iter()
+                        .map(|(row_key, _)| row_key),
+                );
+            }
+            // The token index may share the tx-by-addr table
+            table_rows.sort();
+            table_rows.dedup();
+        }
+
+        if !dry_run {
+            // Delete the block last, so that a failed deletion can be retried.
+            for (table, row_keys) in &rows {
+                for row_keys in row_keys.chunks(ROWS_BATCH_SIZE) {
+                    self.connection.delete_rows_with_retry(table, row_keys).await?;
+                }
+            }
+            self.connection
+                .delete_rows_with_retry("blocks", &[block_key.clone()])
+                .await?;
+        }
+        rows.insert("blocks".to_string(), vec![block_key]);
+
+        let mut kept_rows = BTreeMap::new();
+        if !other_slot_tx_rows.is_empty() {
+            kept_rows.insert("tx".to_string(), other_slot_tx_rows);
+        }
+
+        Ok(DeleteReport {
+            dry_run,
+            slots: vec![slot],
+            missing_slots: vec![],
+            rows,
+            kept_rows,
+        })
     }
 
     fn should_include_in_tx_full(&self, address: &Pubkey) -> bool {
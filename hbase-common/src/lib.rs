@@ -0,0 +1,2 @@
+pub mod thrift_config;
+pub mod transport;
@@ -0,0 +1,104 @@
+use {
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThriftTransport {
+    /// Plain buffered transport, the default of `hbase thrift start`.
+    Buffered,
+    /// Length-prefixed frames, required when the server runs with `-framed` or `-nonblocking`.
+    Framed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThriftProtocol {
+    Binary,
+    /// Required when the server runs with `-compact`.
+    Compact,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThriftTlsConfig {
+    /// PEM file with the CA certificates used to verify the server. System roots are used if unset.
+    pub ca_cert_path: Option<String>,
+    /// PEM client certificate chain for mutual TLS.
+    pub client_cert_path: Option<String>,
+    /// PEM private key matching `client_cert_path`.
+    pub client_key_path: Option<String>,
+    /// Name used for SNI and certificate verification. Defaults to the host part of the address.
+    pub server_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThriftConfig {
+    pub transport: ThriftTransport,
+    pub protocol: ThriftProtocol,
+    pub tls: Option<ThriftTlsConfig>,
+}
+
+impl Default for ThriftConfig {
+    fn default() -> Self {
+        Self {
+            transport: ThriftTransport::Buffered,
+            protocol: ThriftProtocol::Binary,
+            tls: None,
+        }
+    }
+}
+
+impl fmt::Display for ThriftConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transport: {}, protocol: {}, tls: {}",
+            self.transport,
+            self.protocol,
+            self.tls.is_some()
+        )
+    }
+}
+
+impl fmt::Display for ThriftTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThriftTransport::Buffered => write!(f, "buffered"),
+            ThriftTransport::Framed => write!(f, "framed"),
+        }
+    }
+}
+
+impl FromStr for ThriftTransport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "buffered" => Ok(ThriftTransport::Buffered),
+            "framed" => Ok(ThriftTransport::Framed),
+            _ => Err(format!("unknown thrift transport: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for ThriftProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThriftProtocol::Binary => write!(f, "binary"),
+            ThriftProtocol::Compact => write!(f, "compact"),
+        }
+    }
+}
+
+impl FromStr for ThriftProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary" => Ok(ThriftProtocol::Binary),
+            "compact" => Ok(ThriftProtocol::Compact),
+            _ => Err(format!("unknown thrift protocol: {s}")),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use {
+    crate::thrift_config::{ThriftConfig, ThriftProtocol, ThriftTlsConfig, ThriftTransport},
+    openssl::ssl::{SslConnector, SslFiletype, SslMethod, SslStream},
+    std::{
+        io::{self, Read, Write},
+        net::{TcpStream, ToSocketAddrs},
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+    thrift::{
+        protocol::{
+            TBinaryInputProtocolFactory, TBinaryOutputProtocolFactory,
+            TCompactInputProtocolFactory, TCompactOutputProtocolFactory,
+            TInputProtocol, TInputProtocolFactory,
+            TOutputProtocol, TOutputProtocolFactory,
+        },
+        transport::{
+            ReadHalf, WriteHalf,
+            TBufferedReadTransportFactory, TBufferedWriteTransportFactory,
+            TFramedReadTransportFactory, TFramedWriteTransportFactory,
+            TIoChannel, TReadTransportFactory, TTcpChannel, TWriteTransportFactory,
+        },
+        ProtocolErrorKind,
+    },
+    thiserror::Error,
+};
+
+/// Errors opening a connection, converted by each backend into its own error type.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Thrift: {0}")]
+    Thrift(#[from] thrift::Error),
+
+    #[error("TLS: {0}")]
+    Tls(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub type InputProtocol = Box<dyn TInputProtocol + Send>;
+pub type OutputProtocol = Box<dyn TOutputProtocol + Send>;
+
+/// TLS stream shared by the read and write halves of a Thrift channel.
+///
+/// The sync client never reads and writes at the same time, so a mutex is enough to share it.
+#[derive(Clone)]
+struct TlsChannel {
+    stream: Arc<Mutex<SslStream<TcpStream>>>,
+}
+
+impl TlsChannel {
+    fn lock(&self) -> io::Result<std::sync::MutexGuard<'_, SslStream<TcpStream>>> {
+        self.stream
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "TLS stream lock poisoned"))
+    }
+}
+
+impl Read for TlsChannel {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.lock()?.read(buf)
+    }
+}
+
+impl Write for TlsChannel {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock()?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock()?.flush()
+    }
+}
+
+impl TIoChannel for TlsChannel {
+    fn split(self) -> thrift::Result<(ReadHalf<Self>, WriteHalf<Self>)> {
+        Ok((ReadHalf::new(self.clone()), WriteHalf::new(self)))
+    }
+}
+
+/// Open a connection to the Thrift server at `address` and wrap it in the transport and
+/// protocol selected by `config`.
+pub fn open_protocols(
+    address: &str,
+    timeout: Option<Duration>,
+    config: &ThriftConfig,
+) -> Result<(InputProtocol, OutputProtocol)> {
+    let stream = match timeout {
+        Some(timeout) => {
+            let socket_addr = address
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("could not resolve {address}"),
+                ))?;
+            TcpStream::connect_timeout(&socket_addr, timeout)?
+        }
+        None => TcpStream::connect(address)?,
+    };
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)?;
+
+    match &config.tls {
+        None => {
+            let (input_chan, output_chan) = TTcpChannel::with_stream(stream).split()?;
+            Ok(wrap_channel(config, Box::new(input_chan), Box::new(output_chan)))
+        }
+        Some(tls_config) => {
+            let (input_chan, output_chan) = connect_tls(address, stream, tls_config)?.split()?;
+            Ok(wrap_channel(config, Box::new(input_chan), Box::new(output_chan)))
+        }
+    }
+}
+
+fn wrap_channel(
+    config: &ThriftConfig,
+    input_chan: Box<dyn Read + Send>,
+    output_chan: Box<dyn Write + Send>,
+) -> (InputProtocol, OutputProtocol) {
+    let (input_transport, output_transport) = match config.transport {
+        ThriftTransport::Buffered => (
+            TBufferedReadTransportFactory::new().create(input_chan),
+            TBufferedWriteTransportFactory::new().create(output_chan),
+        ),
+        ThriftTransport::Framed => (
+            TFramedReadTransportFactory::new().create(input_chan),
+            TFramedWriteTransportFactory::new().create(output_chan),
+        ),
+    };
+
+    match config.protocol {
+        ThriftProtocol::Binary => (
+            TBinaryInputProtocolFactory::new().create(input_transport),
+            TBinaryOutputProtocolFactory::new().create(output_transport),
+        ),
+        ThriftProtocol::Compact => (
+            TCompactInputProtocolFactory::new().create(input_transport),
+            TCompactOutputProtocolFactory::new().create(output_transport),
+        ),
+    }
+}
+
+fn connect_tls(
+    address: &str,
+    stream: TcpStream,
+    tls_config: &ThriftTlsConfig,
+) -> Result<TlsChannel> {
+    let tls_error = |err: openssl::error::ErrorStack| Error::Tls(err.to_string());
+
+    let mut builder = SslConnector::builder(SslMethod::tls()).map_err(tls_error)?;
+    if let Some(ca_cert_path) = &tls_config.ca_cert_path {
+        builder.set_ca_file(ca_cert_path).map_err(tls_error)?;
+    }
+    if let Some(client_cert_path) = &tls_config.client_cert_path {
+        builder.set_certificate_chain_file(client_cert_path).map_err(tls_error)?;
+    }
+    if let Some(client_key_path) = &tls_config.client_key_path {
+        builder
+            .set_private_key_file(client_key_path, SslFiletype::PEM)
+            .map_err(tls_error)?;
+        builder.check_private_key().map_err(tls_error)?;
+    }
+
+    let server_name = tls_config
+        .server_name
+        .clone()
+        .unwrap_or_else(|| host_from_address(address).to_string());
+
+    let stream = builder
+        .build()
+        .connect(&server_name, stream)
+        .map_err(|err| Error::Tls(format!("handshake with {address} failed: {err}")))?;
+
+    Ok(TlsChannel {
+        stream: Arc::new(Mutex::new(stream)),
+    })
+}
+
+fn host_from_address(address: &str) -> &str {
+    let host = address
+        .rsplit_once(':')
+        .map(|(host, _port)| host)
+        .unwrap_or(address);
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// Errors that prove the transport or protocol settings do not match the server: the server
+/// answered with bytes that do not decode as a message of the expected protocol. Each request
+/// opens its own connection, so these come from the first call on it.
+///
+/// A closed connection or a bad frame size can also come from a server restart or an idle
+/// connection being dropped, so they are not taken as a mismatch and stay retryable.
+pub fn is_protocol_mismatch(err: &thrift::Error) -> bool {
+    match err {
+        thrift::Error::Protocol(err) => matches!(
+            err.kind,
+            ProtocolErrorKind::BadVersion | ProtocolErrorKind::InvalidData
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, thrift::TransportErrorKind};
+
+    #[test]
+    fn test_host_from_address() {
+        assert_eq!(host_from_address("hbase-thrift:9090"), "hbase-thrift");
+        assert_eq!(host_from_address("[::1]:9090"), "::1");
+        assert_eq!(host_from_address("hbase-thrift"), "hbase-thrift");
+    }
+
+    #[test]
+    fn test_is_protocol_mismatch() {
+        let bad_version = thrift::Error::Protocol(thrift::ProtocolError::new(
+            ProtocolErrorKind::BadVersion,
+            "bad version",
+        ));
+        assert!(is_protocol_mismatch(&bad_version));
+
+        let eof = thrift::Error::Transport(thrift::TransportError::new(
+            TransportErrorKind::EndOfFile,
+            "eof",
+        ));
+        assert!(!is_protocol_mismatch(&eof));
+
+        let size_limit = thrift::Error::Protocol(thrift::ProtocolError::new(
+            ProtocolErrorKind::SizeLimit,
+            "size limit",
+        ));
+        assert!(!is_protocol_mismatch(&size_limit));
+
+        let timed_out = thrift::Error::Transport(thrift::TransportError::new(
+            TransportErrorKind::TimedOut,
+            "timed out",
+        ));
+        assert!(!is_protocol_mismatch(&timed_out));
+    }
+}
@@ -2,6 +2,7 @@ use {
     crate::{
         hbase::{HBase, Result},
     },
+    solana_hbase_common::{thrift_config::ThriftConfig, transport::open_protocols},
     log::*,
     std::{
         time::{
@@ -11,58 +12,46 @@ use {
     hbase_thrift::hbase::{
         HbaseSyncClient,
     },
-    thrift::{
-        protocol::{
-            TBinaryInputProtocol, TBinaryOutputProtocol,
-        },
-        transport::{TBufferedReadTransport, TBufferedWriteTransport, TIoChannel, TTcpChannel},
-    },
 };
 
 #[derive(Clone, Debug)]
 pub struct HBaseConnection {
     address: String,
-    // timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    thrift_config: ThriftConfig,
 }
 
 impl HBaseConnection {
     pub async fn new(
         address: &str,
         _read_only: bool,
-        _timeout: Option<Duration>,
+        timeout: Option<Duration>,
+        thrift_config: ThriftConfig,
     ) -> Result<Self> {
-        debug!("Creating HBase connection instance");
+        debug!("Creating HBase connection instance [{}]", thrift_config);
 
         Ok(Self {
             address: address.to_string(),
-            // timeout,
+            timeout,
+            thrift_config,
         })
     }
 
-    pub fn client(&self) -> HBase {
-        let mut channel = TTcpChannel::new();
-
-        channel.open(self.address.clone()).unwrap();
-
-        let (input_chan, output_chan) = channel.split().unwrap();
-
-        let input_prot = TBinaryInputProtocol::new(
-            TBufferedReadTransport::new(input_chan),
-            true
-        );
-        let output_prot = TBinaryOutputProtocol::new(
-            TBufferedWriteTransport::new(output_chan),
-            true
-        );
+    pub fn client(&self) -> Result<HBase> {
+        let (input_prot, output_prot) = open_protocols(
+            &self.address,
+            self.timeout,
+            &self.thrift_config,
+        )?;
 
         let client = HbaseSyncClient::new(
             input_prot,
             output_prot
         );
 
-        HBase {
+        Ok(HBase {
             client,
             // timeout: self.timeout,
-        }
+        })
     }
 }
\ No newline at end of file
@@ -4,7 +4,7 @@ use {
         hbase::*,
     },
     solana_storage_utils::{
-        compression::{decompress},
+        cell_envelope::{unwrap_cell, CellError, PayloadEncoding},
     },
     log::*,
 };
@@ -46,7 +46,7 @@ where
         .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
         .1;
 
-    let data = decompress(value)?;
+    let data = unwrap_cell_data(value, PayloadEncoding::Protobuf, table, &key)?;
     T::decode(&data[..]).map_err(|err| {
         warn!("Failed to deserialize {}/{}: {}", table, key, err);
         Error::ObjectCorrupt(format!("{table}/{key}"))
@@ -67,9 +67,33 @@ where
         .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
         .1;
 
-    let data = decompress(value)?;
+    let data = unwrap_cell_data(value, PayloadEncoding::Bincode, table, &key)?;
     bincode::deserialize(&data).map_err(|err| {
         warn!("Failed to deserialize {}/{}: {}", table, key, err);
         Error::ObjectCorrupt(format!("{table}/{key}"))
     })
-}
\ No newline at end of file
+}
+
+fn unwrap_cell_data(
+    value: &[u8],
+    expected_encoding: PayloadEncoding,
+    table: &str,
+    key: &str,
+) -> Result<Vec<u8>> {
+    match unwrap_cell(value) {
+        Ok((Some(encoding), _)) if encoding != expected_encoding => {
+            warn!("Unexpected payload encoding {:?} for {}/{}", encoding, table, key);
+            Err(Error::ObjectCorrupt(format!("{table}/{key}")))
+        }
+        Ok((_, data)) => Ok(data),
+        Err(CellError::Io(err)) => Err(Error::Io(err)),
+        Err(err @ CellError::ChecksumMismatch { .. }) => {
+            warn!("Failed to verify {}/{}: {}", table, key, err);
+            Err(Error::ChecksumMismatch(format!("{table}/{key}")))
+        }
+        Err(err) => {
+            warn!("Failed to unwrap {}/{}: {}", table, key, err);
+            Err(Error::ObjectCorrupt(format!("{table}/{key}")))
+        }
+    }
+}
@@ -6,19 +6,11 @@ use {
         },
         hbase_error::Error,
     },
+    solana_hbase_common::transport::{InputProtocol, OutputProtocol},
     log::*,
     hbase_thrift::hbase::{
         HbaseSyncClient, THbaseSyncClient, TScan
     },
-    thrift::{
-        protocol::{
-            TBinaryInputProtocol, TBinaryOutputProtocol,
-        },
-        transport::{
-            TBufferedReadTransport, TBufferedWriteTransport,
-            TTcpChannel
-        },
-    },
     std::collections::BTreeMap,
     std::convert::TryInto,
 };
@@ -38,9 +30,6 @@ pub enum CellData<B, P> {
 }
 
 
-type InputProtocol = TBinaryInputProtocol<TBufferedReadTransport<thrift::transport::ReadHalf<TTcpChannel>>>;
-type OutputProtocol = TBinaryOutputProtocol<TBufferedWriteTransport<thrift::transport::WriteHalf<TTcpChannel>>>;
-
 pub struct HBase {
     pub client: HbaseSyncClient<InputProtocol, OutputProtocol>,
     // timeout: Option<Duration>,
@@ -1,4 +1,5 @@
 use {
+    solana_hbase_common::transport::{self, is_protocol_mismatch},
     log::*,
     thiserror::Error,
 };
@@ -24,11 +25,20 @@ pub enum Error {
     #[error("Object is corrupt: {0}")]
     ObjectCorrupt(String),
 
+    #[error("Object checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+
     #[error("Timeout")]
     Timeout,
 
     #[error("Thrift")]
     Thrift(thrift::Error),
+
+    #[error("Thrift protocol mismatch: {0}; check that the transport (buffered/framed), protocol (binary/compact) and TLS settings match the HBase Thrift server")]
+    ProtocolMismatch(String),
+
+    #[error("TLS: {0}")]
+    Tls(String),
 }
 
 impl From<std::io::Error> for Error {
@@ -39,6 +49,20 @@ impl From<std::io::Error> for Error {
 
 impl From<thrift::Error> for Error {
     fn from(err: thrift::Error) -> Self {
-        Self::Thrift(err)
+        if is_protocol_mismatch(&err) {
+            Self::ProtocolMismatch(err.to_string())
+        } else {
+            Self::Thrift(err)
+        }
     }
-}
\ No newline at end of file
+}
+
+impl From<transport::Error> for Error {
+    fn from(err: transport::Error) -> Self {
+        match err {
+            transport::Error::Io(err) => err.into(),
+            transport::Error::Thrift(err) => err.into(),
+            transport::Error::Tls(err) => Self::Tls(err),
+        }
+    }
+}
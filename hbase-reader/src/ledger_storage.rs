@@ -2,7 +2,10 @@
 
 use {
     crate::{
-        deserializer::deserialize_protobuf_or_bincode_cell_data,
+        deserializer::{
+            deserialize_bincode_cell_data,
+            deserialize_protobuf_or_bincode_cell_data,
+        },
         tx_utils::{
             calculate_epoch,
             determine_transaction_type,
@@ -11,6 +14,7 @@ use {
             get_cached_transaction,
         },
         storage_config::LedgerStorageConfig,
+        table_names::TableNames,
         hbase_error,
         connection,
         hbase,
@@ -22,7 +26,9 @@ use {
     //-------------------------
     // solana_metrics::{datapoint_info, inc_new_counter_debug},
     solana_clock::{
+        Epoch,
         Slot,
+        UnixTimestamp,
     },
     solana_pubkey::{
         Pubkey,
@@ -37,6 +43,7 @@ use {
     solana_transaction_status::{
         ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
         ConfirmedTransactionWithStatusMeta,
+        Reward,
         TransactionByAddrInfo,
     },
     solana_transaction_status_client_types::{
@@ -49,10 +56,64 @@ use {
         LegacyTransactionByAddrInfo,
     },
     solana_storage_utils::{
+        balance_history::{
+            BalanceChange,
+            BalanceHistoryEntry,
+        },
+        token_balance_history::{
+            TokenBalanceChange,
+            TokenBalanceHistoryEntry,
+        },
+        block_index::{
+            block_height_to_key,
+            blockhash_to_key,
+            slot_to_block_height_key,
+        },
+        block_stats::{
+            BlockStats,
+        },
+        block_time::{
+            block_time_prefix,
+            block_time_prefix_end,
+            key_to_block_time,
+            slot_to_block_time_key,
+        },
+        hot_accounts::{
+            key_to_suppression_slot,
+            suppression_periods,
+            SuppressionChange,
+            SuppressionPeriod,
+        },
+        memo_index::{
+            memo_key,
+            memo_key_end,
+            memo_prefix_key,
+            memo_prefix_key_end,
+            normalize_memo,
+            row_key_to_memo,
+            MemoMatch,
+        },
+        leader_index::{
+            key_to_leader_slot,
+            leader_slot_key,
+            leader_slot_key_end,
+            slot_to_leader_key,
+        },
+        rewards::{
+            is_inflation_reward,
+            key_to_rewards,
+            rewards_epoch_prefix,
+            RewardCell,
+        },
         tx_info::TransactionInfo,
+        zstd_dictionary::load_dictionary,
         slot_to_blocks_key,
+        slot_to_key,
         slot_to_tx_by_addr_key,
         key_to_slot,
+        tx_by_addr_key,
+        tx_by_addr_salt_prefix,
+        MAX_TX_BY_ADDR_SALT_BUCKETS,
     },
     std::{
         convert::{TryInto},
@@ -75,12 +136,21 @@ impl From<hbase_error::Error> for Error {
     }
 }
 
+/// Rows read per request when scanning the rewards of an address.
+const REWARDS_ROWS_BATCH_SIZE: i64 = 1000;
+
+/// Rows read per request when scanning the suppression changes of an address.
+const SUPPRESSION_ROWS_BATCH_SIZE: i64 = 1000;
+
 #[derive(Clone)]
 pub struct LedgerStorage {
     connection: connection::HBaseConnection,
     use_md5_row_key_salt: bool,
     cache_client: Option<MemcacheClient>,
     disable_tx_fallback: bool,
+    check_suppressed_addresses: bool,
+    tx_by_addr_salt_buckets: Option<u16>,
+    table_names: TableNames,
     // TODO: Implement metrics
     // metrics: Arc<Metrics>,
     //----------------------
@@ -122,12 +192,22 @@ impl LedgerStorage {
             use_md5_row_key_salt,
             enable_full_tx_cache,
             disable_tx_fallback,
+            check_suppressed_addresses,
+            tx_by_addr_salt_buckets,
             cache_address,
+            thrift_config,
+            zstd_dictionaries,
+            table_names,
         } = config;
+        for path in &zstd_dictionaries {
+            let id = load_dictionary(path)?;
+            info!("Loaded zstd dictionary {} from {}", id, path);
+        }
         let connection = connection::HBaseConnection::new(
             address.as_str(),
             read_only,
             timeout,
+            thrift_config,
         )
             .await?;
 
@@ -137,33 +217,709 @@ impl LedgerStorage {
 
                 let cache_addr_clone = cache_addr.clone();
 
-                match task::spawn_blocking(move || MemcacheClient::connect(cache_addr_clone.as_str())).await {
-                    Ok(Ok(client)) => Some(client),
-                    Ok(Err(e)) => {
-                        error!("Failed to connect to cache server at {}: {}", cache_addr, e);
-                        None
+                match task::spawn_blocking(move || MemcacheClient::connect(cache_addr_clone.as_str())).await {
+                    Ok(Ok(client)) => Some(client),
+                    Ok(Err(e)) => {
+                        error!("Failed to connect to cache server at {}: {}", cache_addr, e);
+                        None
+                    },
+                    Err(e) => {
+                        error!("Tokio task join error while connecting to cache server: {}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            connection,
+            use_md5_row_key_salt,
+            cache_client,
+            disable_tx_fallback,
+            check_suppressed_addresses,
+            tx_by_addr_salt_buckets,
+            table_names,
+            // TODO: Implement metrics
+            // metrics,
+            //------------------------
+        })
+    }
+
+    /// Table whose row keys are the slots of the stored blocks, see `TableNames::slots`.
+    fn slots_table_name(&self) -> &str {
+        self.table_names.slots(self.use_md5_row_key_salt)
+    }
+
+    /// Value of a `block-index` row, see `solana_storage_utils::block_index`.
+    async fn get_block_index_cell(&self, row_key: String) -> Result<Option<u64>> {
+        let mut hbase = self.connection.client()?;
+        match hbase.get_bincode_cell::<u64>(&self.table_names.block_index, row_key).await {
+            Ok(value) => Ok(Some(value)),
+            Err(hbase_error::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Signatures of the transactions that changed the balance of a token account owned by
+    /// `owner`, newest first. Paginated like `get_confirmed_signatures_for_address`.
+    ///
+    /// Only available if the writer indexed token owners in their own table, otherwise they are
+    /// listed by `get_confirmed_signatures_for_address`.
+    pub async fn get_confirmed_signatures_for_owner(
+        &self,
+        owner: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        let signatures = self
+            .get_confirmed_signatures_for_key(
+                &self.table_names.tx_by_owner,
+                owner,
+                before_signature,
+                until_signature,
+                limit,
+                None,
+            )
+            .await?;
+        self.check_address_suppressions(owner, before_signature, until_signature, limit, signatures)
+            .await
+    }
+
+    /// Signatures of the transactions that changed a token balance of `mint`, newest first.
+    /// Paginated like `get_confirmed_signatures_for_address`.
+    pub async fn get_confirmed_signatures_for_mint(
+        &self,
+        mint: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        let signatures = self
+            .get_confirmed_signatures_for_key(
+                &self.table_names.tx_by_mint,
+                mint,
+                before_signature,
+                until_signature,
+                limit,
+                None,
+            )
+            .await?;
+        self.check_address_suppressions(mint, before_signature, until_signature, limit, signatures)
+            .await
+    }
+
+    /// Signatures of the transactions that invoked `program`, directly or through CPI, newest
+    /// first. Paginated like `get_confirmed_signatures_for_address`.
+    pub async fn get_confirmed_signatures_for_program(
+        &self,
+        program: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        self.get_confirmed_signatures_for_key(
+            &self.table_names.tx_by_program,
+            program,
+            before_signature,
+            until_signature,
+            limit,
+            None,
+        )
+            .await
+    }
+
+    /// Stats of up to `limit` blocks between `start_slot` and `end_slot` inclusive, oldest
+    /// first.
+    ///
+    /// Only available if the writer recorded block stats.
+    pub async fn get_block_stats(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<(Slot, BlockStats)>> {
+        if start_slot > end_slot {
+            return Ok(vec![]);
+        }
+        let mut hbase = self.connection.client()?;
+        let row_data = hbase
+            .get_row_data(
+                &self.table_names.block_stats,
+                Some(slot_to_key(start_slot)),
+                Some(slot_to_key(end_slot.saturating_add(1))),
+                limit as i64,
+            )
+            .await?;
+
+        row_data
+            .into_iter()
+            .map(|(row_key, data)| {
+                let slot = key_to_slot(&row_key).ok_or_else(|| {
+                    hbase_error::Error::ObjectCorrupt(format!("{}/{row_key}", self.table_names.block_stats))
+                })?;
+                let block_stats = deserialize_bincode_cell_data(&data, &self.table_names.block_stats, row_key)?;
+                Ok((slot, block_stats))
+            })
+            .collect()
+    }
+
+    /// Up to `limit` transactions with a memo equal to `memo` once both are normalized, newest
+    /// first.
+    ///
+    /// Only available if the writer indexed memos.
+    pub async fn get_signatures_for_memo(&self, memo: &str, limit: usize) -> Result<Vec<MemoMatch>> {
+        let memo = normalize_memo(memo);
+        self.get_memo_matches(memo_key(&memo), memo_key_end(&memo), limit)
+            .await
+    }
+
+    /// Up to `limit` transactions with a memo starting with `prefix` once both are normalized,
+    /// ordered by memo then newest first.
+    pub async fn get_signatures_for_memo_prefix(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<MemoMatch>> {
+        let prefix = normalize_memo(prefix);
+        self.get_memo_matches(memo_prefix_key(&prefix), memo_prefix_key_end(&prefix), limit)
+            .await
+    }
+
+    async fn get_memo_matches(
+        &self,
+        start_key: String,
+        end_key: String,
+        limit: usize,
+    ) -> Result<Vec<MemoMatch>> {
+        let mut hbase = self.connection.client()?;
+        let row_data = hbase
+            .get_row_data(&self.table_names.memo_index, Some(start_key), Some(end_key), limit as i64)
+            .await?;
+
+        row_data
+            .into_iter()
+            .map(|(row_key, data)| {
+                let (memo, slot, index) = row_key_to_memo(&row_key).ok_or_else(|| {
+                    hbase_error::Error::ObjectCorrupt(format!("{}/{row_key}", self.table_names.memo_index))
+                })?;
+                let signature = deserialize_bincode_cell_data(&data, &self.table_names.memo_index, row_key)?;
+                Ok(MemoMatch {
+                    memo,
+                    slot,
+                    index,
+                    signature,
+                })
+            })
+            .collect()
+    }
+
+    /// Lamport balance changes of `address` between `start_slot` and `end_slot` inclusive, newest
+    /// first, at most `limit` of them.
+    ///
+    /// Only available if the writer recorded the balance history.
+    pub async fn get_balance_history(
+        &self,
+        address: &Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<BalanceHistoryEntry>> {
+        Ok(self
+            .get_history(&self.table_names.balance_history, address, start_slot, end_slot, limit, |_: &BalanceChange| true)
+            .await?
+            .into_iter()
+            .map(|(slot, balance_change)| BalanceHistoryEntry {
+                slot,
+                index: balance_change.index,
+                delta: balance_change.delta,
+                post_balance: balance_change.post_balance,
+            })
+            .collect())
+    }
+
+    /// Lamport balance changes of `address` in the blocks produced between `start_time` and
+    /// `end_time` inclusive, newest first. Requires the block time index.
+    pub async fn get_balance_history_in_time_range(
+        &self,
+        address: &Pubkey,
+        start_time: UnixTimestamp,
+        end_time: UnixTimestamp,
+        limit: usize,
+    ) -> Result<Vec<BalanceHistoryEntry>> {
+        let end_slot = match self.get_slot_at_time(end_time).await? {
+            Some(end_slot) => end_slot,
+            None => return Ok(vec![]),
+        };
+        let start_slot = self
+            .get_slot_at_time(start_time.saturating_sub(1))
+            .await?
+            .map(|slot| slot + 1)
+            .unwrap_or(0);
+
+        self.get_balance_history(address, start_slot, end_slot, limit)
+            .await
+    }
+
+    /// Token balance changes of `owner` between `start_slot` and `end_slot` inclusive, newest
+    /// first, at most `limit` of them. If `mint` is given, only the changes of that mint.
+    ///
+    /// Only available if the writer recorded the token balance history.
+    pub async fn get_token_balance_history(
+        &self,
+        owner: &Pubkey,
+        mint: Option<&Pubkey>,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<TokenBalanceHistoryEntry>> {
+        Ok(self
+            .get_history(
+                &self.table_names.token_balance_history,
+                owner,
+                start_slot,
+                end_slot,
+                limit,
+                |change: &TokenBalanceChange| mint.is_none_or(|mint| change.mint == *mint),
+            )
+            .await?
+            .into_iter()
+            .map(|(slot, change)| TokenBalanceHistoryEntry { slot, change })
+            .collect())
+    }
+
+    /// Periods over which the transactions of `address` are missing from `tx-by-addr`, because
+    /// the writer's hot account policy suppressed it, oldest first.
+    pub async fn get_address_suppressions(&self, address: &Pubkey) -> Result<Vec<SuppressionPeriod>> {
+        let mut hbase = self.connection.client()?;
+        // Row keys of an address have the same length, so the suffix resumes the scan after a
+        // row.
+        let mut start_key = format!("{address}/");
+        let end_key = format!("{address}0");
+
+        let mut changes = vec![];
+        loop {
+            let row_data = hbase
+                .get_row_data(
+                    &self.table_names.suppressed_addresses,
+                    Some(start_key.clone()),
+                    Some(end_key.clone()),
+                    SUPPRESSION_ROWS_BATCH_SIZE,
+                )
+                .await?;
+            let is_last_batch = row_data.len() < SUPPRESSION_ROWS_BATCH_SIZE as usize;
+
+            for (row_key, data) in row_data {
+                let slot = key_to_suppression_slot(&row_key).ok_or_else(|| {
+                    hbase_error::Error::ObjectCorrupt(format!("{}/{row_key}", self.table_names.suppressed_addresses))
+                })?;
+                start_key = format!("{row_key}0");
+                let change: SuppressionChange =
+                    deserialize_bincode_cell_data(&data, &self.table_names.suppressed_addresses, row_key)?;
+                changes.push((slot, change));
+            }
+
+            if is_last_batch {
+                return Ok(suppression_periods(changes));
+            }
+        }
+    }
+
+    /// Transaction from `table`, which is laid out like `tx_full`, e.g. a table the writer routes
+    /// some transactions to.
+    pub async fn get_full_transaction_from_table(
+        &self,
+        table: &str,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        let mut hbase = self.connection.client()?;
+
+        let tx_cell_data = hbase
+            .get_protobuf_or_bincode_cell::<StoredConfirmedTransactionWithStatusMeta, generated::ConfirmedTransactionWithStatusMeta>(
+                table,
+                signature.to_string(),
+            )
+            .await
+            .map_err(|err| match err {
+                hbase_error::Error::RowNotFound => Error::SignatureNotFound,
+                _ => err.into(),
+            })?;
+
+        Ok(match tx_cell_data {
+            hbase::CellData::Bincode(tx) => Some(tx.into()),
+            hbase::CellData::Protobuf(tx) => Some(tx.try_into().map_err(|_err| {
+                error!("Protobuf object is corrupted");
+                hbase_error::Error::ObjectCorrupt(format!("{}/{}", table, signature))
+            })?),
+        })
+    }
+
+    /// Signatures of the transactions with `address` in `table`, which is laid out like
+    /// `tx-by-addr`, e.g. a table the writer routes some transactions to, and salted like it.
+    /// Newest first. Fails like `get_confirmed_signatures_for_address` over suppressed slots.
+    pub async fn get_confirmed_signatures_for_address_in_table(
+        &self,
+        table: &str,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32,
+        )>,
+    > {
+        let signatures = self
+            .get_confirmed_signatures_for_key(
+                table,
+                address,
+                before_signature,
+                until_signature,
+                limit,
+                self.tx_by_addr_salt_buckets,
+            )
+            .await?;
+        self.check_address_suppressions(address, before_signature, until_signature, limit, signatures)
+            .await
+    }
+
+    /// Slot of a transaction in the `tx` table, if it is there.
+    async fn get_transaction_slot(&self, signature: &Signature) -> Result<Option<Slot>> {
+        let mut hbase = self.connection.client()?;
+        match hbase.get_bincode_cell::<TransactionInfo>(&self.table_names.tx, signature.to_string()).await {
+            Ok(TransactionInfo { slot, .. }) => Ok(Some(slot)),
+            Err(hbase_error::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Changes listed under `address` in `table`, which is keyed like `tx-by-addr` and holds a
+    /// bincode `Vec<T>` per slot, between `start_slot` and `end_slot` inclusive, newest first.
+    /// Changes rejected by `filter` don't count towards `limit`.
+    async fn get_history<T>(
+        &self,
+        table: &str,
+        address: &Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+        filter: impl Fn(&T) -> bool,
+    ) -> Result<Vec<(Slot, T)>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        if start_slot > end_slot || limit == 0 {
+            return Ok(vec![]);
+        }
+        let mut hbase = self.connection.client()?;
+        let address_prefix = format!("{address}/");
+
+        // Slots are inverted in the row keys, so the scan starts at `end_slot`. The stop row is
+        // exclusive, hence the suffix to include the row of `start_slot`. Row keys of an address
+        // have the same length, so the suffix also resumes the scan after a row.
+        let mut start_key = format!("{}{}", address_prefix, slot_to_tx_by_addr_key(end_slot));
+        let end_key = format!("{}{}0", address_prefix, slot_to_tx_by_addr_key(start_slot));
+
+        let mut entries = vec![];
+        loop {
+            let row_data = hbase
+                .get_row_data(table, Some(start_key.clone()), Some(end_key.clone()), limit as i64)
+                .await?;
+            let is_last_batch = row_data.len() < limit;
+
+            for (row_key, data) in row_data {
+                let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
+                    hbase_error::Error::ObjectCorrupt(format!(
+                        "Failed to convert key to slot: {table}/{row_key}"
+                    ))
+                })?;
+                start_key = format!("{row_key}0");
+                let changes: Vec<T> = deserialize_bincode_cell_data(&data, table, row_key)?;
+
+                for change in changes.into_iter().rev().filter(|change| filter(change)) {
+                    entries.push((slot, change));
+                    if entries.len() >= limit {
+                        return Ok(entries);
+                    }
+                }
+            }
+
+            if is_last_batch {
+                return Ok(entries);
+            }
+        }
+    }
+
+    /// Rows listed under `address` in `table`, which is laid out like `tx-by-addr`, from
+    /// `first_slot` down to `last_slot` exclusive, newest first, with their slots. With salted
+    /// row keys every bucket holds some of the slots of the address, so the buckets are scanned
+    /// concurrently for `limit` rows each and the rows are merged by slot.
+    async fn get_tx_by_addr_rows(
+        &self,
+        table: &str,
+        address: &Pubkey,
+        first_slot: Slot,
+        last_slot: Slot,
+        limit: i64,
+        salt_buckets: Option<u16>,
+    ) -> Result<Vec<(Slot, hbase::RowKey, hbase::RowData)>> {
+        let salt_prefixes = match salt_buckets {
+            Some(buckets) => {
+                let buckets = buckets.clamp(1, MAX_TX_BY_ADDR_SALT_BUCKETS);
+                (0..buckets)
+                    .map(|bucket| tx_by_addr_salt_prefix(bucket, buckets))
+                    .collect()
+            }
+            None => vec![String::new()],
+        };
+
+        let scans = salt_prefixes.into_iter().map(|salt_prefix| async move {
+            let mut hbase = self.connection.client()?;
+            let address_prefix = format!("{salt_prefix}{address}/");
+            let row_data = hbase
+                .get_row_data(
+                    table,
+                    Some(format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot))),
+                    Some(format!("{}{}", address_prefix, slot_to_tx_by_addr_key(last_slot))),
+                    limit,
+                )
+                .await?;
+            row_data
+                .into_iter()
+                .map(|(row_key, data)| {
+                    let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
+                        hbase_error::Error::ObjectCorrupt(format!(
+                            "Failed to convert key to slot: {table}/{row_key}"
+                        ))
+                    })?;
+                    Ok((slot, row_key, data))
+                })
+                .collect::<Result<Vec<_>>>()
+        });
+        let bucket_rows = futures::future::try_join_all(scans).await?;
+
+        Ok(merge_tx_by_addr_rows(bucket_rows, limit))
+    }
+
+    /// Fail a signature listing of `address` that passed through slots the address was
+    /// suppressed from by the writer's hot account policy, if `check_suppressed_addresses`.
+    async fn check_address_suppressions(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+        signatures: Vec<(ConfirmedTransactionStatusWithSignature, u32)>,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        if !self.check_suppressed_addresses {
+            return Ok(signatures);
+        }
+        let periods = self.get_address_suppressions(address).await?;
+        if periods.is_empty() {
+            return Ok(signatures);
+        }
+
+        // Slots the listing went through, down to the oldest signature if it is full
+        let newest_slot = match before_signature {
+            Some(signature) => self.get_transaction_slot(signature).await?.unwrap_or(Slot::MAX),
+            None => Slot::MAX,
+        };
+        let oldest_slot = match signatures.last() {
+            Some((signature, _)) if signatures.len() >= limit => signature.slot,
+            _ => match until_signature {
+                Some(signature) => self.get_transaction_slot(signature).await?.unwrap_or(0),
+                None => 0,
+            },
+        };
+        match periods.iter().find(|period| {
+            period.start_slot <= newest_slot
+                && period.end_slot.is_none_or(|end_slot| end_slot > oldest_slot)
+        }) {
+            Some(period) => Err(Error::AddressHistorySuppressed(*address, period.start_slot)),
+            None => Ok(signatures),
+        }
+    }
+
+    /// Signatures listed under `address` in `table`, which is laid out like `tx-by-addr`, newest
+    /// first. Row keys are salted if `salt_buckets` is set, see `get_tx_by_addr_rows`.
+    async fn get_confirmed_signatures_for_key(
+        &self,
+        table: &str,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+        salt_buckets: Option<u16>,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32,
+        )>,
+    > {
+        // info!(
+        //     "LedgerStorage::get_confirmed_signatures_for_address: {:?}",
+        //     address
+        // );
+        // info!("Using signature range [before: {:?}, until: {:?}]", before_signature.clone(), until_signature.clone());
+
+        // inc_new_counter_debug!("storage-hbase-query", 1);
+        let mut hbase = self.connection.client()?;
+
+        // Figure out where to start listing from based on `before_signature`
+        let (first_slot, before_transaction_index, before_fallback) = match before_signature {
+            None => (Slot::MAX, 0, false),
+            Some(before_signature) => {
+                // Try fetching from `tx` first
+                match hbase.get_bincode_cell(&self.table_names.tx, before_signature.to_string()).await {
+                    Ok(TransactionInfo { slot, index, .. }) => (slot, index, false),
+                    // Fallback to `tx_full` if `tx` is not found
+                    Err(hbase_error::Error::RowNotFound) => {
+                        match self.get_full_transaction(before_signature).await? {
+                            Some(full_transaction) => (full_transaction.slot, 0, true),
+                            None => return Ok(vec![]),
+                        }
+                    },
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        };
+
+        debug!("Got starting slot: {:?}, index: {:?}, using tx_full fallback: {:?}",
+            first_slot.clone(),
+            before_transaction_index.clone(),
+            before_fallback
+        );
+
+        // Figure out where to end listing from based on `until_signature`
+        let (last_slot, until_transaction_index, until_fallback) = match until_signature {
+            None => (0, u32::MAX, false),
+            Some(until_signature) => {
+                // Try fetching from `tx` first
+                match hbase.get_bincode_cell(&self.table_names.tx, until_signature.to_string()).await {
+                    Ok(TransactionInfo { slot, index, .. }) => (slot, index, false),
+                    // Fallback to `tx_full` if `tx` is not found
+                    Err(hbase_error::Error::RowNotFound) => {
+                        match self.get_full_transaction(until_signature).await? {
+                            Some(full_transaction) => (full_transaction.slot, 0, true),
+                            None => return Ok(vec![]),
+                        }
+                    },
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        };
+
+        debug!("Got ending slot: {:?}, index: {:?}, using tx_full fallback: {:?}",
+            last_slot.clone(),
+            until_transaction_index.clone(),
+            until_fallback
+        );
+
+        let mut infos = vec![];
+
+        debug!("Getting the starting slot length from {}", table);
+
+        let starting_slot_tx_len = hbase
+            .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
+                table,
+                tx_by_addr_key(address, first_slot, salt_buckets),
+            )
+            .await
+            .map(|cell_data| {
+                match cell_data {
+                    hbase::CellData::Bincode(tx_by_addr) => tx_by_addr.len(),
+                    hbase::CellData::Protobuf(tx_by_addr) => tx_by_addr.tx_by_addrs.len(),
+                }
+            })
+            .unwrap_or(0);
+
+        debug!("Got starting slot tx len: {:?}", starting_slot_tx_len);
+
+        // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
+        // number that might be flitered out
+        let tx_by_addr_data = self
+            .get_tx_by_addr_rows(
+                table,
+                address,
+                first_slot,
+                last_slot.saturating_sub(1),
+                limit as i64 + starting_slot_tx_len as i64,
+                salt_buckets,
+            )
+            .await?;
+
+        debug!("Loaded {:?} {} entries", tx_by_addr_data.len(), table);
+
+        'outer: for (slot, row_key, data) in tx_by_addr_data {
+            debug!("Deserializing {} result data", table);
+
+            let deserialized_cell_data = deserialize_protobuf_or_bincode_cell_data::<
+                Vec<LegacyTransactionByAddrInfo>,
+                tx_by_addr::TransactionByAddr,
+            >(&data, table, row_key.clone())?;
+
+            let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
+                hbase::CellData::Bincode(tx_by_addr) => {
+                    tx_by_addr.into_iter().map(|legacy| legacy.into()).collect()
+                }
+                hbase::CellData::Protobuf(tx_by_addr) => {
+                    tx_by_addr.try_into().map_err(|error| {
+                        hbase_error::Error::ObjectCorrupt(format!(
+                            "Failed to deserialize: {}: {}/{}",
+                            error,
+                            table,
+                            row_key.clone()
+                        ))
+                    })?
+                }
+            };
+
+            cell_data.reverse();
+
+            debug!("Filtering the result data");
+
+            for tx_by_addr_info in cell_data.into_iter() {
+                debug!("Checking result [slot: {:?}, index: {:?}], signature: {:?}", slot, tx_by_addr_info.index, tx_by_addr_info.signature);
+
+                // Filter out records before `before_transaction_index`
+                if !before_fallback && slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
+                    debug!("Skipping transaction before [slot: {:?}, index: {:?}], signature: {:?}", slot, tx_by_addr_info.index, tx_by_addr_info.signature);
+                    continue;
+                }
+
+                // Filter out records after `until_transaction_index` unless fallback was used
+                if !until_fallback && slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
+                    debug!("Skipping transaction until [slot: {:?}, index: {:?}], signature: {:?}", slot, tx_by_addr_info.index, tx_by_addr_info.signature);
+                    continue;
+                }
+
+                infos.push((
+                    ConfirmedTransactionStatusWithSignature {
+                        signature: tx_by_addr_info.signature,
+                        slot,
+                        err: tx_by_addr_info.err,
+                        memo: tx_by_addr_info.memo,
+                        block_time: tx_by_addr_info.block_time,
                     },
-                    Err(e) => {
-                        error!("Tokio task join error while connecting to cache server: {}", e);
-                        None
-                    }
+                    tx_by_addr_info.index,
+                ));
+                // Respect limit
+                debug!("Checking the limit: {:?}/{:?}", infos.len(), limit);
+                if infos.len() >= limit {
+                    debug!("Limit was reached, exiting loop");
+                    break 'outer;
                 }
-            } else {
-                None
             }
-        } else {
-            None
-        };
+        }
 
-        Ok(Self {
-            connection,
-            use_md5_row_key_salt,
-            cache_client,
-            disable_tx_fallback,
-            // TODO: Implement metrics
-            // metrics,
-            //------------------------
-        })
+        debug!("Returning {:?} result entries", infos.len());
+
+        Ok(infos)
     }
 }
 
@@ -173,13 +929,9 @@ impl LedgerStorageAdapter for LedgerStorage {
     async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         debug!("LedgerStorage::get_first_available_block request received");
 
-        if self.use_md5_row_key_salt {
-            return Ok(Some(0));
-        }
-
         // inc_new_counter_debug!("storage-hbase-query", 1);
-        let mut hbase = self.connection.client();
-        let blocks = hbase.get_row_keys("blocks", None, None, 1, false).await?;
+        let mut hbase = self.connection.client()?;
+        let blocks = hbase.get_row_keys(self.slots_table_name(), None, None, 1, false).await?;
         if blocks.is_empty() {
             return Ok(None);
         }
@@ -196,17 +948,13 @@ impl LedgerStorageAdapter for LedgerStorage {
             start_slot, limit
         );
 
-        if self.use_md5_row_key_salt {
-            return Ok(vec![]);
-        }
-
         // inc_new_counter_debug!("storage-hbase-query", 1);
-        let mut hbase = self.connection.client();
+        let mut hbase = self.connection.client()?;
         let blocks = hbase
             .get_row_keys(
-                "blocks",
-                Some(slot_to_blocks_key(start_slot, false)),
-                Some(slot_to_blocks_key(start_slot + limit as u64, false)), // None,
+                self.slots_table_name(),
+                Some(slot_to_key(start_slot)),
+                Some(slot_to_key(start_slot + limit as u64)), // None,
                 limit as i64,
                 false
             )
@@ -223,13 +971,13 @@ impl LedgerStorageAdapter for LedgerStorage {
         // inc_new_counter_debug!("storage-hbase-query", 1);
 
         let start = Instant::now();
-        let mut hbase = self.connection.client();
+        let mut hbase = self.connection.client()?;
         let duration: Duration = start.elapsed();
         debug!("HBase connection took {:?}", duration);
 
         let block_cell_data_serialized = hbase
             .get_protobuf_or_bincode_cell_serialized::<StoredConfirmedBlock, generated::ConfirmedBlock>(
-                "blocks",
+                &self.table_names.blocks,
                 slot_to_blocks_key(slot, self.use_md5_row_key_salt),
             )
             .await
@@ -243,7 +991,7 @@ impl LedgerStorageAdapter for LedgerStorage {
         let block_cell_data =
             deserialize_protobuf_or_bincode_cell_data::<StoredConfirmedBlock, generated::ConfirmedBlock>(
                 &block_cell_data_serialized,
-                "blocks",
+                &self.table_names.blocks,
                 slot_to_blocks_key(slot, self.use_md5_row_key_salt),
             )?;
 
@@ -264,9 +1012,9 @@ impl LedgerStorageAdapter for LedgerStorage {
             signature
         );
         // inc_new_counter_debug!("storage-hbase-query", 1);
-        let mut hbase = self.connection.client();
+        let mut hbase = self.connection.client()?;
         let transaction_info = hbase
-            .get_bincode_cell::<TransactionInfo>("tx", signature.to_string())
+            .get_bincode_cell::<TransactionInfo>(&self.table_names.tx, signature.to_string())
             .await
             .map_err(|err| match err {
                 // hbase::Error::RowNotFound => Error::SignatureNotFound,
@@ -286,26 +1034,7 @@ impl LedgerStorageAdapter for LedgerStorage {
         );
         // inc_new_counter_debug!("storage-hbase-query", 1);
 
-        let mut hbase = self.connection.client();
-
-        let tx_cell_data = hbase
-            .get_protobuf_or_bincode_cell::<StoredConfirmedTransactionWithStatusMeta, generated::ConfirmedTransactionWithStatusMeta>(
-                "tx_full",
-                signature.to_string(),
-            )
-            .await
-            .map_err(|err| match err {
-                hbase_error::Error::RowNotFound => Error::SignatureNotFound,
-                _ => err.into(),
-            })?;
-
-        Ok(match tx_cell_data {
-            hbase::CellData::Bincode(tx) => Some(tx.into()),
-            hbase::CellData::Protobuf(tx) => Some(tx.try_into().map_err(|_err| {
-                error!("Protobuf object is corrupted");
-                hbase_error::Error::ObjectCorrupt(format!("tx_full/{}", signature.to_string()))
-            })?),
-        })
+        self.get_full_transaction_from_table(&self.table_names.full_tx, signature).await
     }
 
     /// Fetch a confirmed transaction
@@ -378,11 +1107,11 @@ impl LedgerStorageAdapter for LedgerStorage {
 
         debug!("Looking for transaction in tx table");
 
-        let mut hbase = self.connection.client();
+        let mut hbase = self.connection.client()?;
 
         // Figure out which block the transaction is located in
         let TransactionInfo { slot, index, .. } = hbase
-            .get_bincode_cell("tx", signature.to_string())
+            .get_bincode_cell(&self.table_names.tx, signature.to_string())
             .await
             .map_err(|err| match err {
                 hbase_error::Error::RowNotFound => Error::SignatureNotFound,
@@ -433,183 +1162,76 @@ impl LedgerStorageAdapter for LedgerStorage {
             u32,
         )>,
     > {
-        // info!(
-        //     "LedgerStorage::get_confirmed_signatures_for_address: {:?}",
-        //     address
-        // );
-        // info!("Using signature range [before: {:?}, until: {:?}]", before_signature.clone(), until_signature.clone());
-
-        // inc_new_counter_debug!("storage-hbase-query", 1);
-        let mut hbase = self.connection.client();
-        let address_prefix = format!("{address}/");
-
-        // Figure out where to start listing from based on `before_signature`
-        let (first_slot, before_transaction_index, before_fallback) = match before_signature {
-            None => (Slot::MAX, 0, false),
-            Some(before_signature) => {
-                // Try fetching from `tx` first
-                match hbase.get_bincode_cell("tx", before_signature.to_string()).await {
-                    Ok(TransactionInfo { slot, index, .. }) => (slot, index, false),
-                    // Fallback to `tx_full` if `tx` is not found
-                    Err(hbase_error::Error::RowNotFound) => {
-                        match self.get_full_transaction(before_signature).await? {
-                            Some(full_transaction) => (full_transaction.slot, 0, true),
-                            None => return Ok(vec![]),
-                        }
-                    },
-                    Err(err) => return Err(err.into()),
-                }
-            }
-        };
-
-        debug!("Got starting slot: {:?}, index: {:?}, using tx_full fallback: {:?}",
-            first_slot.clone(),
-            before_transaction_index.clone(),
-            before_fallback
-        );
-
-        // Figure out where to end listing from based on `until_signature`
-        let (last_slot, until_transaction_index, until_fallback) = match until_signature {
-            None => (0, u32::MAX, false),
-            Some(until_signature) => {
-                // Try fetching from `tx` first
-                match hbase.get_bincode_cell("tx", until_signature.to_string()).await {
-                    Ok(TransactionInfo { slot, index, .. }) => (slot, index, false),
-                    // Fallback to `tx_full` if `tx` is not found
-                    Err(hbase_error::Error::RowNotFound) => {
-                        match self.get_full_transaction(until_signature).await? {
-                            Some(full_transaction) => (full_transaction.slot, 0, true),
-                            None => return Ok(vec![]),
-                        }
-                    },
-                    Err(err) => return Err(err.into()),
-                }
-            }
-        };
-
-        debug!("Got ending slot: {:?}, index: {:?}, using tx_full fallback: {:?}",
-            last_slot.clone(),
-            until_transaction_index.clone(),
-            until_fallback
-        );
-
-        let mut infos = vec![];
-
-        debug!("Getting the starting slot length from tx-by-addr");
-
-        let starting_slot_tx_len = hbase
-            .get_protobuf_or_bincode_cell::<Vec<LegacyTransactionByAddrInfo>, tx_by_addr::TransactionByAddr>(
-                "tx-by-addr",
-                format!("{}{}", address_prefix, slot_to_tx_by_addr_key(first_slot)),
-            )
-            .await
-            .map(|cell_data| {
-                match cell_data {
-                    hbase::CellData::Bincode(tx_by_addr) => tx_by_addr.len(),
-                    hbase::CellData::Protobuf(tx_by_addr) => tx_by_addr.tx_by_addrs.len(),
-                }
-            })
-            .unwrap_or(0);
-
-        debug!("Got starting slot tx len: {:?}", starting_slot_tx_len);
-
-        // Return the next tx-by-addr data of amount `limit` plus extra to account for the largest
-        // number that might be flitered out
-        let tx_by_addr_data = hbase
-            .get_row_data(
-                "tx-by-addr",
-                Some(format!(
-                    "{}{}",
-                    address_prefix,
-                    slot_to_tx_by_addr_key(first_slot),
-                )),
-                Some(format!(
-                    "{}{}",
-                    address_prefix,
-                    slot_to_tx_by_addr_key(last_slot.saturating_sub(1)),
-                )),
-                limit as i64 + starting_slot_tx_len as i64,
+        let signatures = self
+            .get_confirmed_signatures_for_key(
+                &self.table_names.tx_by_addr,
+                address,
+                before_signature,
+                until_signature,
+                limit,
+                self.tx_by_addr_salt_buckets,
             )
             .await?;
+        self.check_address_suppressions(address, before_signature, until_signature, limit, signatures)
+            .await
+    }
 
-        debug!("Loaded {:?} tx-by-addr entries", tx_by_addr_data.len());
-
-        'outer: for (row_key, data) in tx_by_addr_data {
-            let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
-                hbase_error::Error::ObjectCorrupt(format!(
-                    "Failed to convert key to slot: tx-by-addr/{row_key}"
-                ))
-            })?;
-
-            debug!("Deserializing tx-by-addr result data");
-
-            let deserialized_cell_data = deserialize_protobuf_or_bincode_cell_data::<
-                Vec<LegacyTransactionByAddrInfo>,
-                tx_by_addr::TransactionByAddr,
-            >(&data, "tx-by-addr", row_key.clone())?;
-
-            let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
-                hbase::CellData::Bincode(tx_by_addr) => {
-                    tx_by_addr.into_iter().map(|legacy| legacy.into()).collect()
-                }
-                hbase::CellData::Protobuf(tx_by_addr) => {
-                    tx_by_addr.try_into().map_err(|error| {
-                        hbase_error::Error::ObjectCorrupt(format!(
-                            "Failed to deserialize: {}: tx-by-addr/{}",
-                            error,
-                            row_key.clone()
-                        ))
-                    })?
-                }
-            };
-
-            cell_data.reverse();
-
-            debug!("Filtering the result data");
-
-            for tx_by_addr_info in cell_data.into_iter() {
-                debug!("Checking result [slot: {:?}, index: {:?}], signature: {:?}", slot, tx_by_addr_info.index, tx_by_addr_info.signature);
-
-                // Filter out records before `before_transaction_index`
-                if !before_fallback && slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
-                    debug!("Skipping transaction before [slot: {:?}, index: {:?}], signature: {:?}", slot, tx_by_addr_info.index, tx_by_addr_info.signature);
-                    continue;
-                }
+    async fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTimestamp>> {
+        let mut hbase = self.connection.client()?;
+        match hbase
+            .get_bincode_cell::<UnixTimestamp>(&self.table_names.block_time, slot_to_block_time_key(slot))
+            .await
+        {
+            Ok(block_time) => Ok(Some(block_time)),
+            Err(hbase_error::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
 
-                // Filter out records after `until_transaction_index` unless fallback was used
-                if !until_fallback && slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
-                    debug!("Skipping transaction until [slot: {:?}, index: {:?}], signature: {:?}", slot, tx_by_addr_info.index, tx_by_addr_info.signature);
-                    continue;
-                }
+    async fn get_slot_at_time(&self, timestamp: UnixTimestamp) -> Result<Option<Slot>> {
+        Ok(self
+            .get_blocks_in_time_range(UnixTimestamp::MIN, timestamp, 1)
+            .await?
+            .first()
+            .map(|(slot, _)| *slot))
+    }
 
-                infos.push((
-                    ConfirmedTransactionStatusWithSignature {
-                        signature: tx_by_addr_info.signature,
-                        slot,
-                        err: tx_by_addr_info.err,
-                        memo: tx_by_addr_info.memo,
-                        block_time: tx_by_addr_info.block_time,
-                    },
-                    tx_by_addr_info.index,
-                ));
-                // Respect limit
-                debug!("Checking the limit: {:?}/{:?}", infos.len(), limit);
-                if infos.len() >= limit {
-                    debug!("Limit was reached, exiting loop");
-                    break 'outer;
-                }
-            }
+    async fn get_blocks_in_time_range(
+        &self,
+        start_time: UnixTimestamp,
+        end_time: UnixTimestamp,
+        limit: usize,
+    ) -> Result<Vec<(Slot, UnixTimestamp)>> {
+        if start_time > end_time {
+            return Ok(vec![]);
         }
+        let mut hbase = self.connection.client()?;
+        let row_keys = hbase
+            .get_row_keys(
+                &self.table_names.block_time,
+                Some(block_time_prefix(end_time)),
+                Some(block_time_prefix_end(start_time)),
+                limit as i64,
+                false,
+            )
+            .await?;
 
-        debug!("Returning {:?} result entries", infos.len());
-
-        Ok(infos)
+        row_keys
+            .iter()
+            .map(|row_key| {
+                key_to_block_time(row_key)
+                    .map(|(block_time, slot)| (slot, block_time))
+                    .ok_or_else(|| {
+                        hbase_error::Error::ObjectCorrupt(format!("{}/{row_key}", self.table_names.block_time)).into()
+                    })
+            })
+            .collect()
     }
 
     async fn get_latest_stored_slot(&self) -> Result<Slot> {
         // inc_new_counter_debug!("storage-hbase-query", 1);
-        let mut hbase = self.connection.client();
-        match hbase.get_last_row_key("blocks").await {
+        let mut hbase = self.connection.client()?;
+        match hbase.get_last_row_key(self.slots_table_name()).await {
             Ok(last_row_key) => {
                 match key_to_slot(&last_row_key) {
                     Some(slot) => Ok(slot),
@@ -627,7 +1249,160 @@ impl LedgerStorageAdapter for LedgerStorage {
         }
     }
 
+    async fn get_block_by_hash(&self, blockhash: &str) -> Result<Option<(Slot, ConfirmedBlock)>> {
+        let Some(slot) = self.get_block_index_cell(blockhash_to_key(blockhash)).await? else {
+            return Ok(None);
+        };
+        match self.get_confirmed_block(slot).await {
+            Ok(block) => Ok(Some((slot, block))),
+            Err(Error::BlockNotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn get_slot_by_block_height(&self, block_height: u64) -> Result<Option<Slot>> {
+        self.get_block_index_cell(block_height_to_key(block_height)).await
+    }
+
+    async fn get_block_height(&self, slot: Slot) -> Result<Option<u64>> {
+        self.get_block_index_cell(slot_to_block_height_key(slot)).await
+    }
+
+    async fn get_inflation_rewards(
+        &self,
+        address: &Pubkey,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<(Epoch, Slot, Reward)>> {
+        if start_epoch > end_epoch {
+            return Ok(vec![]);
+        }
+        let mut hbase = self.connection.client()?;
+        let address = address.to_string();
+        // Row keys of an address have the same length, so the suffix resumes the scan after a
+        // row. The end is never a row key itself.
+        let mut start_key = rewards_epoch_prefix(&address, start_epoch);
+        let end_key = rewards_epoch_prefix(&address, end_epoch.saturating_add(1));
+
+        let mut rewards = vec![];
+        loop {
+            let row_data = hbase
+                .get_row_data(
+                    &self.table_names.rewards,
+                    Some(start_key.clone()),
+                    Some(end_key.clone()),
+                    REWARDS_ROWS_BATCH_SIZE,
+                )
+                .await?;
+            let is_last_batch = row_data.len() < REWARDS_ROWS_BATCH_SIZE as usize;
+
+            for (row_key, data) in row_data {
+                let (_, epoch, slot) = key_to_rewards(&row_key).ok_or_else(|| {
+                    hbase_error::Error::ObjectCorrupt(format!("{}/{row_key}", self.table_names.rewards))
+                })?;
+                start_key = format!("{row_key}0");
+                let cells: Vec<RewardCell> =
+                    deserialize_bincode_cell_data(&data, &self.table_names.rewards, row_key)?;
+                rewards.extend(
+                    cells
+                        .into_iter()
+                        .filter(|cell| is_inflation_reward(cell.reward_type))
+                        .map(|cell| (epoch, slot, cell.into_reward(address.clone()))),
+                );
+            }
+
+            if is_last_batch {
+                return Ok(rewards);
+            }
+        }
+    }
+
+    async fn get_block_leader(&self, slot: Slot) -> Result<Option<Pubkey>> {
+        let mut hbase = self.connection.client()?;
+        match hbase
+            .get_bincode_cell::<Pubkey>(&self.table_names.leader_index, slot_to_leader_key(slot))
+            .await
+        {
+            Ok(leader) => Ok(Some(leader)),
+            Err(hbase_error::Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn get_blocks_by_leader(
+        &self,
+        leader: &Pubkey,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<Slot>> {
+        if start_slot > end_slot {
+            return Ok(vec![]);
+        }
+        let mut hbase = self.connection.client()?;
+        let row_keys = hbase
+            .get_row_keys(
+                &self.table_names.leader_index,
+                Some(leader_slot_key(leader, start_slot)),
+                Some(leader_slot_key_end(leader, end_slot)),
+                limit as i64,
+                false,
+            )
+            .await?;
+
+        row_keys
+            .iter()
+            .map(|row_key| {
+                key_to_leader_slot(row_key).ok_or_else(|| {
+                    hbase_error::Error::ObjectCorrupt(format!("{}/{row_key}", self.table_names.leader_index)).into()
+                })
+            })
+            .collect()
+    }
+
     fn clone_box(&self) -> Box<dyn LedgerStorageAdapter> {
         Box::new(self.clone())
     }
 }
+
+/// Rows of the scanned `tx-by-addr` buckets, newest first, up to `limit`. Each bucket is sorted
+/// newest first, a single unsalted bucket is returned as is.
+fn merge_tx_by_addr_rows(
+    mut bucket_rows: Vec<Vec<(Slot, hbase::RowKey, hbase::RowData)>>,
+    limit: i64,
+) -> Vec<(Slot, hbase::RowKey, hbase::RowData)> {
+    if bucket_rows.len() == 1 {
+        return bucket_rows.pop().unwrap();
+    }
+    let mut rows: Vec<_> = bucket_rows.into_iter().flatten().collect();
+    rows.sort_by(|(slot, ..), (other_slot, ..)| other_slot.cmp(slot));
+    rows.truncate(limit.max(0) as usize);
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge_tx_by_addr_rows() {
+        let row = |bucket: u8, slot: Slot| (slot, format!("{bucket:02x}/addr/{slot}"), vec![]);
+
+        // Unsalted rows are already sorted and limited by the scan
+        let rows = merge_tx_by_addr_rows(vec![vec![row(0, 7), row(0, 3)]], 2);
+        assert_eq!(rows.iter().map(|(slot, ..)| *slot).collect::<Vec<_>>(), vec![7, 3]);
+
+        let bucket_rows = vec![
+            vec![row(0, 9), row(0, 6), row(0, 1)],
+            vec![],
+            vec![row(2, 8), row(2, 5)],
+            vec![row(3, 7), row(3, 2)],
+        ];
+        let rows = merge_tx_by_addr_rows(bucket_rows.clone(), 4);
+        assert_eq!(rows.iter().map(|(slot, ..)| *slot).collect::<Vec<_>>(), vec![9, 8, 7, 6]);
+        assert_eq!(rows[1].1, "02/addr/8");
+
+        assert_eq!(merge_tx_by_addr_rows(bucket_rows.clone(), 10).len(), 7);
+        assert!(merge_tx_by_addr_rows(bucket_rows, 0).is_empty());
+    }
+}
@@ -5,6 +5,8 @@ mod hbase_error;
 mod tx_utils;
 mod tx_cache;
 mod connection;
-mod storage_config;
+pub use solana_hbase_common::thrift_config;
+pub mod storage_config;
+pub mod table_names;
 // mod types;
-mod ledger_storage;
\ No newline at end of file
+pub mod ledger_storage;
\ No newline at end of file
@@ -1,5 +1,9 @@
 
 use {
+    crate::{
+        table_names::TableNames,
+        thrift_config::ThriftConfig,
+    },
     std::{
         time::{Duration},
     },
@@ -16,7 +20,17 @@ pub struct LedgerStorageConfig {
     pub use_md5_row_key_salt: bool,
     pub enable_full_tx_cache: bool,
     pub disable_tx_fallback: bool,
+    /// Fail address, token owner and mint signature listings that pass through slots the
+    /// address was suppressed from by the writer's hot account policy, instead of returning
+    /// them incomplete.
+    pub check_suppressed_addresses: bool,
+    /// Salt buckets the writer spread the `tx-by-addr` rows over, if it salted them.
+    pub tx_by_addr_salt_buckets: Option<u16>,
     pub cache_address: Option<String>,
+    pub thrift_config: ThriftConfig,
+    /// Trained zstd dictionaries used by the writer, needed to read cells compressed with them.
+    pub zstd_dictionaries: Vec<String>,
+    pub table_names: TableNames,
 }
 
 impl Default for LedgerStorageConfig {
@@ -28,7 +42,12 @@ impl Default for LedgerStorageConfig {
             use_md5_row_key_salt: false,
             enable_full_tx_cache: false,
             disable_tx_fallback: false,
+            check_suppressed_addresses: false,
+            tx_by_addr_salt_buckets: None,
             cache_address: Some(DEFAULT_ADDRESS.to_string()),
+            thrift_config: ThriftConfig::default(),
+            zstd_dictionaries: vec![],
+            table_names: TableNames::default(),
         }
     }
 }
\ No newline at end of file
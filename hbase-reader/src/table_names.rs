@@ -0,0 +1,96 @@
+use {
+    solana_storage_utils::{
+        balance_history::BALANCE_HISTORY_TABLE_NAME,
+        block_index::BLOCK_INDEX_TABLE_NAME,
+        block_stats::BLOCK_STATS_TABLE_NAME,
+        block_time::BLOCK_TIME_TABLE_NAME,
+        hot_accounts::SUPPRESSED_ADDRESSES_TABLE_NAME,
+        leader_index::LEADER_INDEX_TABLE_NAME,
+        memo_index::MEMO_INDEX_TABLE_NAME,
+        program_index::TX_BY_PROGRAM_TABLE_NAME,
+        rewards::REWARDS_TABLE_NAME,
+        slot_index::SLOT_INDEX_TABLE_NAME,
+        token_balance_history::TOKEN_BALANCE_HISTORY_TABLE_NAME,
+        token_index::{TX_BY_MINT_TABLE_NAME, TX_BY_OWNER_TABLE_NAME},
+    },
+};
+
+/// Tables read by `LedgerStorage`. They must match the table names of the writer's
+/// `UploaderConfig`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableNames {
+    pub blocks: String,
+    pub tx: String,
+    pub tx_by_addr: String,
+    pub full_tx: String,
+    pub tx_by_owner: String,
+    pub tx_by_mint: String,
+    pub tx_by_program: String,
+    pub block_time: String,
+    pub block_index: String,
+    pub rewards: String,
+    pub leader_index: String,
+    pub block_stats: String,
+    pub memo_index: String,
+    pub balance_history: String,
+    pub token_balance_history: String,
+    pub suppressed_addresses: String,
+    pub slot_index: String,
+}
+
+impl Default for TableNames {
+    fn default() -> Self {
+        Self {
+            blocks: "blocks".to_string(),
+            tx: "tx".to_string(),
+            tx_by_addr: "tx-by-addr".to_string(),
+            full_tx: "tx_full".to_string(),
+            tx_by_owner: TX_BY_OWNER_TABLE_NAME.to_string(),
+            tx_by_mint: TX_BY_MINT_TABLE_NAME.to_string(),
+            tx_by_program: TX_BY_PROGRAM_TABLE_NAME.to_string(),
+            block_time: BLOCK_TIME_TABLE_NAME.to_string(),
+            block_index: BLOCK_INDEX_TABLE_NAME.to_string(),
+            rewards: REWARDS_TABLE_NAME.to_string(),
+            leader_index: LEADER_INDEX_TABLE_NAME.to_string(),
+            block_stats: BLOCK_STATS_TABLE_NAME.to_string(),
+            memo_index: MEMO_INDEX_TABLE_NAME.to_string(),
+            balance_history: BALANCE_HISTORY_TABLE_NAME.to_string(),
+            token_balance_history: TOKEN_BALANCE_HISTORY_TABLE_NAME.to_string(),
+            suppressed_addresses: SUPPRESSED_ADDRESSES_TABLE_NAME.to_string(),
+            slot_index: SLOT_INDEX_TABLE_NAME.to_string(),
+        }
+    }
+}
+
+impl TableNames {
+    /// Table whose row keys are the slots of the stored blocks, formatted like `slot_to_key`.
+    /// Salted `blocks` row keys are not sorted by slot, the writer then indexes them in
+    /// `slot_index`.
+    pub fn slots(&self, use_md5_row_key_salt: bool) -> &str {
+        if use_md5_row_key_salt {
+            &self.slot_index
+        } else {
+            &self.blocks
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_slots_table_name() {
+        let table_names = TableNames::default();
+        assert_eq!(table_names.slots(false), "blocks");
+        assert_eq!(table_names.slots(true), "slot-index");
+
+        let table_names = TableNames {
+            blocks: "blocks_test".to_string(),
+            slot_index: "slot-index_test".to_string(),
+            ..TableNames::default()
+        };
+        assert_eq!(table_names.slots(false), "blocks_test");
+        assert_eq!(table_names.slots(true), "slot-index_test");
+    }
+}
@@ -0,0 +1,156 @@
+//! Compares throughput and ratio of the cell compression policies on real blocks.
+//!
+//! Blocks are read from the JSON files in `BENCH_BLOCKS_DIR`, in the format accepted by the block
+//! uploader (`getBlock` with `json` encoding and full transaction details):
+//!
+//!     BENCH_BLOCKS_DIR=/path/to/blocks cargo bench -p solana-hbase-writer --bench compression
+
+#![allow(deprecated)]
+
+use {
+    dexter_storage_proto_tx::convert::generated,
+    prost::Message,
+    solana_block_decoder::{
+        block::encoded_block::EncodedConfirmedBlock,
+        convert_block,
+    },
+    solana_storage_utils::{
+        compression::{compress_with_policy, CompressionMode, CompressionPolicy},
+        tx_info::TransactionInfo,
+    },
+    solana_transaction_status::{
+        BlockEncodingOptions,
+        ConfirmedTransactionWithStatusMeta,
+        TransactionWithStatusMeta,
+        VersionedConfirmedBlock,
+    },
+    solana_transaction_status_client_types::{
+        TransactionDetails,
+        UiTransactionEncoding,
+    },
+    std::{
+        env,
+        fs,
+        time::Instant,
+    },
+};
+
+const POLICIES: &[(&str, usize)] = &[
+    ("none", 0),
+    ("best", 0),
+    ("best", 256),
+    ("bzip2", 0),
+    ("gzip", 0),
+    ("gzip:1", 0),
+    ("zstd", 0),
+    ("zstd:1", 0),
+    ("zstd:3", 0),
+    ("zstd:9", 0),
+    ("zstd:3", 256),
+    ("lz4", 0),
+    ("snappy", 0),
+];
+
+#[derive(Default)]
+struct Cells {
+    blocks: Vec<Vec<u8>>,
+    tx: Vec<Vec<u8>>,
+    tx_full: Vec<Vec<u8>>,
+}
+
+fn main() {
+    let Ok(blocks_dir) = env::var("BENCH_BLOCKS_DIR") else {
+        eprintln!("BENCH_BLOCKS_DIR is not set, skipping compression benchmark");
+        return;
+    };
+
+    let mut cells = Cells::default();
+    for entry in fs::read_dir(&blocks_dir).expect("read BENCH_BLOCKS_DIR") {
+        let path = entry.expect("read dir entry").path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+            continue;
+        }
+        let json = fs::read_to_string(&path).expect("read block");
+        let slot = serde_json::from_str::<serde_json::Value>(&json)
+            .ok()
+            .and_then(|json| json["blockID"].as_u64())
+            .unwrap_or_default();
+        let block: EncodedConfirmedBlock = serde_json::from_str(&json).expect("parse block");
+        let options = BlockEncodingOptions {
+            transaction_details: TransactionDetails::Full,
+            show_rewards: true,
+            max_supported_transaction_version: Some(0),
+        };
+        let block = convert_block(block, UiTransactionEncoding::Json, options)
+            .unwrap_or_else(|err| panic!("convert {}: {}", path.display(), err));
+        add_cells(&mut cells, slot, block);
+    }
+
+    println!(
+        "{:<8} {:<16} {:>8} {:>12} {:>12} {:>8} {:>10}",
+        "table", "policy", "cells", "input", "output", "ratio", "MB/s"
+    );
+    for (table, table_cells) in [
+        ("blocks", &cells.blocks),
+        ("tx", &cells.tx),
+        ("tx_full", &cells.tx_full),
+    ] {
+        for (mode, min_size) in POLICIES {
+            let policy = CompressionPolicy {
+                mode: mode.parse::<CompressionMode>().unwrap(),
+                min_size: *min_size,
+            };
+            run(table, table_cells, &policy);
+        }
+    }
+}
+
+fn add_cells(cells: &mut Cells, slot: u64, block: VersionedConfirmedBlock) {
+    for (index, transaction_with_meta) in block.transactions.iter().enumerate() {
+        let tx_info = TransactionInfo {
+            slot,
+            index: index as u32,
+            err: transaction_with_meta.meta.status.clone().err(),
+        };
+        cells.tx.push(bincode::serialize(&tx_info).unwrap());
+
+        let full_tx: generated::ConfirmedTransactionWithStatusMeta =
+            ConfirmedTransactionWithStatusMeta {
+                slot,
+                tx_with_meta: TransactionWithStatusMeta::Complete(transaction_with_meta.clone()),
+                block_time: block.block_time,
+            }
+            .into();
+        cells.tx_full.push(full_tx.encode_to_vec());
+    }
+
+    let block: generated::ConfirmedBlock = block.into();
+    cells.blocks.push(block.encode_to_vec());
+}
+
+fn run(table: &str, cells: &[Vec<u8>], policy: &CompressionPolicy) {
+    let input = cells.iter().map(Vec::len).sum::<usize>();
+
+    let start = Instant::now();
+    let output = cells
+        .iter()
+        .map(|cell| compress_with_policy(policy, cell).unwrap().len())
+        .sum::<usize>();
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let mode = if policy.min_size > 0 {
+        format!("{}>={}", policy.mode, policy.min_size)
+    } else {
+        policy.mode.to_string()
+    };
+    println!(
+        "{:<8} {:<16} {:>8} {:>12} {:>12} {:>8.3} {:>10.1}",
+        table,
+        mode,
+        cells.len(),
+        input,
+        output,
+        output as f64 / input.max(1) as f64,
+        input as f64 / 1_000_000.0 / elapsed.max(f64::EPSILON),
+    );
+}
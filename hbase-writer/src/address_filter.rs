@@ -0,0 +1,110 @@
+//! Address lists of the tx_full and tx-by-addr filters.
+//!
+//! Besides the addresses given in `FilterTxIncludeExclude::addrs`, a filter can load more from
+//! `FilterTxIncludeExclude::file`, which holds either a JSON array of addresses or one address
+//! per line, ignoring blank lines and `#` comments. `LedgerStorage` keeps the loaded filters as
+//! one `AddressFilters` and swaps it as a whole when the files are reloaded, so a block is always
+//! filtered with a single version of the lists.
+
+use {
+    crate::uploader_config::{FilterTxIncludeExclude, UploaderConfig},
+    solana_pubkey::Pubkey,
+    std::{collections::HashSet, path::Path, str::FromStr},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum AddressFilterError {
+    #[error("I/O: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid JSON address list: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+}
+
+/// Addresses in an address list file.
+pub fn load_address_file(path: impl AsRef<Path>) -> Result<HashSet<Pubkey>, AddressFilterError> {
+    parse_address_list(&std::fs::read_to_string(path)?)
+}
+
+/// Addresses in the contents of an address list file.
+pub fn parse_address_list(contents: &str) -> Result<HashSet<Pubkey>, AddressFilterError> {
+    let addresses: Vec<String> = if contents.trim_start().starts_with('[') {
+        serde_json::from_str(contents)?
+    } else {
+        contents
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim())
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    addresses
+        .iter()
+        .map(|address| {
+            Pubkey::from_str(address).map_err(|_| AddressFilterError::InvalidAddress(address.clone()))
+        })
+        .collect()
+}
+
+/// The filters in effect, with the addresses of their files loaded.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddressFilters {
+    pub tx_full: Option<FilterTxIncludeExclude>,
+    pub tx_by_addr: Option<FilterTxIncludeExclude>,
+}
+
+impl AddressFilters {
+    pub fn load(config: &UploaderConfig) -> Result<Self, AddressFilterError> {
+        Ok(Self {
+            tx_full: config.tx_full_filter.as_ref().map(load_filter).transpose()?,
+            tx_by_addr: config.tx_by_addr_filter.as_ref().map(load_filter).transpose()?,
+        })
+    }
+
+    pub fn include_in_tx_full(&self, address: &Pubkey) -> bool {
+        self.tx_full.as_ref().is_none_or(|filter| filter.allows(address))
+    }
+
+    pub fn include_in_tx_by_addr(&self, address: &Pubkey) -> bool {
+        self.tx_by_addr.as_ref().is_none_or(|filter| filter.allows(address))
+    }
+}
+
+fn load_filter(filter: &FilterTxIncludeExclude) -> Result<FilterTxIncludeExclude, AddressFilterError> {
+    let mut addrs = filter.addrs.clone();
+    if let Some(file) = &filter.file {
+        addrs.extend(load_address_file(file)?);
+    }
+    Ok(FilterTxIncludeExclude {
+        addrs,
+        ..filter.clone()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_list() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let expected = HashSet::from([first, second]);
+
+        assert_eq!(
+            parse_address_list(&format!("# exchanges\n{first}\n\n  {second}  # hot wallet\n")).unwrap(),
+            expected
+        );
+        assert_eq!(parse_address_list(&format!(r#"["{first}", "{second}"]"#)).unwrap(), expected);
+        assert!(parse_address_list("").unwrap().is_empty());
+        assert!(matches!(
+            parse_address_list(&format!("{first}\nbad")),
+            Err(AddressFilterError::InvalidAddress(address)) if address == "bad"
+        ));
+        assert!(parse_address_list("[1]").is_err());
+    }
+}
@@ -1,24 +1,22 @@
 use {
     crate::{
         hbase::{
+            Error,
             HBase,
+            RowData,
             RowKey,
             Result,
-        }
+        },
     },
+    solana_hbase_common::{thrift_config::ThriftConfig, transport::open_protocols},
     backoff::{future::retry, ExponentialBackoff},
+    solana_storage_utils::compression::CompressionPolicy,
     std::{
         time::{
             Duration,
         },
     },
     hbase_thrift::hbase::{HbaseSyncClient},
-    thrift::{
-        protocol::{
-            TBinaryInputProtocol, TBinaryOutputProtocol,
-        },
-        transport::{TBufferedReadTransport, TBufferedWriteTransport, TIoChannel, TTcpChannel},
-    },
     log::{debug, info},
 };
 
@@ -26,6 +24,15 @@ use {
 pub struct HBaseConnection {
     address: String,
     timeout: Option<Duration>,
+    thrift_config: ThriftConfig,
+}
+
+/// Configuration problems will not go away by retrying, so stop the backoff early for them.
+fn backoff_error(err: Error) -> backoff::Error<Error> {
+    match err {
+        Error::ProtocolMismatch(_) | Error::Tls(_) => backoff::Error::permanent(err),
+        _ => backoff::Error::transient(err),
+    }
 }
 
 impl HBaseConnection {
@@ -33,55 +40,49 @@ impl HBaseConnection {
         address: &str,
         _read_only: bool,
         timeout: Option<Duration>,
+        thrift_config: ThriftConfig,
     ) -> Self {
-        info!("Connecting to HBase at address {}", address.to_string());
+        info!("Connecting to HBase at address {} [{}]", address, thrift_config);
 
         Self {
             address: address.to_string(),
             timeout,
+            thrift_config,
         }
     }
 
-    pub fn client(&self) -> HBase {
-        let mut channel = TTcpChannel::new();
-
-        channel.open(self.address.clone()).unwrap();
-
-        let (input_chan, output_chan) = channel.split().unwrap();
-
-        let input_prot = TBinaryInputProtocol::new(
-            TBufferedReadTransport::new(input_chan),
-            true
-        );
-        let output_prot = TBinaryOutputProtocol::new(
-            TBufferedWriteTransport::new(output_chan),
-            true
-        );
+    pub fn client(&self) -> Result<HBase> {
+        let (input_prot, output_prot) = open_protocols(
+            &self.address,
+            self.timeout,
+            &self.thrift_config,
+        )?;
 
         let client = HbaseSyncClient::new(
             input_prot,
             output_prot
         );
 
-        HBase {
+        Ok(HBase {
             client,
             timeout: self.timeout,
-        }
+        })
     }
 
     pub async fn put_bincode_cells_with_retry<T>(
         &self,
         table: &str,
         cells: &[(RowKey, T)],
-        use_compression: bool,
+        compression: &CompressionPolicy,
+        use_envelope: bool,
         use_wal: bool,
     ) -> Result<usize>
     where
         T: serde::ser::Serialize,
     {
         retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            Ok(client.put_bincode_cells(table, cells, use_compression, use_wal).await?)
+            let mut client = self.client().map_err(backoff_error)?;
+            client.put_bincode_cells(table, cells, compression, use_envelope, use_wal).await.map_err(backoff_error)
         })
             .await
     }
@@ -90,16 +91,120 @@ impl HBaseConnection {
         &self,
         table: &str,
         cells: &[(RowKey, T)],
-        use_compression: bool,
+        compression: &CompressionPolicy,
+        use_envelope: bool,
         use_wal: bool,
     ) -> Result<usize>
     where
         T: prost::Message,
     {
         retry(ExponentialBackoff::default(), || async {
-            let mut client = self.client();
-            Ok(client.put_protobuf_cells(table, cells, use_compression, use_wal).await?)
+            let mut client = self.client().map_err(backoff_error)?;
+            client.put_protobuf_cells(table, cells, compression, use_envelope, use_wal).await.map_err(backoff_error)
+        })
+            .await
+    }
+
+    pub async fn put_row_data_with_retry(
+        &self,
+        table: &str,
+        row_data: &[(RowKey, RowData)],
+        use_wal: bool,
+    ) -> Result<()> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client().map_err(backoff_error)?;
+            client.put_row_data(table, "x", row_data, use_wal).await.map_err(backoff_error)
+        })
+            .await
+    }
+
+    pub async fn get_row_data_with_retry(
+        &self,
+        table: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client().map_err(backoff_error)?;
+            client.get_row_data(table, row_keys).await.map_err(backoff_error)
         })
             .await
     }
-}
\ No newline at end of file
+
+    pub async fn scan_row_data_with_retry(&self, table: &str) -> Result<Vec<(RowKey, RowData)>> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client().map_err(backoff_error)?;
+            client.scan_row_data(table).await.map_err(backoff_error)
+        })
+            .await
+    }
+
+    pub async fn scan_row_keys_with_retry(
+        &self,
+        table: &str,
+        start_at: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RowKey>> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client().map_err(backoff_error)?;
+            client.scan_row_keys(table, start_at, limit).await.map_err(backoff_error)
+        })
+            .await
+    }
+
+    pub async fn delete_rows_with_retry(
+        &self,
+        table: &str,
+        row_keys: &[RowKey],
+        use_wal: bool,
+    ) -> Result<()> {
+        retry(ExponentialBackoff::default(), || async {
+            let mut client = self.client().map_err(backoff_error)?;
+            client.delete_rows(table, row_keys, use_wal).await.map_err(backoff_error)
+        })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        std::cell::Cell,
+        thrift::{ProtocolError, ProtocolErrorKind, TransportError, TransportErrorKind},
+    };
+
+    fn attempts_until_done(first_error: thrift::Error) -> (usize, Result<()>) {
+        let backoff = ExponentialBackoff {
+            initial_interval: Duration::from_millis(1),
+            max_elapsed_time: Some(Duration::from_secs(1)),
+            ..ExponentialBackoff::default()
+        };
+        let first_error = Cell::new(Some(first_error));
+        let attempts = Cell::new(0);
+        let result = backoff::retry(backoff, || {
+            attempts.set(attempts.get() + 1);
+            match first_error.take() {
+                Some(err) => Err(backoff_error(err.into())),
+                None => Ok(()),
+            }
+        })
+            .map_err(|err| match err {
+                backoff::Error::Permanent(err) | backoff::Error::Transient { err, .. } => err,
+            });
+        (attempts.get(), result)
+    }
+
+    #[test]
+    fn test_backoff_error() {
+        // The server closing the connection mid-request, e.g. while restarting
+        let eof = thrift::Error::Transport(TransportError::new(TransportErrorKind::EndOfFile, "eof"));
+        let (attempts, result) = attempts_until_done(eof);
+        assert_eq!(attempts, 2);
+        assert!(result.is_ok());
+
+        let bad_version = thrift::Error::Protocol(ProtocolError::new(ProtocolErrorKind::BadVersion, "bad version"));
+        let (attempts, result) = attempts_until_done(bad_version);
+        assert_eq!(attempts, 1);
+        assert!(matches!(result, Err(Error::ProtocolMismatch(_))));
+    }
+}
@@ -0,0 +1,56 @@
+use {
+    crate::hbase::{Error, Result, RowDataSlice},
+    log::*,
+    solana_storage_utils::cell_envelope::{unwrap_cell, CellError},
+};
+
+pub(crate) fn deserialize_protobuf_cell_data<T>(
+    row_data: RowDataSlice,
+    table: &str,
+    key: &str,
+) -> Result<T>
+where
+    T: prost::Message + Default,
+{
+    let data = cell_data(row_data, "x:proto", table, key)?;
+    T::decode(&data[..]).map_err(|err| {
+        warn!("Failed to deserialize {}/{}: {}", table, key, err);
+        Error::ObjectCorrupt(format!("{table}/{key}"))
+    })
+}
+
+pub(crate) fn deserialize_bincode_cell_data<T>(
+    row_data: RowDataSlice,
+    table: &str,
+    key: &str,
+) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let data = cell_data(row_data, "x:bin", table, key)?;
+    bincode::deserialize(&data).map_err(|err| {
+        warn!("Failed to deserialize {}/{}: {}", table, key, err);
+        Error::ObjectCorrupt(format!("{table}/{key}"))
+    })
+}
+
+fn cell_data(row_data: RowDataSlice, cell_name: &str, table: &str, key: &str) -> Result<Vec<u8>> {
+    let value = &row_data
+        .iter()
+        .find(|(name, _)| name == cell_name)
+        .ok_or_else(|| Error::ObjectNotFound(format!("{table}/{key}")))?
+        .1;
+
+    match unwrap_cell(value) {
+        Ok((_, data)) => Ok(data),
+        Err(CellError::Io(err)) => Err(Error::Io(err)),
+        Err(err @ CellError::ChecksumMismatch { .. }) => {
+            warn!("Failed to verify {}/{}: {}", table, key, err);
+            Err(Error::ChecksumMismatch(format!("{table}/{key}")))
+        }
+        Err(err) => {
+            warn!("Failed to unwrap {}/{}: {}", table, key, err);
+            Err(Error::ObjectCorrupt(format!("{table}/{key}")))
+        }
+    }
+}
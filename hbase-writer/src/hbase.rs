@@ -1,29 +1,29 @@
 use {
+    solana_hbase_common::transport::{
+        self,
+        is_protocol_mismatch,
+        InputProtocol,
+        OutputProtocol,
+    },
     solana_storage_utils::{
-        compression::{compress_best, compress, CompressionMethod},
+        cell_envelope::{wrap_cell, PayloadEncoding},
+        compression::{compress_with_policy, CompressionPolicy},
     },
     log::*,
     std::{
+        collections::BTreeMap,
         time::{
             Duration,
         },
     },
     thiserror::Error,
     hbase_thrift::hbase::{
-        BatchMutation, HbaseSyncClient, THbaseSyncClient,
+        AlreadyExists, BatchMutation, ColumnDescriptor, HbaseSyncClient, Mutation, TRegionInfo,
+        TScan, THbaseSyncClient,
     },
     hbase_thrift::{
         MutationBuilder
     },
-    thrift::{
-        protocol::{
-            TBinaryInputProtocol, TBinaryOutputProtocol,
-        },
-        transport::{
-            TBufferedReadTransport, TBufferedWriteTransport,
-            TTcpChannel
-        },
-    },
 };
 
 pub type RowKey = String;
@@ -56,11 +56,20 @@ pub enum Error {
     #[error("Object is corrupt: {0}")]
     ObjectCorrupt(String),
 
+    #[error("Object checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+
     #[error("Timeout")]
     Timeout,
 
     #[error("Thrift")]
     Thrift(thrift::Error),
+
+    #[error("Thrift protocol mismatch: {0}; check that the transport (buffered/framed), protocol (binary/compact) and TLS settings match the HBase Thrift server")]
+    ProtocolMismatch(String),
+
+    #[error("TLS: {0}")]
+    Tls(String),
 }
 
 impl std::convert::From<std::io::Error> for Error {
@@ -71,18 +80,26 @@ impl std::convert::From<std::io::Error> for Error {
 
 impl std::convert::From<thrift::Error> for Error {
     fn from(err: thrift::Error) -> Self {
-        Self::Thrift(err)
+        if is_protocol_mismatch(&err) {
+            Self::ProtocolMismatch(err.to_string())
+        } else {
+            Self::Thrift(err)
+        }
     }
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
-
+impl From<transport::Error> for Error {
+    fn from(err: transport::Error) -> Self {
+        match err {
+            transport::Error::Io(err) => err.into(),
+            transport::Error::Thrift(err) => err.into(),
+            transport::Error::Tls(err) => Self::Tls(err),
+        }
+    }
+}
 
-type InputTransport = TBufferedReadTransport<thrift::transport::ReadHalf<TTcpChannel>>;
-type OutputTransport = TBufferedWriteTransport<thrift::transport::WriteHalf<TTcpChannel>>;
+pub type Result<T> = std::result::Result<T, Error>;
 
-type InputProtocol = TBinaryInputProtocol<InputTransport>;
-type OutputProtocol = TBinaryOutputProtocol<OutputTransport>;
 
 pub struct HBase {
     pub client: HbaseSyncClient<InputProtocol, OutputProtocol>,
@@ -94,26 +111,15 @@ impl HBase {
         &mut self,
         table: &str,
         cells: &[(RowKey, T)],
-        use_compression: bool,
+        compression: &CompressionPolicy,
+        use_envelope: bool,
         use_wal: bool,
     ) -> Result<usize>
     where
         T: serde::ser::Serialize,
     {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let serialized_data = bincode::serialize(&data).unwrap();
-
-            let data = if use_compression {
-                compress_best(&serialized_data)?
-            } else {
-                compress(CompressionMethod::NoCompression, &serialized_data)?
-            };
-
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("bin".to_string(), data)]));
-        }
+        let new_row_data = encode_bincode_cells(cells, compression, use_envelope)?;
+        let bytes_written = row_data_size(&new_row_data);
 
         self.put_row_data(table, "x", &new_row_data, use_wal).await?;
         Ok(bytes_written)
@@ -123,37 +129,25 @@ impl HBase {
         &mut self,
         table: &str,
         cells: &[(RowKey, T)],
-        use_compression: bool,
+        compression: &CompressionPolicy,
+        use_envelope: bool,
         use_wal: bool,
     ) -> Result<usize>
     where
         T: prost::Message,
     {
-        let mut bytes_written = 0;
-        let mut new_row_data = vec![];
-        for (row_key, data) in cells {
-            let mut buf = Vec::with_capacity(data.encoded_len());
-            data.encode(&mut buf).unwrap();
-
-            let data = if use_compression {
-                compress_best(&buf)?
-            } else {
-                compress(CompressionMethod::NoCompression, &buf)?
-            };
-
-            bytes_written += data.len();
-            new_row_data.push((row_key, vec![("proto".to_string(), data)]));
-        }
+        let new_row_data = encode_protobuf_cells(cells, compression, use_envelope)?;
+        let bytes_written = row_data_size(&new_row_data);
 
         self.put_row_data(table, "x", &new_row_data, use_wal).await?;
         Ok(bytes_written)
     }
 
-    async fn put_row_data(
+    pub async fn put_row_data(
         &mut self,
         table_name: &str,
         family_name: &str,
-        row_data: &[(&RowKey, RowData)],
+        row_data: &[(RowKey, RowData)],
         use_wal: bool,
     ) -> Result<()> {
         let mut mutation_batches = Vec::new();
@@ -173,5 +167,271 @@ impl HBase {
 
         Ok(())
     }
+
+    /// Latest cells of the `x` family of the existing rows among `row_keys`. Cell names include
+    /// the family, e.g. `x:proto`.
+    pub async fn get_row_data(
+        &mut self,
+        table_name: &str,
+        row_keys: &[RowKey],
+    ) -> Result<Vec<(RowKey, RowData)>> {
+        let rows = self.client.get_rows_with_columns(
+            table_name.as_bytes().to_vec(),
+            row_keys.iter().map(|row_key| row_key.as_bytes().to_vec()).collect(),
+            vec![b"x".to_vec()],
+            BTreeMap::new(),
+        )?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let row_key = String::from_utf8(row.row?).ok()?;
+                let row_data = row
+                    .columns
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|(name, cell)| {
+                        Some((String::from_utf8(name).ok()?, cell.value?))
+                    })
+                    .collect();
+                Some((row_key, row_data))
+            })
+            .collect())
+    }
+
+    /// Delete the `x` family, and so all the data, of one or more `table` rows.
+    pub async fn delete_rows(
+        &mut self,
+        table_name: &str,
+        row_keys: &[RowKey],
+        use_wal: bool,
+    ) -> Result<()> {
+        let mutation_batches = row_keys
+            .iter()
+            .map(|row_key| {
+                // A column without a qualifier deletes the whole family, which `MutationBuilder`
+                // can not express.
+                let mutation = Mutation {
+                    column: Some(b"x".to_vec()),
+                    value: None,
+                    is_delete: Some(true),
+                    write_to_w_a_l: Some(use_wal),
+                };
+                BatchMutation::new(Some(row_key.as_bytes().to_vec()), vec![mutation])
+            })
+            .collect();
+
+        self.client.mutate_rows(table_name.as_bytes().to_vec(), mutation_batches, Default::default())?;
+
+        Ok(())
+    }
+
+    pub async fn get_table_names(&mut self) -> Result<Vec<String>> {
+        let table_names = self.client.get_table_names()?;
+
+        Ok(table_names
+            .into_iter()
+            .map(|name| String::from_utf8_lossy(&name).to_string())
+            .collect())
+    }
+
+    /// Create `table_name` with the given column families. Returns `false` if the table already
+    /// exists.
+    pub async fn create_table(
+        &mut self,
+        table_name: &str,
+        column_families: Vec<ColumnDescriptor>,
+    ) -> Result<bool> {
+        match self.client.create_table(table_name.as_bytes().to_vec(), column_families) {
+            Ok(()) => Ok(true),
+            Err(thrift::Error::User(err)) if err.downcast_ref::<AlreadyExists>().is_some() => {
+                Ok(false)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Column families of `table_name`, keyed by family name without the trailing `:`.
+    pub async fn get_column_descriptors(
+        &mut self,
+        table_name: &str,
+    ) -> Result<BTreeMap<String, ColumnDescriptor>> {
+        let descriptors = self.client.get_column_descriptors(table_name.as_bytes().to_vec())?;
+
+        Ok(descriptors
+            .into_iter()
+            .map(|(name, descriptor)| {
+                let name = String::from_utf8_lossy(&name);
+                (name.trim_end_matches(':').to_string(), descriptor)
+            })
+            .collect())
+    }
+
+    pub async fn get_table_regions(&mut self, table_name: &str) -> Result<Vec<TRegionInfo>> {
+        Ok(self.client.get_table_regions(table_name.as_bytes().to_vec())?)
+    }
+
+    /// Latest cells of the `x` family of all the rows of `table_name`, for tables small enough to
+    /// read whole.
+    pub async fn scan_row_data(&mut self, table_name: &str) -> Result<Vec<(RowKey, RowData)>> {
+        let batch_size = 1000;
+        let scan = TScan {
+            columns: Some(vec![b"x".to_vec()]),
+            caching: Some(batch_size),
+            ..TScan::default()
+        };
+        let scan_id = self.client.scanner_open_with_scan(
+            table_name.as_bytes().to_vec(),
+            scan,
+            BTreeMap::new(),
+        )?;
+
+        let mut row_data = vec![];
+        loop {
+            let rows = self.client.scanner_get_list(scan_id, batch_size)?;
+            if rows.is_empty() {
+                break;
+            }
+            row_data.extend(rows.into_iter().filter_map(|row| {
+                let row_key = String::from_utf8(row.row?).ok()?;
+                let cells = row
+                    .columns
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|(name, cell)| Some((String::from_utf8(name).ok()?, cell.value?)))
+                    .collect();
+                Some((row_key, cells))
+            }));
+        }
+
+        self.client.scanner_close(scan_id)?;
+
+        Ok(row_data)
+    }
+
+    /// Up to `limit` row keys of `table_name`, from `start_at` on.
+    pub async fn scan_row_keys(
+        &mut self,
+        table_name: &str,
+        start_at: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<RowKey>> {
+        let batch_size = limit.clamp(1, 1000) as i32;
+        let scan = TScan {
+            start_row: start_at.map(|row_key| row_key.as_bytes().to_vec()),
+            caching: Some(batch_size),
+            filter_string: Some(b"KeyOnlyFilter() AND FirstKeyOnlyFilter()".to_vec()),
+            ..TScan::default()
+        };
+        let scan_id = self.client.scanner_open_with_scan(
+            table_name.as_bytes().to_vec(),
+            scan,
+            BTreeMap::new(),
+        )?;
+
+        let mut row_keys = vec![];
+        while row_keys.len() < limit {
+            let rows = self.client.scanner_get_list(scan_id, batch_size)?;
+            if rows.is_empty() {
+                break;
+            }
+            row_keys.extend(rows.into_iter().filter_map(|row| String::from_utf8(row.row?).ok()));
+        }
+        row_keys.truncate(limit);
+
+        self.client.scanner_close(scan_id)?;
+
+        Ok(row_keys)
+    }
+
+    /// Cell values of the first `limit` cells of the `x` family of `table_name`.
+    pub async fn get_cell_values(&mut self, table_name: &str, limit: usize) -> Result<Vec<CellValue>> {
+        let batch_size = limit.clamp(1, 1000) as i32;
+        let scan = TScan {
+            columns: Some(vec![b"x".to_vec()]),
+            caching: Some(batch_size),
+            ..TScan::default()
+        };
+        let scan_id = self.client.scanner_open_with_scan(
+            table_name.as_bytes().to_vec(),
+            scan,
+            BTreeMap::new(),
+        )?;
+
+        let mut values = vec![];
+        while values.len() < limit {
+            let rows = self.client.scanner_get_list(scan_id, batch_size)?;
+            if rows.is_empty() {
+                break;
+            }
+            for row in rows {
+                values.extend(
+                    row.columns
+                        .unwrap_or_default()
+                        .into_values()
+                        .filter_map(|cell| cell.value),
+                );
+            }
+        }
+        values.truncate(limit);
+
+        self.client.scanner_close(scan_id)?;
+
+        Ok(values)
+    }
 }
 
+/// Serialize and compress `cells` into the `bin` cells of their rows.
+pub fn encode_bincode_cells<T>(
+    cells: &[(RowKey, T)],
+    compression: &CompressionPolicy,
+    use_envelope: bool,
+) -> Result<Vec<(RowKey, RowData)>>
+where
+    T: serde::ser::Serialize,
+{
+    let mut row_data = vec![];
+    for (row_key, data) in cells {
+        let serialized_data = bincode::serialize(&data).unwrap();
+
+        let mut data = compress_with_policy(compression, &serialized_data)?;
+        if use_envelope {
+            data = wrap_cell(PayloadEncoding::Bincode, &data);
+        }
+
+        row_data.push((row_key.clone(), vec![("bin".to_string(), data)]));
+    }
+    Ok(row_data)
+}
+
+/// Encode and compress `cells` into the `proto` cells of their rows.
+pub fn encode_protobuf_cells<T>(
+    cells: &[(RowKey, T)],
+    compression: &CompressionPolicy,
+    use_envelope: bool,
+) -> Result<Vec<(RowKey, RowData)>>
+where
+    T: prost::Message,
+{
+    let mut row_data = vec![];
+    for (row_key, data) in cells {
+        let mut buf = Vec::with_capacity(data.encoded_len());
+        data.encode(&mut buf).unwrap();
+
+        let mut data = compress_with_policy(compression, &buf)?;
+        if use_envelope {
+            data = wrap_cell(PayloadEncoding::Protobuf, &data);
+        }
+
+        row_data.push((row_key.clone(), vec![("proto".to_string(), data)]));
+    }
+    Ok(row_data)
+}
+
+/// Bytes of the cell values of `row_data`.
+pub fn row_data_size(row_data: &[(RowKey, RowData)]) -> usize {
+    row_data
+        .iter()
+        .flat_map(|(_, cells)| cells.iter().map(|(_, value)| value.len()))
+        .sum()
+}
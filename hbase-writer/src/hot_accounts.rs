@@ -0,0 +1,197 @@
+//! Suppression of the addresses in too many transactions to be worth indexing in `tx-by-addr`.
+//!
+//! `HotAccountTracker` counts the transactions of each address over the last `window_slots`
+//! slots of the uploaded blocks. An address in more than `max_transactions` of them stops being
+//! indexed, and is indexed again once it is in fewer than `resume_transactions`. The changes are
+//! recorded in the table described in `solana_storage_utils::hot_accounts`, from which the
+//! suppressed addresses are restored when the uploader restarts. They are only indexed again once
+//! the uploader counted their transactions over a whole window.
+
+use {
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_storage_utils::hot_accounts::SuppressionChange,
+    std::collections::{BTreeMap, HashMap, HashSet},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotAccountPolicy {
+    /// Slots the transactions of each address are counted over
+    pub window_slots: u64,
+    pub max_transactions: u64,
+    /// Lower than `max_transactions`, so an address around the threshold doesn't flap
+    pub resume_transactions: u64,
+}
+
+impl Default for HotAccountPolicy {
+    fn default() -> Self {
+        Self {
+            window_slots: 150,
+            max_transactions: 50_000,
+            resume_transactions: 25_000,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HotAccountTracker {
+    policy: HotAccountPolicy,
+    /// Transactions of each address in the blocks of the window
+    blocks: BTreeMap<Slot, HashMap<Pubkey, u64>>,
+    totals: HashMap<Pubkey, u64>,
+    suppressed: HashSet<Pubkey>,
+    /// First slot counted, suppressions end once the window starts after it
+    first_slot: Option<Slot>,
+    restored: bool,
+}
+
+impl HotAccountTracker {
+    pub fn new(policy: HotAccountPolicy) -> Self {
+        Self {
+            policy,
+            blocks: BTreeMap::new(),
+            totals: HashMap::new(),
+            suppressed: HashSet::new(),
+            first_slot: None,
+            restored: false,
+        }
+    }
+
+    /// Whether the suppressed addresses were restored from the recorded changes.
+    pub fn is_restored(&self) -> bool {
+        self.restored
+    }
+
+    pub fn restore(&mut self, suppressed: impl IntoIterator<Item = Pubkey>) {
+        self.suppressed.extend(suppressed);
+        self.restored = true;
+    }
+
+    pub fn is_suppressed(&self, address: &Pubkey) -> bool {
+        self.suppressed.contains(address)
+    }
+
+    /// Count the transactions of each address in the block at `slot`, and return the addresses
+    /// whose suppression changes from that block on. Blocks older than the window are not
+    /// counted, an uploaded again block replaces its previous counts.
+    pub fn add_block(
+        &mut self,
+        slot: Slot,
+        transactions: HashMap<Pubkey, u64>,
+    ) -> Vec<(Pubkey, SuppressionChange)> {
+        let newest_slot = self.blocks.keys().next_back().copied().unwrap_or(slot).max(slot);
+        let window_start = newest_slot.saturating_sub(self.policy.window_slots.saturating_sub(1));
+        if slot < window_start {
+            return vec![];
+        }
+
+        let first_slot = *self.first_slot.get_or_insert(slot);
+        let can_resume = window_start > first_slot;
+
+        let mut touched: HashSet<Pubkey> = transactions.keys().copied().collect();
+        if let Some(previous) = self.blocks.remove(&slot) {
+            touched.extend(self.subtract(previous));
+        }
+        for (address, count) in &transactions {
+            *self.totals.entry(*address).or_default() += count;
+        }
+        self.blocks.insert(slot, transactions);
+
+        if can_resume {
+            touched.extend(self.suppressed.iter().copied());
+        }
+
+        let evicted: Vec<Slot> = self.blocks.range(..window_start).map(|(slot, _)| *slot).collect();
+        for evicted_slot in evicted {
+            if let Some(block) = self.blocks.remove(&evicted_slot) {
+                touched.extend(self.subtract(block));
+            }
+        }
+
+        let mut changes: Vec<_> = touched
+            .into_iter()
+            .filter_map(|address| {
+                let window_transactions = self.totals.get(&address).copied().unwrap_or_default();
+                let suppressed = if self.suppressed.contains(&address) {
+                    !can_resume || window_transactions >= self.policy.resume_transactions
+                } else {
+                    window_transactions > self.policy.max_transactions
+                };
+                if suppressed == self.suppressed.contains(&address) {
+                    return None;
+                }
+                if suppressed {
+                    self.suppressed.insert(address);
+                } else {
+                    self.suppressed.remove(&address);
+                }
+                Some((
+                    address,
+                    SuppressionChange {
+                        suppressed,
+                        window_transactions,
+                    },
+                ))
+            })
+            .collect();
+        changes.sort_by_key(|(address, _)| *address);
+        changes
+    }
+
+    /// Remove the counts of a block from the totals, returning its addresses.
+    fn subtract(&mut self, block: HashMap<Pubkey, u64>) -> impl Iterator<Item = Pubkey> {
+        for (address, count) in &block {
+            if let Some(total) = self.totals.get_mut(address) {
+                *total = total.saturating_sub(*count);
+                if *total == 0 {
+                    self.totals.remove(address);
+                }
+            }
+        }
+        block.into_keys()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hot_account_tracker() {
+        let hot = Pubkey::new_unique();
+        let cold = Pubkey::new_unique();
+        let mut tracker = HotAccountTracker::new(HotAccountPolicy {
+            window_slots: 3,
+            max_transactions: 10,
+            resume_transactions: 5,
+        });
+        let restored = Pubkey::new_unique();
+        tracker.restore([restored]);
+        let block = |hot_count, cold_count| HashMap::from([(hot, hot_count), (cold, cold_count)]);
+        let change = |suppressed, window_transactions| SuppressionChange {
+            suppressed,
+            window_transactions,
+        };
+
+        assert!(tracker.add_block(1, block(6, 1)).is_empty());
+        assert!(tracker.add_block(3, HashMap::from([(restored, 1)])).is_empty());
+        assert_eq!(tracker.add_block(2, block(6, 1)), vec![(hot, change(true, 12))]);
+        assert!(tracker.is_suppressed(&hot) && !tracker.is_suppressed(&cold));
+
+        // Slot 1 leaves the window, so a whole window was counted, but 8 transactions are still
+        // above the resume threshold
+        assert_eq!(
+            tracker.add_block(4, block(2, 1)),
+            vec![(restored, change(false, 1))]
+        );
+        // Too old to count
+        assert!(tracker.add_block(1, block(100, 100)).is_empty());
+        // Slot 2 leaves the window
+        assert_eq!(tracker.add_block(5, block(1, 1)), vec![(hot, change(false, 3))]);
+        assert!(!tracker.is_suppressed(&hot));
+
+        // Uploading a block again replaces its counts
+        assert_eq!(tracker.add_block(5, block(9, 1)), vec![(hot, change(true, 11))]);
+        assert_eq!(tracker.add_block(5, block(1, 1)), vec![(hot, change(false, 3))]);
+    }
+}
@@ -2,9 +2,21 @@
 
 use {
     crate::{
-        hbase::Error as HBaseError,
+        address_filter::{AddressFilterError, AddressFilters},
+        hbase::{
+            encode_bincode_cells,
+            encode_protobuf_cells,
+            row_data_size,
+            Error as HBaseError,
+            RowData,
+            RowDataSlice,
+            RowKey,
+        },
         connection::HBaseConnection,
+        deserializer::{deserialize_bincode_cell_data, deserialize_protobuf_cell_data},
+        hot_accounts::HotAccountTracker,
         tx_cache::cache_transaction,
+        tx_filter_rules::{RuleTarget, TxFacts},
         tx_utils::{
             get_account_keys,
             is_error_tx,
@@ -12,8 +24,10 @@ use {
             is_program_account,
             convert_to_transaction_with_status_meta,
         },
+        schema::{self, uploader_tables, SchemaReport},
+        schema_config::SchemaConfig,
         storage_config::LedgerStorageConfig,
-        uploader_config::UploaderConfig,
+        uploader_config::{FilterTxIncludeExclude, UploaderConfig},
         tx_cache::CacheWriteError,
     },
     async_trait::async_trait,
@@ -24,10 +38,14 @@ use {
     // solana_metrics::{datapoint_info, inc_new_counter_debug},
     solana_clock::{
         Slot,
+        UnixTimestamp,
     },
     solana_pubkey::{
         Pubkey,
     },
+    solana_signature::{
+        Signature,
+    },
     agave_reserved_account_keys::ReservedAccountKeys,
     dexter_storage_proto_tx::convert::{
         generated
@@ -37,30 +55,59 @@ use {
     },
     solana_transaction_status::{
         extract_memos::extract_and_fmt_memos,
+        ConfirmedBlock,
         ConfirmedTransactionWithStatusMeta,
         TransactionByAddrInfo,
         VersionedConfirmedBlock,
         VersionedTransactionWithStatusMeta,
     },
     solana_storage_writer::{
+        DeleteReport,
         Error as StorageError,
         LedgerStorageAdapter,
+        TableUploadStats,
+        UploadReport,
     },
     solana_storage_utils::{
+        balance_history::{balance_changes, BalanceChange},
+        token_balance_history::{token_balance_changes, TokenBalanceChange},
+        block_rows::block_row_keys,
+        block_index::block_index_cells,
+        block_stats::{BlockStats, BlockStatsBuilder},
+        leader_index::leader_index_cells,
+        memo_index::memo_index_cells,
+        hot_accounts::{suppression_key, SuppressionChange},
+        rewards::{rewards_cells, RewardCell},
+        slot_index::slot_index_cell,
+        block_time::{block_time_to_key, slot_to_block_time_key},
+        compression::{CompressionMode, CompressionPolicy},
+        program_index::invoked_programs,
+        token_index::token_balance_accounts,
         tx_info::TransactionInfo,
+        zstd_dictionary::{get_dictionary, load_dictionary},
+        blocks_key_to_slot,
         slot_to_blocks_key,
+        slot_to_key,
         slot_to_tx_by_addr_key,
+        salt_tx_by_addr_key,
+        tx_by_addr_key,
     },
     std::{
         collections::{
+            BTreeMap,
+            BTreeSet,
             HashMap,
+            HashSet,
         },
         boxed::Box,
+        sync::{Arc, Mutex, RwLock},
+        time::{Duration, Instant},
     },
     thiserror::Error,
     memcache::{Client, MemcacheError},
     tokio::{
-        task::JoinError,
+        signal::unix::{signal, SignalKind},
+        task::{JoinError, JoinHandle},
     },
 };
 
@@ -119,6 +166,17 @@ impl From<CacheWriteError> for TaskError {
     }
 }
 
+impl From<TaskError> for StorageError {
+    fn from(err: TaskError) -> Self {
+        match err {
+            TaskError::HBaseError(hbase_err) => StorageError::StorageBackendError(Box::new(hbase_err)),
+            TaskError::MemcacheError(memcache_err) => StorageError::CacheError(Box::new(memcache_err)),
+            TaskError::IoError(io_err) => StorageError::IoError(io_err),
+            TaskError::EncodingError(enc_err) => StorageError::EncodingError(enc_err),
+        }
+    }
+}
+
 impl From<TaskError> for Error {
     fn from(err: TaskError) -> Self {
         match err {
@@ -132,9 +190,22 @@ impl From<TaskError> for Error {
 
 pub type Result<T> = std::result::Result<T, StorageError>;
 
+/// Rows read per request when looking up the rows derived from a block.
+const GET_ROWS_BATCH_SIZE: usize = 1000;
+
+/// Rows written per request when uploading several blocks at once.
+const PUT_ROWS_BATCH_SIZE: usize = 10_000;
+
+/// `blocks` row keys read per request when backfilling the slot index.
+const SCAN_ROW_KEYS_BATCH_SIZE: usize = 10_000;
+
+/// Slot a `tx` or `tx_full` row belongs to, read from its cell.
+type RowSlotFn = fn(RowDataSlice, &str, &str) -> std::result::Result<Slot, HBaseError>;
+
 enum TaskResult {
-    BytesWritten(usize),
-    CachedTransactions(usize),
+    /// Stats of each block written to the table.
+    TableWritten(String, Vec<TableUploadStats>),
+    CachedTransactions(Vec<usize>),
 }
 
 #[derive(Debug)]
@@ -168,6 +239,9 @@ impl From<MemcacheError> for TaskError {
 pub struct LedgerStorage {
     connection: HBaseConnection,
     uploader_config: UploaderConfig,
+    /// Swapped as a whole when the address filter files are reloaded.
+    address_filters: Arc<RwLock<Arc<AddressFilters>>>,
+    hot_accounts: Option<Arc<Mutex<HotAccountTracker>>>,
     cache_client: Option<Client>,
     enable_full_tx_cache: bool,
     tx_cache_expiration: Option<std::time::Duration>,
@@ -193,11 +267,31 @@ impl LedgerStorage {
             address,
             uploader_config,
             cache_config,
+            thrift_config,
+            zstd_dictionaries,
         } = config;
+        for path in &zstd_dictionaries {
+            let id = load_dictionary(path)
+                .unwrap_or_else(|err| panic!("Failed to load zstd dictionary {}: {}", path, err));
+            info!("Loaded zstd dictionary {} from {}", id, path);
+        }
+        for policy in [
+            uploader_config.blocks_compression_policy(),
+            uploader_config.tx_compression_policy(),
+            uploader_config.tx_by_addr_compression_policy(),
+            uploader_config.tx_full_compression_policy(),
+        ] {
+            if let CompressionMode::ZstdDictionary { id, .. } = policy.mode {
+                get_dictionary(id).unwrap_or_else(|err| {
+                    panic!("Compression mode {} needs --zstd-dictionary: {}", policy.mode, err)
+                });
+            }
+        }
         let connection = HBaseConnection::new(
             address.as_str(),
             read_only,
             timeout,
+            thrift_config,
         )
             .await;
 
@@ -209,31 +303,341 @@ impl LedgerStorage {
             None
         };
 
-        Self {
+        let address_filters = AddressFilters::load(&uploader_config)
+            .unwrap_or_else(|err| panic!("Failed to load address filters: {}", err));
+
+        let hot_accounts = uploader_config
+            .hot_account_policy
+            .map(|policy| Arc::new(Mutex::new(HotAccountTracker::new(policy))));
+
+        let storage = Self {
             connection,
             uploader_config,
+            address_filters: Arc::new(RwLock::new(Arc::new(address_filters))),
+            hot_accounts,
             cache_client,
             enable_full_tx_cache: cache_config.enable_full_tx_cache,
             tx_cache_expiration: cache_config.tx_cache_expiration,
+        };
+        storage.spawn_address_filter_reloads();
+        storage
+    }
+
+    /// Address filters in effect.
+    pub fn address_filters(&self) -> Arc<AddressFilters> {
+        self.address_filters.read().unwrap().clone()
+    }
+
+    /// Reload the files of the address filters and swap in the new lists. The current lists are
+    /// kept if any file fails to load.
+    pub fn reload_address_filters(&self) -> std::result::Result<(), AddressFilterError> {
+        let address_filters = AddressFilters::load(&self.uploader_config)?;
+        let num_addresses = |filter: &Option<FilterTxIncludeExclude>| {
+            filter.as_ref().map_or(0, |filter| filter.addrs.len())
+        };
+        info!(
+            "Reloaded address filters: {} tx_full addresses, {} tx-by-addr addresses",
+            num_addresses(&address_filters.tx_full),
+            num_addresses(&address_filters.tx_by_addr),
+        );
+        *self.address_filters.write().unwrap() = Arc::new(address_filters);
+        Ok(())
+    }
+
+    /// Restore the addresses suppressed by the hot account policy from the last recorded change
+    /// of each address, once before the first upload.
+    async fn restore_hot_accounts(&self) -> Result<()> {
+        let Some(hot_accounts) = &self.hot_accounts else {
+            return Ok(());
+        };
+        if hot_accounts.lock().unwrap().is_restored() {
+            return Ok(());
         }
+
+        let table = &self.uploader_config.suppressed_addresses_table_name;
+        let row_data = self.connection
+            .scan_row_data_with_retry(table)
+            .await
+            .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+
+        // Rows of an address are ordered by slot, so the last one is the current state
+        let mut last_changes: HashMap<Pubkey, bool> = HashMap::new();
+        for (row_key, row_data) in row_data {
+            let change: SuppressionChange = deserialize_bincode_cell_data(&row_data, table, &row_key)
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+            match row_key.split_once('/').and_then(|(address, _)| address.parse().ok()) {
+                Some(address) => {
+                    last_changes.insert(address, change.suppressed);
+                }
+                None => warn!("Ignoring {}/{}, its address is invalid", table, row_key),
+            }
+        }
+        let suppressed: Vec<Pubkey> = last_changes
+            .into_iter()
+            .filter_map(|(address, suppressed)| suppressed.then_some(address))
+            .collect();
+        info!("HBase: restored {} addresses suppressed as hot accounts", suppressed.len());
+
+        hot_accounts.lock().unwrap().restore(suppressed);
+        Ok(())
     }
-}
 
-#[async_trait]
-impl LedgerStorageAdapter for LedgerStorage {
-    async fn upload_confirmed_block(
+    /// Write the `slot-index` rows of the blocks uploaded before the slot index was, found by
+    /// scanning the `blocks` row keys. Rows already in the index are written again unchanged.
+    /// Returns the number of blocks indexed.
+    pub async fn backfill_slot_index(&self) -> Result<usize> {
+        let blocks_table = &self.uploader_config.blocks_table_name;
+        let table = &self.uploader_config.slot_index_table_name;
+        let mut start_at: Option<RowKey> = None;
+        let mut indexed = 0;
+        loop {
+            let row_keys = self.connection
+                .scan_row_keys_with_retry(blocks_table, start_at.as_deref(), SCAN_ROW_KEYS_BATCH_SIZE)
+                .await
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+            let slots: Vec<Slot> = row_keys
+                .iter()
+                .filter_map(|row_key| {
+                    let slot = blocks_key_to_slot(row_key);
+                    if slot.is_none() {
+                        warn!("Ignoring {}/{}, its slot is invalid", blocks_table, row_key);
+                    }
+                    slot
+                })
+                .collect();
+
+            let row_data = slot_index_row_data(&slots, self.uploader_config.use_cell_envelope)
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+            for row_data in row_data.chunks(PUT_ROWS_BATCH_SIZE) {
+                self.connection
+                    .put_row_data_with_retry(table, row_data, self.uploader_config.hbase_write_to_wal)
+                    .await
+                    .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+            }
+            indexed += slots.len();
+            info!("HBase: indexed {} blocks in {}", indexed, table);
+
+            if row_keys.len() < SCAN_ROW_KEYS_BATCH_SIZE {
+                return Ok(indexed);
+            }
+            // The smallest key after the last one read
+            start_at = row_keys.last().map(|row_key| format!("{row_key}\0"));
+        }
+    }
+
+    fn spawn_address_filter_reloads(&self) {
+        let has_files = [&self.uploader_config.tx_full_filter, &self.uploader_config.tx_by_addr_filter]
+            .iter()
+            .any(|filter| filter.as_ref().is_some_and(|filter| filter.file.is_some()));
+        if !has_files {
+            return;
+        }
+
+        if let Some(period) = self.uploader_config.address_filter_reload_interval {
+            let storage = self.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                loop {
+                    interval.tick().await;
+                    if let Err(err) = storage.reload_address_filters() {
+                        warn!("Failed to reload address filters, keeping the current ones: {}", err);
+                    }
+                }
+            });
+        }
+
+        if self.uploader_config.reload_address_filters_on_sighup {
+            let mut hangups = signal(SignalKind::hangup())
+                .unwrap_or_else(|err| panic!("Failed to listen for SIGHUP: {}", err));
+            let storage = self.clone();
+            tokio::spawn(async move {
+                while hangups.recv().await.is_some() {
+                    info!("SIGHUP received, reloading address filters");
+                    if let Err(err) = storage.reload_address_filters() {
+                        warn!("Failed to reload address filters, keeping the current ones: {}", err);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Create the tables this storage writes to if they are missing, and validate the ones that
+    /// already exist against `schema_config`.
+    pub async fn ensure_schema(&self, schema_config: &SchemaConfig) -> Result<SchemaReport> {
+        let tables = uploader_tables(&self.uploader_config);
+        let mut client = self.connection.client()
+            .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+
+        schema::ensure_schema(&mut client, &tables, schema_config)
+            .await
+            .map_err(|err| StorageError::StorageBackendError(Box::new(err)))
+    }
+
+    /// Up to `limit` cells of `table` as stored, e.g. to train a zstd dictionary on them.
+    pub async fn sample_cells(&self, table: &str, limit: usize) -> Result<Vec<Vec<u8>>> {
+        let mut client = self.connection.client()
+            .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+
+        client.get_cell_values(table, limit)
+            .await
+            .map_err(|err| StorageError::StorageBackendError(Box::new(err)))
+    }
+
+    /// Existing rows among `row_keys`, split into the ones `row_slot` attributes to `slot` and
+    /// the ones it attributes to another slot. `row_slot` is not called if it is `None`.
+    async fn existing_rows(
         &self,
+        table: &str,
+        row_keys: &[String],
         slot: Slot,
-        confirmed_block: VersionedConfirmedBlock,
-    ) -> Result<()> {
-        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        row_slot: Option<RowSlotFn>,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let mut rows = vec![];
+        let mut other_slot_rows = vec![];
+        for row_keys in row_keys.chunks(GET_ROWS_BATCH_SIZE) {
+            let row_data = self.connection
+                .get_row_data_with_retry(table, row_keys)
+                .await
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+
+            for (row_key, row_data) in row_data {
+                let Some(row_slot) = row_slot else {
+                    rows.push(row_key);
+                    continue;
+                };
+                match row_slot(&row_data, table, &row_key) {
+                    Ok(row_slot) if row_slot == slot => rows.push(row_key),
+                    Ok(_) => other_slot_rows.push(row_key),
+                    Err(err) => {
+                        warn!("Keeping {}/{}, its slot is unknown: {}", table, row_key, err);
+                        other_slot_rows.push(row_key);
+                    }
+                }
+            }
+        }
+        Ok((rows, other_slot_rows))
+    }
+
+    fn should_include_in_tx_by_program(&self, program: &Pubkey) -> bool {
+        self.uploader_config
+            .tx_by_program_filter
+            .as_ref()
+            .is_none_or(|filter| filter.allows(program))
+    }
+
+    /// Spawn the write of the rows of several blocks to `table`, which is laid out like
+    /// `tx-by-addr`.
+    fn spawn_tx_by_addr_upload(
+        &self,
+        table: String,
+        tx_by_addr_cells: Vec<Vec<(RowKey, tx_by_addr::TransactionByAddr)>>,
+    ) -> JoinHandle<std::result::Result<TaskResult, TaskError>> {
+        let conn = self.connection.clone();
+        let tx_by_addr_compression = self.uploader_config.tx_by_addr_compression_policy();
+        let use_cell_envelope = self.uploader_config.use_cell_envelope;
+        let write_to_wal = self.uploader_config.hbase_write_to_wal;
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let row_data = tx_by_addr_cells
+                .iter()
+                .map(|cells| encode_protobuf_cells(cells, &tx_by_addr_compression, use_cell_envelope))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            put_blocks_row_data(&conn, table, row_data, write_to_wal, start).await
+        })
+    }
 
-        info!("HBase: Uploading block {:?} from slot {:?}", confirmed_block.blockhash, slot);
+    /// Spawn the write of the rows of several blocks to `table`, which is laid out like
+    /// `tx_full`.
+    fn spawn_full_tx_upload(
+        &self,
+        table: String,
+        full_tx_cells: Vec<Vec<(RowKey, generated::ConfirmedTransactionWithStatusMeta)>>,
+    ) -> JoinHandle<std::result::Result<TaskResult, TaskError>> {
+        let conn = self.connection.clone();
+        let tx_full_compression = self.uploader_config.tx_full_compression_policy();
+        let use_cell_envelope = self.uploader_config.use_cell_envelope;
+        let write_to_wal = self.uploader_config.hbase_write_to_wal;
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let row_data = full_tx_cells
+                .iter()
+                .map(|cells| encode_protobuf_cells(cells, &tx_full_compression, use_cell_envelope))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            put_blocks_row_data(&conn, table, row_data, write_to_wal, start).await
+        })
+    }
+
+    /// Spawn the write of the bincode rows of several blocks to `table`.
+    fn spawn_bincode_upload<T>(
+        &self,
+        table: String,
+        cells: Vec<Vec<(RowKey, T)>>,
+        compression: CompressionPolicy,
+    ) -> JoinHandle<std::result::Result<TaskResult, TaskError>>
+    where
+        T: serde::Serialize + Send + 'static,
+    {
+        let conn = self.connection.clone();
+        let use_cell_envelope = self.uploader_config.use_cell_envelope;
+        let write_to_wal = self.uploader_config.hbase_write_to_wal;
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let row_data = cells
+                .iter()
+                .map(|cells| encode_bincode_cells(cells, &compression, use_cell_envelope))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            put_blocks_row_data(&conn, table, row_data, write_to_wal, start).await
+        })
+    }
+
+    /// Add the transaction described by `info` to the rows of the token owners or mints in
+    /// `addresses`, unless it is already listed there as one of the transaction's accounts.
+    fn index_token_accounts(
+        &self,
+        addresses: BTreeSet<Pubkey>,
+        info: &TransactionByAddrInfo,
+        address_filters: &AddressFilters,
+        rows: &mut HashMap<Pubkey, Vec<TransactionByAddrInfo>>,
+        report: &mut UploadReport,
+    ) {
+        for address in addresses {
+            if !address_filters.include_in_tx_by_addr(&address) {
+                report.skipped.tx_by_addr_filter += 1;
+                continue;
+            }
+            let infos = rows.entry(address).or_default();
+            if infos.last().map(|last| last.index) != Some(info.index) {
+                infos.push(info.clone());
+            }
+        }
+    }
+
+    /// Rows the uploader derives from the block at `slot`.
+    fn block_cells(&self, slot: Slot, confirmed_block: VersionedConfirmedBlock) -> BlockCells {
+        let mut by_addr: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let mut by_owner: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let mut by_mint: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let mut by_program: HashMap<Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let mut balance_history: HashMap<Pubkey, Vec<BalanceChange>> = HashMap::new();
+        let mut token_balance_history: HashMap<Pubkey, Vec<TokenBalanceChange>> = HashMap::new();
+        let mut report = UploadReport::new(slot, confirmed_block.transactions.len());
+        let owner_table_name = self.uploader_config.token_owner_table_name();
+        let mint_table_name = self.uploader_config.token_mint_table_name();
+        let tx_by_addr_table_name = Some(self.uploader_config.tx_by_addr_table_name.as_str());
 
         let reserved_account_keys = ReservedAccountKeys::new_all_activated();
+        let address_filters = self.address_filters();
         let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
         let mut full_tx_cells = vec![];
+        let mut routed_by_addr: BTreeMap<String, HashMap<Pubkey, Vec<TransactionByAddrInfo>>> = BTreeMap::new();
+        let mut routed_full_tx_cells: BTreeMap<String, Vec<(RowKey, generated::ConfirmedTransactionWithStatusMeta)>> =
+            BTreeMap::new();
         let mut full_tx_cache = vec![];
+        let mut memo_cells = vec![];
+        let mut block_stats = self
+            .uploader_config
+            .enable_block_stats
+            .then(BlockStatsBuilder::default);
         for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
             let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
             let err = meta.status.clone().err();
@@ -247,34 +651,99 @@ impl LedgerStorageAdapter for LedgerStorage {
 
             let is_voting = is_voting_tx(transaction_with_meta);
 
+            if let Some(block_stats) = &mut block_stats {
+                block_stats.add_transaction(transaction_with_meta, is_voting);
+            }
+
             if self.uploader_config.filter_voting_tx && is_voting {
                 should_skip_tx_by_addr = true;
                 should_skip_full_tx = true;
+                report.skipped.voting_tx += 1;
+            }
+
+            let rule_facts = self
+                .uploader_config
+                .tx_filter_rules
+                .as_ref()
+                .map(|rules| (rules, TxFacts::new(transaction_with_meta, is_voting)));
+            let mut denied_by_rules = false;
+            let mut allowed_by_rules = |target| {
+                let allowed = rule_facts
+                    .as_ref()
+                    .is_none_or(|(rules, facts)| rules.allows(target, facts));
+                denied_by_rules |= !allowed;
+                allowed
+            };
+            let should_skip_tx = !allowed_by_rules(RuleTarget::Tx);
+            if !should_skip_tx_by_addr && !allowed_by_rules(RuleTarget::TxByAddr) {
+                should_skip_tx_by_addr = true;
+            }
+            if !should_skip_full_tx
+                && self.uploader_config.enable_full_tx
+                && !allowed_by_rules(RuleTarget::TxFull)
+            {
+                should_skip_full_tx = true;
+            }
+            let should_skip_cache = self.enable_full_tx_cache && !allowed_by_rules(RuleTarget::Cache);
+            if denied_by_rules {
+                report.skipped.filter_rules += 1;
             }
 
             let is_error = is_error_tx(transaction_with_meta);
 
-            if self.uploader_config.filter_error_tx && is_error {
+            if self.uploader_config.filter_error_tx && is_error && !should_skip_full_tx {
                 should_skip_full_tx = true;
+                report.skipped.error_tx += 1;
             }
 
             let combined_keys = get_account_keys(&transaction_with_meta);
 
+            let route = if self.uploader_config.tx_routes.is_empty() {
+                None
+            } else {
+                let programs = invoked_programs(transaction_with_meta);
+                let account_keys: Vec<Pubkey> = transaction_with_meta.account_keys().iter().copied().collect();
+                self.uploader_config
+                    .tx_routes
+                    .iter()
+                    .find(|route| route.matches(&programs, &account_keys))
+            };
+
+            if !should_skip_full_tx {
+                let excluded = transaction_with_meta.account_keys().iter().any(|address| {
+                    let is_filtered_program = self.uploader_config.filter_program_accounts
+                        && is_program_account(address, transaction_with_meta, &combined_keys);
+                    !is_filtered_program && !address_filters.include_in_tx_full(address)
+                });
+                if excluded {
+                    should_skip_full_tx = true;
+                    report.skipped.tx_full_filter_tx += 1;
+                }
+            }
+
             if !should_skip_tx_by_addr {
+                let tx_by_addr_rows = match route.and_then(|route| route.tx_by_addr_table_name.as_ref()) {
+                    Some(table) if *table != self.uploader_config.tx_by_addr_table_name => {
+                        routed_by_addr.entry(table.clone()).or_default()
+                    }
+                    _ => &mut by_addr,
+                };
                 for address in transaction_with_meta.account_keys().iter() {
                     // Filter program accounts from tx-by-addr index
                     if self.uploader_config.filter_program_accounts
                         && is_program_account(address, transaction_with_meta, &combined_keys) {
+                        report.skipped.program_accounts += 1;
                         continue;
                     }
 
-                    if should_skip_full_tx || !self.should_include_in_tx_full(address) {
-                        should_skip_full_tx = true;
+                    if reserved_account_keys.is_reserved(address) {
+                        continue;
                     }
-
-                    if !reserved_account_keys.is_reserved(address) && self.should_include_in_tx_by_addr(address) {
-                        by_addr
-                            .entry(address)
+                    if !address_filters.include_in_tx_by_addr(address) {
+                        report.skipped.tx_by_addr_filter += 1;
+                    } else {
+                        tx_by_addr_rows
+                            .entry(*address)
                             .or_default()
                             .push(TransactionByAddrInfo {
                                 signature,
@@ -285,12 +754,72 @@ impl LedgerStorageAdapter for LedgerStorage {
                             });
                     }
                 }
+
+                if owner_table_name.is_some() || mint_table_name.is_some() {
+                    let token_accounts = token_balance_accounts(meta);
+                    let info = TransactionByAddrInfo {
+                        signature,
+                        err: err.clone(),
+                        index,
+                        memo: memo.clone(),
+                        block_time: confirmed_block.block_time,
+                    };
+                    if owner_table_name.is_some() {
+                        let rows = if owner_table_name == tx_by_addr_table_name { &mut *tx_by_addr_rows } else { &mut by_owner };
+                        self.index_token_accounts(token_accounts.owners, &info, &address_filters, rows, &mut report);
+                    }
+                    if mint_table_name.is_some() {
+                        let rows = if mint_table_name == tx_by_addr_table_name { &mut *tx_by_addr_rows } else { &mut by_mint };
+                        self.index_token_accounts(token_accounts.mints, &info, &address_filters, rows, &mut report);
+                    }
+                }
+            }
+
+            if self.uploader_config.index_programs && !(self.uploader_config.filter_voting_tx && is_voting) {
+                for program in invoked_programs(transaction_with_meta) {
+                    if !self.should_include_in_tx_by_program(&program) {
+                        report.skipped.tx_by_program_filter += 1;
+                        continue;
+                    }
+                    by_program
+                        .entry(program)
+                        .or_default()
+                        .push(TransactionByAddrInfo {
+                            signature,
+                            err: err.clone(),
+                            index,
+                            memo: memo.clone(),
+                            block_time: confirmed_block.block_time,
+                        });
+                }
+            }
+
+            if self.uploader_config.enable_balance_history && !(self.uploader_config.filter_voting_tx && is_voting) {
+                for (address, balance_change) in balance_changes(transaction_with_meta, index) {
+                    balance_history.entry(address).or_default().push(balance_change);
+                }
+            }
+
+            if self.uploader_config.enable_memo_index && !(self.uploader_config.filter_voting_tx && is_voting) {
+                memo_cells.extend(memo_index_cells(slot, index, transaction_with_meta));
+            }
+
+            if self.uploader_config.enable_token_balance_history && !(self.uploader_config.filter_voting_tx && is_voting) {
+                for (owner, token_balance_change) in token_balance_changes(&transaction_with_meta.meta, index) {
+                    token_balance_history.entry(owner).or_default().push(token_balance_change);
+                }
             }
 
             if self.uploader_config.enable_full_tx && !should_skip_full_tx {
                 // should_skip_tx = true;
 
-                full_tx_cells.push((
+                let full_tx_rows = match route.and_then(|route| route.tx_full_table_name.as_ref()) {
+                    Some(table) if *table != self.uploader_config.full_tx_table_name => {
+                        routed_full_tx_cells.entry(table.clone()).or_default()
+                    }
+                    _ => &mut full_tx_cells,
+                };
+                full_tx_rows.push((
                     signature.to_string(),
                     ConfirmedTransactionWithStatusMeta {
                         slot,
@@ -301,6 +830,7 @@ impl LedgerStorageAdapter for LedgerStorage {
             }
 
             if self.enable_full_tx_cache
+                && !should_skip_cache
                 && !is_voting
                 && !transaction_with_meta.meta.status.is_err() {
                 full_tx_cache.push((
@@ -313,7 +843,7 @@ impl LedgerStorageAdapter for LedgerStorage {
                 ));
             }
 
-            if !self.uploader_config.disable_tx /*&& !should_skip_tx*/ {
+            if !self.uploader_config.disable_tx && !should_skip_tx {
                 tx_cells.push((
                     signature.to_string(),
                     TransactionInfo {
@@ -326,109 +856,498 @@ impl LedgerStorageAdapter for LedgerStorage {
             }
         }
 
-        let tx_by_addr_cells: Vec<_> = by_addr
+        // Hot accounts are suppressed from every table laid out like `tx-by-addr` except the
+        // program index, whose rows are all for the most invoked addresses by design.
+        let suppression_cells = match &self.hot_accounts {
+            Some(hot_accounts) => {
+                let mut hot_accounts = hot_accounts.lock().unwrap();
+                let mut address_rows = vec![&mut by_addr, &mut by_owner, &mut by_mint];
+                address_rows.extend(routed_by_addr.values_mut());
+
+                // An address listed in several tables counts each of its transactions once
+                let mut indexes: HashMap<Pubkey, HashSet<u32>> = HashMap::new();
+                for rows in &address_rows {
+                    for (address, infos) in rows.iter() {
+                        indexes.entry(*address).or_default().extend(infos.iter().map(|info| info.index));
+                    }
+                }
+                let transactions = indexes
+                    .into_iter()
+                    .map(|(address, indexes)| (address, indexes.len() as u64))
+                    .collect();
+                let changes = hot_accounts.add_block(slot, transactions);
+                for rows in address_rows {
+                    rows.retain(|address, infos| {
+                        let suppressed = hot_accounts.is_suppressed(address);
+                        if suppressed {
+                            report.skipped.hot_accounts += infos.len();
+                        }
+                        !suppressed
+                    });
+                }
+                changes
+                    .into_iter()
+                    .map(|(address, change)| (suppression_key(&address, slot), change))
+                    .collect()
+            }
+            None => vec![],
+        };
+
+        let block_time_cells = match confirmed_block.block_time {
+            Some(block_time) if self.uploader_config.enable_block_time_index => vec![
+                (slot_to_block_time_key(slot), block_time),
+                (block_time_to_key(block_time, slot), block_time),
+            ],
+            _ => vec![],
+        };
+        let block_index_cells = if self.uploader_config.enable_block_index {
+            block_index_cells(slot, &confirmed_block.blockhash, confirmed_block.block_height)
+        } else {
+            vec![]
+        };
+        let rewards_cells = if self.uploader_config.enable_rewards_index {
+            rewards_cells(slot, self.uploader_config.slots_per_epoch, &confirmed_block.rewards)
+        } else {
+            vec![]
+        };
+        let leader_index_cells = if self.uploader_config.enable_leader_index {
+            leader_index_cells(slot, &confirmed_block.rewards)
+        } else {
+            vec![]
+        };
+        let block_stats_cells = block_stats
+            .map(|block_stats| vec![(slot_to_key(slot), block_stats.build())])
+            .unwrap_or_default();
+
+        let tx_by_addr_salt_buckets = self.uploader_config.tx_by_addr_salt_buckets;
+        let block_cell = (
+            slot_to_blocks_key(slot, self.uploader_config.use_md5_row_key_salt),
+            confirmed_block.into(),
+        );
+
+        BlockCells {
+            report,
+            tx_cells,
+            tx_by_addr_cells: tx_by_addr_cells(slot, by_addr, tx_by_addr_salt_buckets),
+            routed_tx_by_addr_cells: routed_by_addr
+                .into_iter()
+                .map(|(table, by_addr)| (table, tx_by_addr_cells(slot, by_addr, tx_by_addr_salt_buckets)))
+                .collect(),
+            tx_by_owner_cells: tx_by_addr_cells(slot, by_owner, None),
+            tx_by_mint_cells: tx_by_addr_cells(slot, by_mint, None),
+            tx_by_program_cells: tx_by_addr_cells(slot, by_program, None),
+            block_time_cells,
+            block_index_cells,
+            rewards_cells,
+            leader_index_cells,
+            block_stats_cells,
+            memo_cells,
+            suppression_cells,
+            balance_history_cells: history_cells(slot, balance_history),
+            token_balance_history_cells: history_cells(slot, token_balance_history),
+            full_tx_cells,
+            routed_full_tx_cells,
+            full_tx_cache,
+            block_cell,
+        }
+    }
+}
+
+/// Rows derived from one block, before they are encoded.
+struct BlockCells {
+    report: UploadReport,
+    tx_cells: Vec<(RowKey, TransactionInfo)>,
+    tx_by_addr_cells: Vec<(RowKey, tx_by_addr::TransactionByAddr)>,
+    /// Keyed by the table the rows are routed to
+    routed_tx_by_addr_cells: BTreeMap<String, Vec<(RowKey, tx_by_addr::TransactionByAddr)>>,
+    tx_by_owner_cells: Vec<(RowKey, tx_by_addr::TransactionByAddr)>,
+    tx_by_mint_cells: Vec<(RowKey, tx_by_addr::TransactionByAddr)>,
+    tx_by_program_cells: Vec<(RowKey, tx_by_addr::TransactionByAddr)>,
+    block_time_cells: Vec<(RowKey, UnixTimestamp)>,
+    block_index_cells: Vec<(RowKey, u64)>,
+    rewards_cells: Vec<(RowKey, Vec<RewardCell>)>,
+    leader_index_cells: Vec<(RowKey, Pubkey)>,
+    block_stats_cells: Vec<(RowKey, BlockStats)>,
+    memo_cells: Vec<(RowKey, Signature)>,
+    suppression_cells: Vec<(RowKey, SuppressionChange)>,
+    balance_history_cells: Vec<(RowKey, Vec<BalanceChange>)>,
+    token_balance_history_cells: Vec<(RowKey, Vec<TokenBalanceChange>)>,
+    full_tx_cells: Vec<(RowKey, generated::ConfirmedTransactionWithStatusMeta)>,
+    routed_full_tx_cells: BTreeMap<String, Vec<(RowKey, generated::ConfirmedTransactionWithStatusMeta)>>,
+    full_tx_cache: Vec<(String, ConfirmedTransactionWithStatusMeta)>,
+    block_cell: (RowKey, generated::ConfirmedBlock),
+}
+
+/// `tx-by-addr` rows of the block at `slot`, one per address, salted if `salt_buckets` is set.
+fn tx_by_addr_cells(
+    slot: Slot,
+    by_addr: HashMap<Pubkey, Vec<TransactionByAddrInfo>>,
+    salt_buckets: Option<u16>,
+) -> Vec<(RowKey, tx_by_addr::TransactionByAddr)> {
+    by_addr
+        .into_iter()
+        .map(|(address, transaction_info_by_addr)| {
+            (
+                tx_by_addr_key(address, slot, salt_buckets),
+                tx_by_addr::TransactionByAddr {
+                    tx_by_addrs: transaction_info_by_addr
+                        .into_iter()
+                        .map(|by_addr| by_addr.into())
+                        .collect(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Balance history rows of the block at `slot`, one per address.
+fn history_cells<T>(slot: Slot, history: HashMap<Pubkey, Vec<T>>) -> Vec<(RowKey, Vec<T>)> {
+    history
+        .into_iter()
+        .map(|(address, changes)| (format!("{}/{}", address, slot_to_tx_by_addr_key(slot)), changes))
+        .collect()
+}
+
+/// `slot-index` rows of the blocks at `slots`, see `solana_storage_utils::slot_index`.
+fn slot_index_row_data(
+    slots: &[Slot],
+    use_cell_envelope: bool,
+) -> std::result::Result<Vec<(RowKey, RowData)>, HBaseError> {
+    let cells: Vec<_> = slots.iter().map(|slot| slot_index_cell(*slot)).collect();
+    encode_bincode_cells(&cells, &CompressionPolicy::none(), use_cell_envelope)
+}
+
+/// Write the encoded rows of several blocks to `table` together, as an upload task.
+async fn put_blocks_row_data(
+    conn: &HBaseConnection,
+    table: String,
+    blocks_row_data: Vec<Vec<(RowKey, RowData)>>,
+    use_wal: bool,
+    start: Instant,
+) -> std::result::Result<TaskResult, TaskError> {
+    let stats = write_blocks_row_data(conn, &table, blocks_row_data, use_wal, start).await?;
+    Ok(TaskResult::TableWritten(table, stats))
+}
+
+/// Write the encoded rows of several blocks to `table` together, and return the stats of each
+/// block. They share the latency of the whole write, which starts at `start` to include the
+/// encoding.
+async fn write_blocks_row_data(
+    conn: &HBaseConnection,
+    table: &str,
+    blocks_row_data: Vec<Vec<(RowKey, RowData)>>,
+    use_wal: bool,
+    start: Instant,
+) -> std::result::Result<Vec<TableUploadStats>, TaskError> {
+    let mut stats: Vec<_> = blocks_row_data
+        .iter()
+        .map(|row_data| TableUploadStats {
+            rows: row_data.len(),
+            bytes: row_data_size(row_data),
+            latency: Duration::ZERO,
+        })
+        .collect();
+
+    let row_data: Vec<_> = blocks_row_data.into_iter().flatten().collect();
+    for row_data in row_data.chunks(PUT_ROWS_BATCH_SIZE) {
+        conn.put_row_data_with_retry(table, row_data, use_wal).await?;
+    }
+
+    let latency = start.elapsed();
+    for stats in &mut stats {
+        stats.latency = latency;
+    }
+    Ok(stats)
+}
+
+#[async_trait]
+impl LedgerStorageAdapter for LedgerStorage {
+    async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<UploadReport> {
+        let mut reports = self
+            .upload_confirmed_blocks(vec![(slot, confirmed_block)])
+            .await?;
+        Ok(reports.remove(0))
+    }
+
+    /// Rows of all the blocks are written together, one table at a time. Like for a single
+    /// block, the blocks themselves are only written once everything else was.
+    ///
+    /// As a table is written for all the blocks at once, the latency reported for a table is
+    /// the one of the whole batch, and is the same in the report of every block.
+    async fn upload_confirmed_blocks(
+        &self,
+        blocks: Vec<(Slot, VersionedConfirmedBlock)>,
+    ) -> Result<Vec<UploadReport>> {
+        for (slot, confirmed_block) in &blocks {
+            info!("HBase: Uploading block {:?} from slot {:?}", confirmed_block.blockhash, slot);
+        }
+
+        self.restore_hot_accounts().await?;
+
+        let mut blocks_cells: Vec<_> = blocks
             .into_iter()
-            .map(|(address, transaction_info_by_addr)| {
-                (
-                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
-                    tx_by_addr::TransactionByAddr {
-                        tx_by_addrs: transaction_info_by_addr
-                            .into_iter()
-                            .map(|by_addr| by_addr.into())
-                            .collect(),
-                    },
-                )
-            })
+            .map(|(slot, confirmed_block)| self.block_cells(slot, confirmed_block))
             .collect();
+        let write_to_wal = self.uploader_config.hbase_write_to_wal;
+        let use_cell_envelope = self.uploader_config.use_cell_envelope;
 
         let mut tasks = vec![];
 
-        if !full_tx_cells.is_empty() && self.uploader_config.enable_full_tx {
-            let conn = self.connection.clone();
-            let full_tx_table_name = self.uploader_config.full_tx_table_name.clone();
-            let use_tx_full_compression = self.uploader_config.use_tx_full_compression.clone();
-            let write_to_wal = self.uploader_config.hbase_write_to_wal.clone();
-            tasks.push(tokio::spawn(async move {
-                conn.put_protobuf_cells_with_retry::<generated::ConfirmedTransactionWithStatusMeta>(
-                    full_tx_table_name.as_str(),
-                    &full_tx_cells,
-                    use_tx_full_compression,
-                    write_to_wal,
-                )
-                    .await
-                    .map(TaskResult::BytesWritten)
-                    .map_err(TaskError::from)
-            }));
+        let full_tx_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.full_tx_cells))
+            .collect();
+        if full_tx_cells.iter().any(|cells| !cells.is_empty()) && self.uploader_config.enable_full_tx {
+            tasks.push(self.spawn_full_tx_upload(
+                self.uploader_config.full_tx_table_name.clone(),
+                full_tx_cells,
+            ));
+        }
+        for table in self.uploader_config.routed_full_tx_table_names() {
+            let routed_full_tx_cells: Vec<_> = blocks_cells
+                .iter_mut()
+                .map(|block_cells| block_cells.routed_full_tx_cells.remove(table).unwrap_or_default())
+                .collect();
+            if routed_full_tx_cells.iter().any(|cells| !cells.is_empty()) && self.uploader_config.enable_full_tx {
+                debug!("HBase: spawning {} upload thread", table);
+                tasks.push(self.spawn_full_tx_upload(table.to_string(), routed_full_tx_cells));
+            }
         }
 
-        if !full_tx_cache.is_empty() && self.enable_full_tx_cache {
-            let mut cached_count = 0;
+        let full_tx_cache: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.full_tx_cache))
+            .collect();
+        if full_tx_cache.iter().any(|cache| !cache.is_empty()) && self.enable_full_tx_cache {
             let cache_client = self.cache_client.clone();
             let tx_cache_expiration = self.tx_cache_expiration;
             debug!("Writing block transactions to cache");
             tasks.push(tokio::spawn(async move {
-                for (signature, transaction) in full_tx_cache {
-                    if let Some(client) = &cache_client {
-                        cache_transaction::<generated::ConfirmedTransactionWithStatusMeta>(
-                            &client,
-                            &signature,
-                            transaction.into(),
-                            tx_cache_expiration,
-                        )
-                            .await
-                            .map_err(TaskError::from)?;
+                let mut cached_counts = vec![0; full_tx_cache.len()];
+                for (cached_count, block_cache) in cached_counts.iter_mut().zip(full_tx_cache) {
+                    for (signature, transaction) in block_cache {
+                        if let Some(client) = &cache_client {
+                            cache_transaction::<generated::ConfirmedTransactionWithStatusMeta>(
+                                &client,
+                                &signature,
+                                transaction.into(),
+                                tx_cache_expiration,
+                            )
+                                .await
+                                .map_err(TaskError::from)?;
 
-                        cached_count += 1;
-                        debug!("Cached transaction with signature {}", signature);
+                            *cached_count += 1;
+                            debug!("Cached transaction with signature {}", signature);
+                        }
                     }
                 }
-                Ok::<TaskResult, TaskError>(TaskResult::CachedTransactions(cached_count))
+                Ok::<TaskResult, TaskError>(TaskResult::CachedTransactions(cached_counts))
             }));
         }
 
-        if !tx_cells.is_empty() && !self.uploader_config.disable_tx {
+        let tx_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_cells))
+            .collect();
+        if tx_cells.iter().any(|cells| !cells.is_empty()) && !self.uploader_config.disable_tx {
             let conn = self.connection.clone();
             let tx_table_name = self.uploader_config.tx_table_name.clone();
-            let use_tx_compression = self.uploader_config.use_tx_compression.clone();
-            let write_to_wal = self.uploader_config.hbase_write_to_wal.clone();
+            let tx_compression = self.uploader_config.tx_compression_policy();
             debug!("HBase: spawning tx upload thread");
             tasks.push(tokio::spawn(async move {
-                debug!("HBase: calling put_bincode_cells_with_retry for tx");
-                conn.put_bincode_cells_with_retry::<TransactionInfo>(
-                    tx_table_name.as_str(),
-                    &tx_cells,
-                    use_tx_compression,
-                    write_to_wal,
-                )
-                    .await
-                    .map(TaskResult::BytesWritten)
-                    .map_err(TaskError::from)
+                let start = Instant::now();
+                let row_data = tx_cells
+                    .iter()
+                    .map(|cells| encode_bincode_cells(cells, &tx_compression, use_cell_envelope))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                put_blocks_row_data(&conn, tx_table_name, row_data, write_to_wal, start).await
             }));
         }
 
-        if !tx_by_addr_cells.is_empty() && !self.uploader_config.disable_tx_by_addr {
-            let conn = self.connection.clone();
-            let tx_by_addr_table_name = self.uploader_config.tx_by_addr_table_name.clone();
-            let use_tx_by_addr_compression = self.uploader_config.use_tx_by_addr_compression.clone();
-            let write_to_wal = self.uploader_config.hbase_write_to_wal.clone();
+        let tx_by_addr_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_addr_cells))
+            .collect();
+        if tx_by_addr_cells.iter().any(|cells| !cells.is_empty()) && !self.uploader_config.disable_tx_by_addr {
             debug!("HBase: spawning tx-by-addr upload thread");
-            tasks.push(tokio::spawn(async move {
-                debug!("HBase: calling put_protobuf_cells_with_retry tx-by-addr");
-                conn.put_protobuf_cells_with_retry::<tx_by_addr::TransactionByAddr>(
-                    tx_by_addr_table_name.as_str(),
-                    &tx_by_addr_cells,
-                    use_tx_by_addr_compression,
-                    write_to_wal
-                )
-                    .await
-                    .map(TaskResult::BytesWritten)
-                    .map_err(TaskError::from)
-                // info!("HBase: finished put_protobuf_cells_with_retry call for tx-by-addr");
-            }));
+            tasks.push(self.spawn_tx_by_addr_upload(
+                self.uploader_config.tx_by_addr_table_name.clone(),
+                tx_by_addr_cells,
+            ));
+        }
+        for table in self.uploader_config.routed_tx_by_addr_table_names() {
+            let routed_tx_by_addr_cells: Vec<_> = blocks_cells
+                .iter_mut()
+                .map(|block_cells| block_cells.routed_tx_by_addr_cells.remove(table).unwrap_or_default())
+                .collect();
+            if routed_tx_by_addr_cells.iter().any(|cells| !cells.is_empty()) && !self.uploader_config.disable_tx_by_addr {
+                debug!("HBase: spawning {} upload thread", table);
+                tasks.push(self.spawn_tx_by_addr_upload(table.to_string(), routed_tx_by_addr_cells));
+            }
+        }
+
+        let tx_by_owner_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_owner_cells))
+            .collect();
+        if tx_by_owner_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning tx-by-owner upload thread");
+            tasks.push(self.spawn_tx_by_addr_upload(
+                self.uploader_config.tx_by_owner_table_name.clone(),
+                tx_by_owner_cells,
+            ));
+        }
+
+        let tx_by_mint_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_mint_cells))
+            .collect();
+        if tx_by_mint_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning tx-by-mint upload thread");
+            tasks.push(self.spawn_tx_by_addr_upload(
+                self.uploader_config.tx_by_mint_table_name.clone(),
+                tx_by_mint_cells,
+            ));
+        }
+
+        let tx_by_program_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.tx_by_program_cells))
+            .collect();
+        if tx_by_program_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning tx-by-program upload thread");
+            tasks.push(self.spawn_tx_by_addr_upload(
+                self.uploader_config.tx_by_program_table_name.clone(),
+                tx_by_program_cells,
+            ));
+        }
+
+        let block_time_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.block_time_cells))
+            .collect();
+        if block_time_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning block time upload thread");
+            // The cells are too small to compress
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.block_time_table_name.clone(),
+                block_time_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let block_index_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.block_index_cells))
+            .collect();
+        if block_index_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning block index upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.block_index_table_name.clone(),
+                block_index_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let rewards_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.rewards_cells))
+            .collect();
+        if rewards_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning rewards upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.rewards_table_name.clone(),
+                rewards_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let leader_index_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.leader_index_cells))
+            .collect();
+        if leader_index_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning leader index upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.leader_index_table_name.clone(),
+                leader_index_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let block_stats_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.block_stats_cells))
+            .collect();
+        if block_stats_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning block stats upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.block_stats_table_name.clone(),
+                block_stats_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let memo_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.memo_cells))
+            .collect();
+        if memo_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning memo index upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.memo_index_table_name.clone(),
+                memo_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let suppression_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.suppression_cells))
+            .collect();
+        if suppression_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning suppressed addresses upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.suppressed_addresses_table_name.clone(),
+                suppression_cells,
+                CompressionPolicy::none(),
+            ));
+        }
+
+        let balance_history_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.balance_history_cells))
+            .collect();
+        if balance_history_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning balance history upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.balance_history_table_name.clone(),
+                balance_history_cells,
+                self.uploader_config.tx_by_addr_compression_policy(),
+            ));
         }
 
-        let mut bytes_written = 0;
-        let mut total_cached_transactions = 0;
+        let token_balance_history_cells: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.token_balance_history_cells))
+            .collect();
+        if token_balance_history_cells.iter().any(|cells| !cells.is_empty()) {
+            debug!("HBase: spawning token balance history upload thread");
+            tasks.push(self.spawn_bincode_upload(
+                self.uploader_config.token_balance_history_table_name.clone(),
+                token_balance_history_cells,
+                self.uploader_config.tx_by_addr_compression_policy(),
+            ));
+        }
+
+        let mut reports: Vec<_> = blocks_cells
+            .iter_mut()
+            .map(|block_cells| std::mem::take(&mut block_cells.report))
+            .collect();
         let mut maybe_first_err: Option<StorageError> = None;
 
         debug!("HBase: waiting for all upload threads to finish...");
@@ -446,26 +1365,23 @@ impl LedgerStorageAdapter for LedgerStorage {
                 Ok(Err(err)) => {
                     debug!("HBase: got error result {:?}", err);
                     if maybe_first_err.is_none() {
-                        match err {
-                            TaskError::HBaseError(hbase_err) => {
-                                maybe_first_err = Some(StorageError::StorageBackendError(Box::new(hbase_err)));
-                            }
-                            TaskError::MemcacheError(memcache_err) => {
-                                maybe_first_err = Some(StorageError::CacheError(Box::new(memcache_err)));
-                            }
-                            TaskError::IoError(io_err) => {
-                                maybe_first_err = Some(StorageError::IoError(io_err));
-                            }
-                            TaskError::EncodingError(enc_err) => {
-                                maybe_first_err = Some(StorageError::EncodingError(enc_err));
-                            }
-                        }
+                        maybe_first_err = Some(err.into());
                     }
                 }
                 Ok(Ok(task_result)) => {
                     match task_result {
-                        TaskResult::BytesWritten(bytes) => bytes_written += bytes,
-                        TaskResult::CachedTransactions(count) => total_cached_transactions += count,
+                        TaskResult::TableWritten(table, stats) => {
+                            for (report, stats) in reports.iter_mut().zip(stats) {
+                                if stats.rows > 0 {
+                                    report.tables.insert(table.clone(), stats);
+                                }
+                            }
+                        }
+                        TaskResult::CachedTransactions(counts) => {
+                            for (report, count) in reports.iter_mut().zip(counts) {
+                                report.cached_transactions += count;
+                            }
+                        }
                     }
                 }
             }
@@ -477,80 +1393,376 @@ impl LedgerStorageAdapter for LedgerStorage {
         }
 
         if self.enable_full_tx_cache {
-            info!("Cached {} transactions from slot {}",slot, total_cached_transactions);
+            let cached_transactions: usize = reports.iter().map(|report| report.cached_transactions).sum();
+            info!("Cached {} transactions from {} blocks", cached_transactions, reports.len());
         }
 
-        let num_transactions = confirmed_block.transactions.len();
-
         // Store the block itself last, after all other metadata about the block has been
         // successfully stored.  This avoids partial uploaded blocks from becoming visible to
         // `get_confirmed_block()` and `get_confirmed_blocks()`
-        let blocks_cells = [(
-            slot_to_blocks_key(slot, self.uploader_config.use_md5_row_key_salt),
-            confirmed_block.into()
-        )];
-
-        debug!("HBase: calling put_protobuf_cells_with_retry for blocks");
+        debug!("HBase: writing blocks");
 
         if !self.uploader_config.disable_blocks {
-            bytes_written += self
-                .connection
-                .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>(
-                    self.uploader_config.blocks_table_name.as_str(),
-                    &blocks_cells,
-                    self.uploader_config.use_blocks_compression,
-                    self.uploader_config.hbase_write_to_wal
-                )
-                .await
-                .map_err(|err| {
+            let start = Instant::now();
+            let blocks_compression = self.uploader_config.blocks_compression_policy();
+            let row_data = blocks_cells
+                .iter()
+                .map(|block_cells| {
+                    encode_protobuf_cells(
+                        std::slice::from_ref(&block_cells.block_cell),
+                        &blocks_compression,
+                        use_cell_envelope,
+                    )
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+            let table = &self.uploader_config.blocks_table_name;
+            let result = write_blocks_row_data(&self.connection, table, row_data, write_to_wal, start).await;
+            match result {
+                Ok(stats) => {
+                    for (report, stats) in reports.iter_mut().zip(stats) {
+                        report.tables.insert(table.clone(), stats);
+                    }
+                }
+                Err(err) => {
                     error!("HBase: failed to upload block: {:?}", err);
-                    // err.into()
-                    StorageError::StorageBackendError(Box::new(err))
-                })?;
+                    return Err(err.into());
+                }
+            }
+
+            // Salted block keys are not sorted by slot, so the blocks are listed from the slot
+            // index, which is only written once the blocks are readable
+            if self.uploader_config.use_md5_row_key_salt {
+                let start = Instant::now();
+                let row_data = reports
+                    .iter()
+                    .map(|report| slot_index_row_data(&[report.slot], use_cell_envelope))
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+                let table = &self.uploader_config.slot_index_table_name;
+                match write_blocks_row_data(&self.connection, table, row_data, write_to_wal, start).await {
+                    Ok(stats) => {
+                        for (report, stats) in reports.iter_mut().zip(stats) {
+                            report.tables.insert(table.clone(), stats);
+                        }
+                    }
+                    Err(err) => {
+                        error!("HBase: failed to upload slot index: {:?}", err);
+                        return Err(err.into());
+                    }
+                }
+            }
         }
 
-        info!(
-            "HBase: successfully uploaded block from slot {} [transactions: {}, bytes: {}]",
-            slot, num_transactions, bytes_written
-        );
+        for report in &reports {
+            info!("HBase: successfully uploaded block from {}", report);
+        }
         // datapoint_info!(
         //     "storage-hbase-upload-block",
         //     ("slot", slot, i64),
         //     ("transactions", num_transactions, i64),
         //     ("bytes", bytes_written, i64),
         // );
-        Ok(())
+        Ok(reports)
     }
 
-    fn should_include_in_tx_full(&self, address: &Pubkey) -> bool {
-        if let Some(ref filter) = self.uploader_config.tx_full_filter {
-            if filter.exclude {
-                // If exclude is true, exclude the address if it's in the set.
-                !filter.addrs.contains(address)
-            } else {
-                // If exclude is false, include the address only if it's in the set.
-                filter.addrs.contains(address)
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<DeleteReport> {
+        let blocks_table = self.uploader_config.blocks_table_name.as_str();
+        let block_key = slot_to_blocks_key(slot, self.uploader_config.use_md5_row_key_salt);
+
+        let block_rows = self.connection
+            .get_row_data_with_retry(blocks_table, &[block_key.clone()])
+            .await
+            .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+        let Some((_, block_row)) = block_rows.first() else {
+            return Err(StorageError::BlockNotFound(slot));
+        };
+        let block: ConfirmedBlock =
+            deserialize_protobuf_cell_data::<generated::ConfirmedBlock>(block_row, blocks_table, &block_key)
+                .and_then(|block| {
+                    block.try_into().map_err(|_err| {
+                        HBaseError::ObjectCorrupt(format!("{blocks_table}/{block_key}"))
+                    })
+                })
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+
+        let mut row_keys = block_row_keys(slot, self.uploader_config.slots_per_epoch, &block);
+        if let Some(salt_buckets) = self.uploader_config.tx_by_addr_salt_buckets {
+            let salt = |keys: &mut Vec<String>| {
+                *keys = keys
+                    .iter()
+                    .filter_map(|key| salt_tx_by_addr_key(key, salt_buckets))
+                    .collect();
+            };
+            salt(&mut row_keys.tx_by_addr);
+            let tx_by_addr_table_name = Some(self.uploader_config.tx_by_addr_table_name.as_str());
+            if self.uploader_config.token_owner_table_name() == tx_by_addr_table_name {
+                salt(&mut row_keys.tx_by_owner);
+            }
+            if self.uploader_config.token_mint_table_name() == tx_by_addr_table_name {
+                salt(&mut row_keys.tx_by_mint);
             }
-        } else {
-            true
         }
-    }
+        let mut rows = BTreeMap::new();
+        let mut kept_rows = BTreeMap::new();
 
-    fn should_include_in_tx_by_addr(&self, address: &Pubkey) -> bool {
-        if let Some(ref filter) = self.uploader_config.tx_by_addr_filter {
-            if filter.exclude {
-                // If exclude is true, exclude the address if it's in the set.
-                !filter.addrs.contains(address)
-            } else {
-                // If exclude is false, include the address only if it's in the set.
-                filter.addrs.contains(address)
+        if !self.uploader_config.disable_tx {
+            let table = self.uploader_config.tx_table_name.clone();
+            let (tx_rows, other_slot_rows) = self
+                .existing_rows(&table, &row_keys.signatures, slot, Some(|row_data, table, key| {
+                    deserialize_bincode_cell_data::<TransactionInfo>(row_data, table, key)
+                        .map(|tx_info| tx_info.slot)
+                }))
+                .await?;
+            rows.insert(table.clone(), tx_rows);
+            kept_rows.insert(table, other_slot_rows);
+        }
+        if !self.uploader_config.disable_tx_by_addr {
+            let table = self.uploader_config.tx_by_addr_table_name.clone();
+            let (tx_by_addr_rows, _) = self
+                .existing_rows(&table, &row_keys.tx_by_addr, slot, None)
+                .await?;
+            rows.insert(table, tx_by_addr_rows);
+
+            // Routed rows have the same keys as the ones they would have in tx-by-addr
+            for table in self.uploader_config.routed_tx_by_addr_table_names() {
+                let (tx_by_addr_rows, _) = self
+                    .existing_rows(table, &row_keys.tx_by_addr, slot, None)
+                    .await?;
+                rows.insert(table.to_string(), tx_by_addr_rows);
             }
-        } else {
-            true
         }
+        for (table, row_keys) in [
+            (self.uploader_config.token_owner_table_name(), &row_keys.tx_by_owner),
+            (self.uploader_config.token_mint_table_name(), &row_keys.tx_by_mint),
+        ] {
+            let Some(table) = table else {
+                continue;
+            };
+            let (token_index_rows, _) = self.existing_rows(table, row_keys, slot, None).await?;
+            // The token index may share the tx-by-addr table
+            let table_rows = rows.entry(table.to_string()).or_insert_with(Vec::new);
+            table_rows.extend(token_index_rows);
+            table_rows.sort();
+            table_rows.dedup();
+        }
+        if self.uploader_config.index_programs {
+            let table = self.uploader_config.tx_by_program_table_name.clone();
+            let (tx_by_program_rows, _) = self
+                .existing_rows(&table, &row_keys.tx_by_program, slot, None)
+                .await?;
+            rows.insert(table, tx_by_program_rows);
+        }
+        if self.uploader_config.enable_block_time_index {
+            let table = self.uploader_config.block_time_table_name.clone();
+            let (block_time_rows, _) = self
+                .existing_rows(&table, &row_keys.block_time, slot, None)
+                .await?;
+            rows.insert(table, block_time_rows);
+        }
+        if self.uploader_config.enable_block_index {
+            let table = self.uploader_config.block_index_table_name.clone();
+            let (block_index_rows, _) = self
+                .existing_rows(&table, &row_keys.block_index, slot, None)
+                .await?;
+            rows.insert(table, block_index_rows);
+        }
+        if self.uploader_config.enable_rewards_index {
+            let table = self.uploader_config.rewards_table_name.clone();
+            let (rewards_rows, _) = self
+                .existing_rows(&table, &row_keys.rewards, slot, None)
+                .await?;
+            rows.insert(table, rewards_rows);
+        }
+        if self.uploader_config.enable_leader_index {
+            let table = self.uploader_config.leader_index_table_name.clone();
+            let (leader_index_rows, _) = self
+                .existing_rows(&table, &row_keys.leader_index, slot, None)
+                .await?;
+            rows.insert(table, leader_index_rows);
+        }
+        if self.uploader_config.enable_block_stats {
+            let table = self.uploader_config.block_stats_table_name.clone();
+            let (block_stats_rows, _) = self
+                .existing_rows(&table, &row_keys.block_stats, slot, None)
+                .await?;
+            rows.insert(table, block_stats_rows);
+        }
+        if self.uploader_config.use_md5_row_key_salt && !self.uploader_config.disable_blocks {
+            let table = self.uploader_config.slot_index_table_name.clone();
+            let (slot_index_rows, _) = self
+                .existing_rows(&table, &row_keys.slot_index, slot, None)
+                .await?;
+            rows.insert(table, slot_index_rows);
+        }
+        if self.uploader_config.enable_memo_index {
+            let table = self.uploader_config.memo_index_table_name.clone();
+            let (memo_index_rows, _) = self
+                .existing_rows(&table, &row_keys.memo_index, slot, None)
+                .await?;
+            rows.insert(table, memo_index_rows);
+        }
+        if self.uploader_config.enable_balance_history {
+            let table = self.uploader_config.balance_history_table_name.clone();
+            let (balance_history_rows, _) = self
+                .existing_rows(&table, &row_keys.balance_history, slot, None)
+                .await?;
+            rows.insert(table, balance_history_rows);
+        }
+        if self.uploader_config.enable_token_balance_history {
+            let table = self.uploader_config.token_balance_history_table_name.clone();
+            let (token_balance_history_rows, _) = self
+                .existing_rows(&table, &row_keys.token_balance_history, slot, None)
+                .await?;
+            rows.insert(table, token_balance_history_rows);
+        }
+        if self.uploader_config.enable_full_tx {
+            let full_tx_tables = std::iter::once(self.uploader_config.full_tx_table_name.as_str())
+                .chain(self.uploader_config.routed_full_tx_table_names());
+            for table in full_tx_tables {
+                let (full_tx_rows, other_slot_rows) = self
+                    .existing_rows(table, &row_keys.signatures, slot, Some(|row_data, table, key| {
+                        deserialize_protobuf_cell_data::<generated::ConfirmedTransactionWithStatusMeta>(
+                            row_data, table, key,
+                        )
+                            .map(|full_tx| full_tx.slot)
+                    }))
+                    .await?;
+                rows.insert(table.to_string(), full_tx_rows);
+                kept_rows.insert(table.to_string(), other_slot_rows);
+            }
+        }
+        kept_rows.retain(|_, row_keys: &mut Vec<String>| !row_keys.is_empty());
+
+        if !dry_run {
+            // Delete the block last, so that a failed deletion can be retried.
+            for (table, row_keys) in &rows {
+                for row_keys in row_keys.chunks(GET_ROWS_BATCH_SIZE) {
+                    self.connection
+                        .delete_rows_with_retry(table, row_keys, self.uploader_config.hbase_write_to_wal)
+                        .await
+                        .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+                }
+            }
+            self.connection
+                .delete_rows_with_retry(blocks_table, &[block_key.clone()], self.uploader_config.hbase_write_to_wal)
+                .await
+                .map_err(|err| StorageError::StorageBackendError(Box::new(err)))?;
+        }
+        rows.insert(blocks_table.to_string(), vec![block_key]);
+
+        let report = DeleteReport {
+            dry_run,
+            slots: vec![slot],
+            missing_slots: vec![],
+            rows,
+            kept_rows,
+        };
+        info!("HBase: block from slot {}: {}", slot, report);
+        Ok(report)
+    }
+
+    fn should_include_in_tx_full(&self, address: &Pubkey) -> bool {
+        self.address_filters().include_in_tx_full(address)
+    }
+
+    fn should_include_in_tx_by_addr(&self, address: &Pubkey) -> bool {
+        self.address_filters().include_in_tx_by_addr(address)
     }
 
     fn clone_box(&self) -> Box<dyn LedgerStorageAdapter> {
         Box::new(self.clone())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::tx_filter_rules::TxFilterRules,
+        solana_hash::Hash,
+        solana_message::{compiled_instruction::CompiledInstruction, Message, VersionedMessage},
+        solana_storage_utils::cell_envelope::{unwrap_cell, PayloadEncoding},
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    fn transaction(signature: Signature, payer: Pubkey, program: Pubkey) -> VersionedTransactionWithStatusMeta {
+        let message = Message::new_with_compiled_instructions(
+            1,
+            0,
+            1,
+            vec![payer, program],
+            Hash::default(),
+            vec![CompiledInstruction::new_from_raw_parts(1, vec![], vec![0])],
+        );
+        VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction {
+                signatures: vec![signature],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta: TransactionStatusMeta::default(),
+        }
+    }
+
+    #[test]
+    fn test_block_cells_tx_full_filter_with_rules() {
+        let excluded_payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let included = Signature::from([2; 64]);
+        let uploader_config = UploaderConfig {
+            enable_full_tx: true,
+            tx_full_filter: Some(FilterTxIncludeExclude {
+                exclude: true,
+                addrs: HashSet::from([excluded_payer]),
+                file: None,
+            }),
+            tx_filter_rules: Some(
+                TxFilterRules::from_json(r#"{ "defaults": { "tx-by-addr": "exclude" } }"#).unwrap(),
+            ),
+            ..UploaderConfig::default()
+        };
+        let storage = futures::executor::block_on(LedgerStorage::new_with_config(LedgerStorageConfig {
+            uploader_config,
+            ..LedgerStorageConfig::default()
+        }));
+
+        let block = VersionedConfirmedBlock {
+            previous_blockhash: String::new(),
+            blockhash: String::new(),
+            parent_slot: 41,
+            transactions: vec![
+                transaction(Signature::from([1; 64]), excluded_payer, program),
+                transaction(included, Pubkey::new_unique(), program),
+            ],
+            rewards: vec![],
+            num_partitions: None,
+            block_time: None,
+            block_height: None,
+        };
+        let block_cells = storage.block_cells(42, block);
+
+        let full_tx_keys: Vec<_> = block_cells.full_tx_cells.iter().map(|(key, _)| key.clone()).collect();
+        assert_eq!(full_tx_keys, vec![included.to_string()]);
+        assert!(block_cells.tx_by_addr_cells.is_empty());
+        assert_eq!(block_cells.report.skipped.tx_full_filter_tx, 1);
+        // Once per transaction, not per table left out
+        assert_eq!(block_cells.report.skipped.filter_rules, 2);
+    }
+
+    #[test]
+    fn test_slot_index_row_data() {
+        for use_cell_envelope in [false, true] {
+            let row_data = slot_index_row_data(&[42, 4096], use_cell_envelope).unwrap();
+            let row_keys: Vec<_> = row_data.iter().map(|(row_key, _)| row_key.as_str()).collect();
+            assert_eq!(row_keys, ["000000000000002a", "0000000000001000"]);
+
+            let (_, cells) = &row_data[0];
+            assert_eq!(cells.len(), 1);
+            assert_eq!(cells[0].0, "bin");
+            let (encoding, data) = unwrap_cell(&cells[0].1).unwrap();
+            assert_eq!(encoding, use_cell_envelope.then_some(PayloadEncoding::Bincode));
+            assert_eq!(bincode::deserialize::<Slot>(&data).unwrap(), 42);
+        }
+    }
+}
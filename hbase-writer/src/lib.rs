@@ -1,10 +1,18 @@
 #![allow(clippy::integer_arithmetic)]
 
 pub mod hbase;
+mod deserializer;
 pub mod connection;
 pub mod tx_cache;
 pub mod tx_utils;
 pub mod storage_config;
 pub mod cache_config;
+pub use solana_hbase_common::thrift_config;
+pub mod schema_config;
+pub mod schema;
 pub mod uploader_config;
+pub mod address_filter;
+pub mod hot_accounts;
+pub mod tx_filter_rules;
+pub mod tx_routes;
 pub mod ledger_storage;
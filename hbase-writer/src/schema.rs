@@ -0,0 +1,385 @@
+use {
+    crate::{
+        hbase::{HBase, Result},
+        schema_config::{SchemaConfig, TableSchema, COLUMN_FAMILY_NAME},
+        uploader_config::UploaderConfig,
+    },
+    hbase_thrift::hbase::ColumnDescriptor,
+    log::*,
+    std::fmt,
+};
+
+const HEX_ALPHABET: &str = "0123456789abcdef";
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// A table written by the uploader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSpec {
+    pub name: String,
+    /// Characters the row keys start with, used to pick split points. `None` for sequential keys,
+    /// which can not be pre-split evenly.
+    pub key_alphabet: Option<&'static str>,
+}
+
+/// Tables the uploader writes to with the given configuration.
+pub fn uploader_tables(config: &UploaderConfig) -> Vec<TableSpec> {
+    let mut tables = vec![];
+
+    if !config.disable_blocks {
+        tables.push(TableSpec {
+            name: config.blocks_table_name.clone(),
+            key_alphabet: config.use_md5_row_key_salt.then_some(HEX_ALPHABET),
+        });
+        if config.use_md5_row_key_salt {
+            tables.push(TableSpec {
+                name: config.slot_index_table_name.clone(),
+                key_alphabet: None,
+            });
+        }
+    }
+    if !config.disable_tx {
+        tables.push(TableSpec {
+            name: config.tx_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+    }
+    if !config.disable_tx_by_addr {
+        let key_alphabet = if config.tx_by_addr_salt_buckets.is_some() {
+            HEX_ALPHABET
+        } else {
+            BASE58_ALPHABET
+        };
+        tables.push(TableSpec {
+            name: config.tx_by_addr_table_name.clone(),
+            key_alphabet: Some(key_alphabet),
+        });
+        for table_name in config.routed_tx_by_addr_table_names() {
+            tables.push(TableSpec {
+                name: table_name.to_string(),
+                key_alphabet: Some(key_alphabet),
+            });
+        }
+    }
+    for table_name in [config.token_owner_table_name(), config.token_mint_table_name()]
+        .into_iter()
+        .flatten()
+    {
+        if !tables.iter().any(|table: &TableSpec| table.name == table_name) {
+            tables.push(TableSpec {
+                name: table_name.to_string(),
+                key_alphabet: Some(BASE58_ALPHABET),
+            });
+        }
+    }
+    if config.index_programs {
+        tables.push(TableSpec {
+            name: config.tx_by_program_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+    }
+    if config.enable_block_time_index {
+        tables.push(TableSpec {
+            name: config.block_time_table_name.clone(),
+            key_alphabet: None,
+        });
+    }
+    if config.enable_block_index {
+        tables.push(TableSpec {
+            name: config.block_index_table_name.clone(),
+            key_alphabet: None,
+        });
+    }
+    if config.enable_rewards_index {
+        tables.push(TableSpec {
+            name: config.rewards_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+    }
+    if config.enable_leader_index {
+        tables.push(TableSpec {
+            name: config.leader_index_table_name.clone(),
+            key_alphabet: None,
+        });
+    }
+    if config.enable_block_stats {
+        tables.push(TableSpec {
+            name: config.block_stats_table_name.clone(),
+            key_alphabet: None,
+        });
+    }
+    if config.enable_memo_index {
+        tables.push(TableSpec {
+            name: config.memo_index_table_name.clone(),
+            key_alphabet: Some(HEX_ALPHABET),
+        });
+    }
+    if config.enable_balance_history {
+        tables.push(TableSpec {
+            name: config.balance_history_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+    }
+    if config.enable_token_balance_history {
+        tables.push(TableSpec {
+            name: config.token_balance_history_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+    }
+    if config.hot_account_policy.is_some() {
+        tables.push(TableSpec {
+            name: config.suppressed_addresses_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+    }
+    if config.enable_full_tx {
+        tables.push(TableSpec {
+            name: config.full_tx_table_name.clone(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        });
+        for table_name in config.routed_full_tx_table_names() {
+            tables.push(TableSpec {
+                name: table_name.to_string(),
+                key_alphabet: Some(BASE58_ALPHABET),
+            });
+        }
+    }
+
+    tables
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    pub table: String,
+    pub setting: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} is {}, expected {}",
+            self.table, self.setting, self.actual, self.expected
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaReport {
+    pub created: Vec<String>,
+    pub existing: Vec<String>,
+    /// Tables created with a single region although more were requested. Thrift `createTable`
+    /// takes no split keys, see `hbase_shell_create_statement`.
+    pub unsplit: Vec<String>,
+    pub mismatches: Vec<SchemaMismatch>,
+}
+
+impl SchemaReport {
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Create the missing tables and check that the existing ones match `config`. Existing tables
+/// are never altered, differences are returned as mismatches.
+pub async fn ensure_schema(
+    client: &mut HBase,
+    tables: &[TableSpec],
+    config: &SchemaConfig,
+) -> Result<SchemaReport> {
+    let existing_tables = client.get_table_names().await?;
+    let mut report = SchemaReport::default();
+
+    for table in tables {
+        let schema = config.schema_for(&table.name);
+
+        if !existing_tables.contains(&table.name)
+            && client.create_table(&table.name, vec![column_descriptor(schema)]).await?
+        {
+            info!("Created table {}", table.name);
+            if !split_keys(table, schema).is_empty() {
+                warn!(
+                    "Table {} was created with a single region, pre-split it with: {}",
+                    table.name,
+                    hbase_shell_create_statement(table, schema)
+                );
+                report.unsplit.push(table.name.clone());
+            }
+            report.created.push(table.name.clone());
+            continue;
+        }
+
+        let mismatches = validate_table(client, table, schema).await?;
+        for mismatch in &mismatches {
+            warn!("Schema mismatch: {}", mismatch);
+        }
+        report.mismatches.extend(mismatches);
+        report.existing.push(table.name.clone());
+    }
+
+    Ok(report)
+}
+
+async fn validate_table(
+    client: &mut HBase,
+    table: &TableSpec,
+    schema: &TableSchema,
+) -> Result<Vec<SchemaMismatch>> {
+    let mut mismatches = vec![];
+    let mut check = |setting: &'static str, expected: String, actual: Option<String>| {
+        let actual = actual.unwrap_or_else(|| "unset".to_string());
+        if !expected.eq_ignore_ascii_case(&actual) {
+            mismatches.push(SchemaMismatch {
+                table: table.name.clone(),
+                setting,
+                expected,
+                actual,
+            });
+        }
+    };
+
+    let descriptors = client.get_column_descriptors(&table.name).await?;
+    let Some(descriptor) = descriptors.get(COLUMN_FAMILY_NAME) else {
+        let families = descriptors.keys().cloned().collect::<Vec<_>>().join(",");
+        check("column families", COLUMN_FAMILY_NAME.to_string(), Some(families));
+        return Ok(mismatches);
+    };
+
+    check(
+        "max versions",
+        schema.max_versions.to_string(),
+        descriptor.max_versions.map(|v| v.to_string()),
+    );
+    check(
+        "TTL",
+        schema.time_to_live_secs().to_string(),
+        descriptor.time_to_live.map(|v| v.to_string()),
+    );
+    check(
+        "bloom filter",
+        schema.bloom_filter.to_string(),
+        descriptor.bloom_filter_type.clone(),
+    );
+    check(
+        "compression",
+        schema.compression.clone(),
+        descriptor.compression.clone(),
+    );
+
+    let expected_regions = split_keys(table, schema).len() + 1;
+    if expected_regions > 1 {
+        let regions = client.get_table_regions(&table.name).await?.len();
+        if regions < expected_regions {
+            check(
+                "region count",
+                format!("at least {expected_regions}"),
+                Some(regions.to_string()),
+            );
+        }
+    }
+
+    Ok(mismatches)
+}
+
+fn column_descriptor(schema: &TableSchema) -> ColumnDescriptor {
+    ColumnDescriptor {
+        name: Some(format!("{COLUMN_FAMILY_NAME}:").into_bytes()),
+        max_versions: Some(schema.max_versions),
+        compression: Some(schema.compression.clone()),
+        in_memory: Some(schema.in_memory),
+        bloom_filter_type: Some(schema.bloom_filter.to_string()),
+        bloom_filter_vector_size: None,
+        bloom_filter_nb_hashes: None,
+        block_cache_enabled: Some(schema.block_cache_enabled),
+        time_to_live: Some(schema.time_to_live_secs()),
+    }
+}
+
+/// Row keys at which `table` should be split to get `schema.regions` regions.
+pub fn split_keys(table: &TableSpec, schema: &TableSchema) -> Vec<String> {
+    let Some(alphabet) = table.key_alphabet else {
+        return vec![];
+    };
+    let chars = alphabet.chars().collect::<Vec<_>>();
+    let regions = schema.regions.min(chars.len());
+
+    (1..regions)
+        .map(|region| chars[region * chars.len() / regions].to_string())
+        .collect()
+}
+
+/// `hbase shell` statement creating `table` pre-split into `schema.regions` regions.
+pub fn hbase_shell_create_statement(table: &TableSpec, schema: &TableSchema) -> String {
+    let mut statement = format!(
+        "create '{}', {{NAME => '{}', VERSIONS => {}, TTL => {}, BLOOMFILTER => '{}', \
+         COMPRESSION => '{}', IN_MEMORY => '{}', BLOCKCACHE => '{}'}}",
+        table.name,
+        COLUMN_FAMILY_NAME,
+        schema.max_versions,
+        schema.time_to_live_secs(),
+        schema.bloom_filter,
+        schema.compression,
+        schema.in_memory,
+        schema.block_cache_enabled,
+    );
+
+    let split_keys = split_keys(table, schema);
+    if !split_keys.is_empty() {
+        let split_keys = split_keys
+            .iter()
+            .map(|key| format!("'{key}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        statement.push_str(&format!(", SPLITS => [{split_keys}]"));
+    }
+
+    statement
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_keys() {
+        let table = TableSpec {
+            name: "blocks".to_string(),
+            key_alphabet: Some(HEX_ALPHABET),
+        };
+        let schema = TableSchema {
+            regions: 4,
+            ..TableSchema::default()
+        };
+        assert_eq!(split_keys(&table, &schema), vec!["4", "8", "c"]);
+
+        let schema = TableSchema {
+            regions: 100,
+            ..TableSchema::default()
+        };
+        assert_eq!(split_keys(&table, &schema).len(), 15);
+
+        let table = TableSpec {
+            key_alphabet: None,
+            ..table
+        };
+        assert!(split_keys(&table, &schema).is_empty());
+    }
+
+    #[test]
+    fn test_hbase_shell_create_statement() {
+        let table = TableSpec {
+            name: "tx".to_string(),
+            key_alphabet: Some(BASE58_ALPHABET),
+        };
+        let schema = TableSchema {
+            regions: 2,
+            ..TableSchema::default()
+        };
+        assert_eq!(
+            hbase_shell_create_statement(&table, &schema),
+            "create 'tx', {NAME => 'x', VERSIONS => 1, TTL => 2147483647, BLOOMFILTER => 'ROW', \
+             COMPRESSION => 'NONE', IN_MEMORY => 'false', BLOCKCACHE => 'true'}, SPLITS => ['W']"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use {
+    std::{
+        collections::HashMap,
+        fmt,
+        str::FromStr,
+        time::Duration,
+    },
+};
+
+pub const COLUMN_FAMILY_NAME: &str = "x";
+
+/// HBase codec used when none is configured. Cells are already compressed by the writer.
+pub const DEFAULT_HBASE_COMPRESSION: &str = "NONE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloomFilterType {
+    None,
+    Row,
+    RowCol,
+}
+
+impl fmt::Display for BloomFilterType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BloomFilterType::None => write!(f, "NONE"),
+            BloomFilterType::Row => write!(f, "ROW"),
+            BloomFilterType::RowCol => write!(f, "ROWCOL"),
+        }
+    }
+}
+
+impl FromStr for BloomFilterType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NONE" => Ok(BloomFilterType::None),
+            "ROW" => Ok(BloomFilterType::Row),
+            "ROWCOL" => Ok(BloomFilterType::RowCol),
+            _ => Err(format!("unknown bloom filter type: {s}")),
+        }
+    }
+}
+
+/// Settings of the `x` column family of a table, plus the number of regions to pre-split it into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    pub max_versions: i32,
+    /// `None` keeps cells forever.
+    pub time_to_live: Option<Duration>,
+    pub bloom_filter: BloomFilterType,
+    /// HBase codec name, e.g. `NONE`, `GZ`, `SNAPPY`, `LZ4` or `ZSTD`.
+    pub compression: String,
+    pub in_memory: bool,
+    pub block_cache_enabled: bool,
+    pub regions: usize,
+}
+
+impl Default for TableSchema {
+    fn default() -> Self {
+        Self {
+            max_versions: 1,
+            time_to_live: None,
+            bloom_filter: BloomFilterType::Row,
+            compression: DEFAULT_HBASE_COMPRESSION.to_string(),
+            in_memory: false,
+            block_cache_enabled: true,
+            regions: 1,
+        }
+    }
+}
+
+impl TableSchema {
+    /// TTL in seconds as HBase expects it, where `i32::MAX` means forever.
+    pub fn time_to_live_secs(&self) -> i32 {
+        self.time_to_live
+            .map(|ttl| ttl.as_secs().min(i32::MAX as u64) as i32)
+            .unwrap_or(i32::MAX)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SchemaConfig {
+    pub default_schema: TableSchema,
+    /// Per table overrides of `default_schema`, keyed by table name.
+    pub table_schemas: HashMap<String, TableSchema>,
+}
+
+impl SchemaConfig {
+    pub fn schema_for(&self, table_name: &str) -> &TableSchema {
+        self.table_schemas
+            .get(table_name)
+            .unwrap_or(&self.default_schema)
+    }
+}
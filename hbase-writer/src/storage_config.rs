@@ -3,6 +3,7 @@ use {
     crate::{
         uploader_config::UploaderConfig,
         cache_config::LedgerCacheConfig,
+        thrift_config::ThriftConfig,
     },
     std::{
         time::{Duration},
@@ -18,6 +19,9 @@ pub struct LedgerStorageConfig {
     pub address: String,
     pub uploader_config: UploaderConfig,
     pub cache_config: LedgerCacheConfig,
+    pub thrift_config: ThriftConfig,
+    /// Trained zstd dictionaries to load, see `CompressionMode::ZstdDictionary`.
+    pub zstd_dictionaries: Vec<String>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -28,6 +32,8 @@ impl Default for LedgerStorageConfig {
             address: DEFAULT_ADDRESS.to_string(),
             uploader_config: UploaderConfig::default(),
             cache_config: LedgerCacheConfig::default(),
+            thrift_config: ThriftConfig::default(),
+            zstd_dictionaries: vec![],
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,266 @@
+//! Rules deciding which tables each transaction is stored in, loaded from a JSON file like:
+//!
+//! ```json
+//! {
+//!     "defaults": { "tx_full": "exclude" },
+//!     "rules": [
+//!         {
+//!             "targets": ["tx_full"],
+//!             "action": "include",
+//!             "when": {
+//!                 "invokes_any": ["<our program>"],
+//!                 "signed_by_none": ["<bot>"]
+//!             }
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! For each target, the first rule listing it whose conditions all hold decides, otherwise the
+//! default of the target does, which is to include. The rules apply on top of the other filters
+//! of `UploaderConfig`: they can leave out more transactions, never add any back.
+
+use {
+    serde::Deserializer,
+    serde_derive::Deserialize,
+    solana_pubkey::Pubkey,
+    solana_storage_utils::program_index::invoked_programs,
+    solana_transaction_status::VersionedTransactionWithStatusMeta,
+    std::{
+        collections::{BTreeSet, HashMap, HashSet},
+        path::Path,
+        str::FromStr,
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum TxFilterRulesError {
+    #[error("I/O: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid filter rules: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// What a rule decides for.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleTarget {
+    Tx,
+    #[serde(rename = "tx-by-addr")]
+    TxByAddr,
+    TxFull,
+    Cache,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Include,
+    Exclude,
+}
+
+/// Conditions on a transaction, all of which must hold. Unset ones always do.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxConditions {
+    /// Invokes one of the programs, directly or through CPI
+    #[serde(deserialize_with = "deserialize_pubkeys")]
+    pub invokes_any: Option<HashSet<Pubkey>>,
+    #[serde(deserialize_with = "deserialize_pubkeys")]
+    pub invokes_none: Option<HashSet<Pubkey>>,
+    #[serde(deserialize_with = "deserialize_pubkeys")]
+    pub signed_by_any: Option<HashSet<Pubkey>>,
+    #[serde(deserialize_with = "deserialize_pubkeys")]
+    pub signed_by_none: Option<HashSet<Pubkey>>,
+    #[serde(deserialize_with = "deserialize_pubkeys")]
+    pub writes_any: Option<HashSet<Pubkey>>,
+    #[serde(deserialize_with = "deserialize_pubkeys")]
+    pub writes_none: Option<HashSet<Pubkey>>,
+    pub success: Option<bool>,
+    pub vote: Option<bool>,
+    /// Fee bounds in lamports, inclusive
+    pub min_fee: Option<u64>,
+    pub max_fee: Option<u64>,
+    pub min_compute_units: Option<u64>,
+    pub max_compute_units: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TxFilterRule {
+    pub targets: Vec<RuleTarget>,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub when: TxConditions,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TxFilterRules {
+    pub defaults: HashMap<RuleTarget, RuleAction>,
+    pub rules: Vec<TxFilterRule>,
+}
+
+/// What the conditions are checked against, computed once per transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxFacts {
+    pub programs: BTreeSet<Pubkey>,
+    pub signers: HashSet<Pubkey>,
+    pub writable: HashSet<Pubkey>,
+    pub success: bool,
+    pub vote: bool,
+    pub fee: u64,
+    pub compute_units: u64,
+}
+
+impl TxFacts {
+    pub fn new(transaction_with_meta: &VersionedTransactionWithStatusMeta, is_voting: bool) -> Self {
+        let message = &transaction_with_meta.transaction.message;
+        let meta = &transaction_with_meta.meta;
+        let static_keys = message.static_account_keys();
+        let num_signers = message.header().num_required_signatures as usize;
+
+        Self {
+            programs: invoked_programs(transaction_with_meta),
+            signers: static_keys.iter().take(num_signers).copied().collect(),
+            writable: static_keys
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| message.is_maybe_writable(*index, None))
+                .map(|(_, address)| *address)
+                .chain(meta.loaded_addresses.writable.iter().copied())
+                .collect(),
+            success: meta.status.is_ok(),
+            vote: is_voting,
+            fee: meta.fee,
+            compute_units: meta.compute_units_consumed.unwrap_or_default(),
+        }
+    }
+}
+
+impl TxConditions {
+    pub fn matches(&self, facts: &TxFacts) -> bool {
+        let any = |addresses: &Option<HashSet<Pubkey>>, set: &dyn Fn(&Pubkey) -> bool| {
+            addresses
+                .as_ref()
+                .is_none_or(|addresses| addresses.iter().any(set))
+        };
+        let none = |addresses: &Option<HashSet<Pubkey>>, set: &dyn Fn(&Pubkey) -> bool| {
+            addresses
+                .as_ref()
+                .is_none_or(|addresses| !addresses.iter().any(set))
+        };
+        let invokes = |address: &Pubkey| facts.programs.contains(address);
+        let signed_by = |address: &Pubkey| facts.signers.contains(address);
+        let writes = |address: &Pubkey| facts.writable.contains(address);
+
+        any(&self.invokes_any, &invokes)
+            && none(&self.invokes_none, &invokes)
+            && any(&self.signed_by_any, &signed_by)
+            && none(&self.signed_by_none, &signed_by)
+            && any(&self.writes_any, &writes)
+            && none(&self.writes_none, &writes)
+            && self.success.is_none_or(|success| success == facts.success)
+            && self.vote.is_none_or(|vote| vote == facts.vote)
+            && self.min_fee.is_none_or(|min_fee| facts.fee >= min_fee)
+            && self.max_fee.is_none_or(|max_fee| facts.fee <= max_fee)
+            && self
+                .min_compute_units
+                .is_none_or(|min_compute_units| facts.compute_units >= min_compute_units)
+            && self
+                .max_compute_units
+                .is_none_or(|max_compute_units| facts.compute_units <= max_compute_units)
+    }
+}
+
+impl TxFilterRules {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TxFilterRulesError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, TxFilterRulesError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Whether a transaction with the given facts is stored in `target`.
+    pub fn allows(&self, target: RuleTarget, facts: &TxFacts) -> bool {
+        let action = self
+            .rules
+            .iter()
+            .find(|rule| rule.targets.contains(&target) && rule.when.matches(facts))
+            .map(|rule| rule.action)
+            .or_else(|| self.defaults.get(&target).copied())
+            .unwrap_or(RuleAction::Include);
+        action == RuleAction::Include
+    }
+}
+
+pub(crate) fn deserialize_pubkeys<'de, D>(deserializer: D) -> Result<Option<HashSet<Pubkey>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <Vec<String> as serde::Deserialize>::deserialize(deserializer)?
+        .iter()
+        .map(|address| {
+            Pubkey::from_str(address)
+                .map_err(|err| serde::de::Error::custom(format!("invalid address {address}: {err}")))
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn facts(programs: &[Pubkey], signers: &[Pubkey]) -> TxFacts {
+        TxFacts {
+            programs: programs.iter().copied().collect(),
+            signers: signers.iter().copied().collect(),
+            writable: signers.iter().copied().collect(),
+            success: true,
+            vote: false,
+            fee: 5000,
+            compute_units: 1000,
+        }
+    }
+
+    #[test]
+    fn test_tx_filter_rules() {
+        let program = Pubkey::new_unique();
+        let bot = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let rules = TxFilterRules::from_json(&format!(
+            r#"{{
+                "defaults": {{ "tx_full": "exclude" }},
+                "rules": [
+                    {{ "targets": ["cache", "tx-by-addr"], "action": "exclude", "when": {{ "vote": true }} }},
+                    {{
+                        "targets": ["tx_full"],
+                        "action": "include",
+                        "when": {{ "invokes_any": ["{program}"], "signed_by_none": ["{bot}"], "min_fee": 5000 }}
+                    }}
+                ]
+            }}"#
+        ))
+        .unwrap();
+
+        assert!(rules.allows(RuleTarget::TxFull, &facts(&[program], &[user])));
+        assert!(!rules.allows(RuleTarget::TxFull, &facts(&[program], &[user, bot])));
+        assert!(!rules.allows(RuleTarget::TxFull, &facts(&[], &[user])));
+        assert!(rules.allows(RuleTarget::Tx, &facts(&[], &[bot])));
+
+        let vote = TxFacts {
+            vote: true,
+            ..facts(&[], &[user])
+        };
+        assert!(!rules.allows(RuleTarget::Cache, &vote));
+        assert!(!rules.allows(RuleTarget::TxByAddr, &vote));
+        assert!(rules.allows(RuleTarget::Tx, &vote));
+
+        assert!(TxFilterRules::from_json(r#"{ "rules": [{ "targets": ["tx"], "action": "exclude", "when": { "invokes_any": ["bad"] } }] }"#).is_err());
+        assert!(TxFilterRules::from_json(r#"{ "rules": [{ "targets": ["tx"], "action": "exclude", "when": { "unknown": 1 } }] }"#).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! Routes sending the `tx_full` and `tx-by-addr` rows of some transactions to their own tables,
+//! loaded from a JSON file like:
+//!
+//! ```json
+//! [
+//!     {
+//!         "programs": ["<dex program>"],
+//!         "tx_full_table_name": "tx_full_dex",
+//!         "tx_by_addr_table_name": "tx-by-addr-dex"
+//!     }
+//! ]
+//! ```
+//!
+//! The first route matching a transaction decides where its rows go. Routes only change the table
+//! rows are written to, the filters of `UploaderConfig` still decide which rows are written.
+
+use {
+    crate::tx_filter_rules::deserialize_pubkeys,
+    serde::Deserializer,
+    serde_derive::Deserialize,
+    solana_pubkey::Pubkey,
+    std::{
+        collections::{BTreeSet, HashSet},
+        path::Path,
+    },
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum TxRoutesError {
+    #[error("I/O: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid transaction routes: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TxRoute {
+    /// Transactions invoking one of these programs, directly or through CPI, match
+    #[serde(default, deserialize_with = "deserialize_pubkey_set")]
+    pub programs: HashSet<Pubkey>,
+    /// Transactions with one of these account keys match
+    #[serde(default, deserialize_with = "deserialize_pubkey_set")]
+    pub addresses: HashSet<Pubkey>,
+    /// Table written instead of `full_tx_table_name`, which is kept if `None`
+    #[serde(default)]
+    pub tx_full_table_name: Option<String>,
+    /// Table written instead of `tx_by_addr_table_name`, which is kept if `None`
+    #[serde(default)]
+    pub tx_by_addr_table_name: Option<String>,
+}
+
+impl TxRoute {
+    pub fn matches(&self, programs: &BTreeSet<Pubkey>, account_keys: &[Pubkey]) -> bool {
+        self.programs.iter().any(|program| programs.contains(program))
+            || account_keys.iter().any(|address| self.addresses.contains(address))
+    }
+}
+
+pub fn load_tx_routes(path: impl AsRef<Path>) -> Result<Vec<TxRoute>, TxRoutesError> {
+    parse_tx_routes(&std::fs::read_to_string(path)?)
+}
+
+pub fn parse_tx_routes(json: &str) -> Result<Vec<TxRoute>, TxRoutesError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+fn deserialize_pubkey_set<'de, D>(deserializer: D) -> Result<HashSet<Pubkey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_pubkeys(deserializer).map(Option::unwrap_or_default)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tx_routes() {
+        let program = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let routes = parse_tx_routes(&format!(
+            r#"[
+                {{ "programs": ["{program}"], "tx_full_table_name": "tx_full_dex" }},
+                {{ "addresses": ["{pool}"], "tx_by_addr_table_name": "tx-by-addr-pool" }}
+            ]"#
+        ))
+        .unwrap();
+
+        assert_eq!(routes[0].tx_full_table_name.as_deref(), Some("tx_full_dex"));
+        assert_eq!(routes[0].tx_by_addr_table_name, None);
+        assert!(routes[0].matches(&BTreeSet::from([program]), &[]));
+        assert!(!routes[0].matches(&BTreeSet::new(), &[program]));
+        assert!(routes[1].matches(&BTreeSet::new(), &[Pubkey::new_unique(), pool]));
+        assert!(parse_tx_routes(r#"[{ "programs": ["bad"] }]"#).is_err());
+        assert!(parse_tx_routes(r#"[{ "tx_full_table": "tx_full_dex" }]"#).is_err());
+    }
+}
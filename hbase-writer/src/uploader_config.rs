@@ -1,10 +1,29 @@
 
 use {
+    crate::{hot_accounts::HotAccountPolicy, tx_filter_rules::TxFilterRules, tx_routes::TxRoute},
+    solana_clock::DEFAULT_SLOTS_PER_EPOCH,
     solana_pubkey::{
         Pubkey,
     },
+    solana_storage_utils::{
+        balance_history::BALANCE_HISTORY_TABLE_NAME,
+        token_balance_history::TOKEN_BALANCE_HISTORY_TABLE_NAME,
+        rewards::REWARDS_TABLE_NAME,
+        leader_index::LEADER_INDEX_TABLE_NAME,
+        block_stats::BLOCK_STATS_TABLE_NAME,
+        memo_index::MEMO_INDEX_TABLE_NAME,
+        hot_accounts::SUPPRESSED_ADDRESSES_TABLE_NAME,
+        slot_index::SLOT_INDEX_TABLE_NAME,
+        block_index::BLOCK_INDEX_TABLE_NAME,
+        block_time::BLOCK_TIME_TABLE_NAME,
+        compression::CompressionPolicy,
+        program_index::TX_BY_PROGRAM_TABLE_NAME,
+        token_index::{TX_BY_MINT_TABLE_NAME, TX_BY_OWNER_TABLE_NAME},
+    },
     std::{
-        collections::HashSet,
+        collections::{BTreeSet, HashSet},
+        path::PathBuf,
+        time::Duration,
     },
 };
 
@@ -17,12 +36,31 @@ pub const FULL_TX_TABLE_NAME: &str = "tx_full";
 pub struct FilterTxIncludeExclude {
     pub exclude: bool,
     pub addrs: HashSet<Pubkey>,
+    /// Address list file with more addresses, see `crate::address_filter`.
+    pub file: Option<PathBuf>,
+}
+
+impl FilterTxIncludeExclude {
+    pub fn allows(&self, address: &Pubkey) -> bool {
+        self.addrs.contains(address) != self.exclude
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct UploaderConfig {
     pub tx_full_filter: Option<FilterTxIncludeExclude>,
     pub tx_by_addr_filter: Option<FilterTxIncludeExclude>,
+    /// Reload the files of `tx_full_filter` and `tx_by_addr_filter` this often.
+    pub address_filter_reload_interval: Option<Duration>,
+    /// Reload the files of `tx_full_filter` and `tx_by_addr_filter` on SIGHUP.
+    pub reload_address_filters_on_sighup: bool,
+    /// Programs indexed in `tx_by_program_table_name`, all of them if `None`.
+    pub tx_by_program_filter: Option<FilterTxIncludeExclude>,
+    /// Rules further limiting which transactions `tx`, `tx-by-addr`, `tx_full` and the cache get.
+    pub tx_filter_rules: Option<TxFilterRules>,
+    /// Tables the `tx_full` and `tx-by-addr` rows of some transactions are written to instead,
+    /// the first matching route applies.
+    pub tx_routes: Vec<TxRoute>,
     pub disable_tx: bool,
     pub disable_tx_by_addr: bool,
     pub disable_blocks: bool,
@@ -31,7 +69,58 @@ pub struct UploaderConfig {
     pub tx_table_name: String,
     pub tx_by_addr_table_name: String,
     pub full_tx_table_name: String,
+    /// Index transactions under the owners of the token accounts in their token balances, so a
+    /// wallet's token transfers show up in its history.
+    pub index_token_owners: bool,
+    /// Index transactions under the mints in their token balances.
+    pub index_token_mints: bool,
+    /// Write the token owner and mint rows to `tx_by_addr_table_name` instead of
+    /// `tx_by_owner_table_name` and `tx_by_mint_table_name`.
+    pub token_index_in_tx_by_addr: bool,
+    pub tx_by_owner_table_name: String,
+    pub tx_by_mint_table_name: String,
+    /// Index transactions under the programs they invoked, including through CPI.
+    pub index_programs: bool,
+    pub tx_by_program_table_name: String,
+    /// Index blocks by their block time, to look up slots by time.
+    pub enable_block_time_index: bool,
+    pub block_time_table_name: String,
+    /// Index blocks by blockhash and block height.
+    pub enable_block_index: bool,
+    pub block_index_table_name: String,
+    /// Index block rewards by recipient and epoch.
+    pub enable_rewards_index: bool,
+    pub rewards_table_name: String,
+    /// Slots per epoch of the cluster, to key the rewards by epoch.
+    pub slots_per_epoch: u64,
+    /// Index blocks by their leader.
+    pub enable_leader_index: bool,
+    pub leader_index_table_name: String,
+    /// Summarize each block into a stats row.
+    pub enable_block_stats: bool,
+    pub block_stats_table_name: String,
+    /// Index transactions by their memos.
+    pub enable_memo_index: bool,
+    pub memo_index_table_name: String,
+    /// Record the lamport balance changes of each address.
+    pub enable_balance_history: bool,
+    pub balance_history_table_name: String,
+    /// Record the token balance changes of each owner, by mint.
+    pub enable_token_balance_history: bool,
+    pub token_balance_history_table_name: String,
+    /// Stop indexing the addresses in too many transactions in `tx_by_addr_table_name`, the
+    /// tables its rows are routed to and the token owner and mint tables, and record when in
+    /// `suppressed_addresses_table_name`. The program index is not suppressed.
+    pub hot_account_policy: Option<HotAccountPolicy>,
+    pub suppressed_addresses_table_name: String,
     pub use_md5_row_key_salt: bool,
+    /// Slots of the uploaded blocks, written with `use_md5_row_key_salt` since the `blocks` row
+    /// keys are then no longer sorted by slot, see `solana_storage_utils::slot_index`.
+    pub slot_index_table_name: String,
+    /// Spread the rows of `tx_by_addr_table_name`, and of the tables `tx-by-addr` rows are
+    /// routed to, over this many salt buckets, see `solana_storage_utils::key::tx_by_addr_key`.
+    /// Readers must be configured with the same number.
+    pub tx_by_addr_salt_buckets: Option<u16>,
     pub filter_program_accounts: bool,
     pub filter_voting_tx: bool,
     pub filter_error_tx: bool,
@@ -39,6 +128,12 @@ pub struct UploaderConfig {
     pub use_tx_compression: bool,
     pub use_tx_by_addr_compression: bool,
     pub use_tx_full_compression: bool,
+    pub blocks_compression: CompressionPolicy,
+    pub tx_compression: CompressionPolicy,
+    pub tx_by_addr_compression: CompressionPolicy,
+    pub tx_full_compression: CompressionPolicy,
+    /// Write cells in the checksummed envelope of `solana_storage_utils::cell_envelope`.
+    pub use_cell_envelope: bool,
     pub hbase_write_to_wal: bool,
 }
 
@@ -47,6 +142,11 @@ impl Default for UploaderConfig {
         Self {
             tx_full_filter: None,
             tx_by_addr_filter: None,
+            address_filter_reload_interval: None,
+            reload_address_filters_on_sighup: false,
+            tx_by_program_filter: None,
+            tx_filter_rules: None,
+            tx_routes: vec![],
             disable_tx: false,
             disable_tx_by_addr: false,
             disable_blocks: false,
@@ -55,7 +155,35 @@ impl Default for UploaderConfig {
             tx_table_name: TX_TABLE_NAME.to_string(),
             tx_by_addr_table_name: TX_BY_ADDR_TABLE_NAME.to_string(),
             full_tx_table_name: FULL_TX_TABLE_NAME.to_string(),
+            index_token_owners: false,
+            index_token_mints: false,
+            token_index_in_tx_by_addr: false,
+            tx_by_owner_table_name: TX_BY_OWNER_TABLE_NAME.to_string(),
+            tx_by_mint_table_name: TX_BY_MINT_TABLE_NAME.to_string(),
+            index_programs: false,
+            tx_by_program_table_name: TX_BY_PROGRAM_TABLE_NAME.to_string(),
+            enable_block_time_index: false,
+            block_time_table_name: BLOCK_TIME_TABLE_NAME.to_string(),
+            enable_block_index: false,
+            block_index_table_name: BLOCK_INDEX_TABLE_NAME.to_string(),
+            enable_rewards_index: false,
+            rewards_table_name: REWARDS_TABLE_NAME.to_string(),
+            slots_per_epoch: DEFAULT_SLOTS_PER_EPOCH,
+            enable_leader_index: false,
+            leader_index_table_name: LEADER_INDEX_TABLE_NAME.to_string(),
+            enable_block_stats: false,
+            block_stats_table_name: BLOCK_STATS_TABLE_NAME.to_string(),
+            enable_memo_index: false,
+            memo_index_table_name: MEMO_INDEX_TABLE_NAME.to_string(),
+            enable_balance_history: false,
+            balance_history_table_name: BALANCE_HISTORY_TABLE_NAME.to_string(),
+            enable_token_balance_history: false,
+            token_balance_history_table_name: TOKEN_BALANCE_HISTORY_TABLE_NAME.to_string(),
+            hot_account_policy: None,
+            suppressed_addresses_table_name: SUPPRESSED_ADDRESSES_TABLE_NAME.to_string(),
             use_md5_row_key_salt: false,
+            slot_index_table_name: SLOT_INDEX_TABLE_NAME.to_string(),
+            tx_by_addr_salt_buckets: None,
             filter_program_accounts: false,
             filter_voting_tx: false,
             filter_error_tx: false,
@@ -63,7 +191,77 @@ impl Default for UploaderConfig {
             use_tx_compression: true,
             use_tx_by_addr_compression: true,
             use_tx_full_compression: true,
+            blocks_compression: CompressionPolicy::default(),
+            tx_compression: CompressionPolicy::default(),
+            tx_by_addr_compression: CompressionPolicy::default(),
+            tx_full_compression: CompressionPolicy::default(),
+            use_cell_envelope: false,
             hbase_write_to_wal: true,
         }
     }
-}
\ No newline at end of file
+}
+
+impl UploaderConfig {
+    /// Table the token owner rows are written to, if they are.
+    pub fn token_owner_table_name(&self) -> Option<&str> {
+        self.token_index_table_name(self.index_token_owners, &self.tx_by_owner_table_name)
+    }
+
+    /// Table the token mint rows are written to, if they are.
+    pub fn token_mint_table_name(&self) -> Option<&str> {
+        self.token_index_table_name(self.index_token_mints, &self.tx_by_mint_table_name)
+    }
+
+    fn token_index_table_name<'a>(&'a self, enabled: bool, table_name: &'a str) -> Option<&'a str> {
+        if !enabled {
+            None
+        } else if self.token_index_in_tx_by_addr {
+            (!self.disable_tx_by_addr).then_some(self.tx_by_addr_table_name.as_str())
+        } else {
+            Some(table_name)
+        }
+    }
+
+    /// Tables `tx_full` rows are routed to, besides `full_tx_table_name`.
+    pub fn routed_full_tx_table_names(&self) -> BTreeSet<&str> {
+        self.tx_routes
+            .iter()
+            .filter_map(|route| route.tx_full_table_name.as_deref())
+            .filter(|table| *table != self.full_tx_table_name)
+            .collect()
+    }
+
+    /// Tables `tx-by-addr` rows are routed to, besides `tx_by_addr_table_name`.
+    pub fn routed_tx_by_addr_table_names(&self) -> BTreeSet<&str> {
+        self.tx_routes
+            .iter()
+            .filter_map(|route| route.tx_by_addr_table_name.as_deref())
+            .filter(|table| *table != self.tx_by_addr_table_name)
+            .collect()
+    }
+
+    pub fn blocks_compression_policy(&self) -> CompressionPolicy {
+        compression_policy(self.use_blocks_compression, self.blocks_compression)
+    }
+
+    pub fn tx_compression_policy(&self) -> CompressionPolicy {
+        compression_policy(self.use_tx_compression, self.tx_compression)
+    }
+
+    pub fn tx_by_addr_compression_policy(&self) -> CompressionPolicy {
+        compression_policy(self.use_tx_by_addr_compression, self.tx_by_addr_compression)
+    }
+
+    pub fn tx_full_compression_policy(&self) -> CompressionPolicy {
+        compression_policy(self.use_tx_full_compression, self.tx_full_compression)
+    }
+}
+
+/// The `use_*_compression` switches still turn compression off regardless of the table policy.
+fn compression_policy(enabled: bool, policy: CompressionPolicy) -> CompressionPolicy {
+    if enabled {
+        policy
+    } else {
+        CompressionPolicy::none()
+    }
+}
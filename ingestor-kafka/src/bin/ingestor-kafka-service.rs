@@ -5,25 +5,35 @@ use {
         producer::KafkaProducer,
         config::Config,
         cli::{DefaultBlockUploaderArgs, block_uploader_app},
+        commands::{process_thrift_arguments, run_subcommand},
     },
     solana_hbase_writer::{
         ledger_storage::LedgerStorage,
         storage_config::LedgerStorageConfig,
         uploader_config::FilterTxIncludeExclude,
         uploader_config::UploaderConfig,
+        tx_filter_rules::TxFilterRules,
+        tx_routes::load_tx_routes,
+        hot_accounts::HotAccountPolicy,
         cache_config::LedgerCacheConfig,
+        thrift_config::ThriftConfig,
     },
     solana_storage_writer::{
         LedgerStorageAdapter,
     },
+    solana_storage_utils::{
+        compression::{CompressionMode, CompressionPolicy},
+    },
     std::sync::Arc,
     std::{
         collections::{HashSet},
+        path::PathBuf,
     },
     solana_pubkey::{
         Pubkey,
     },
     clap::{
+        value_t,
         value_t_or_exit,
         values_t,
         ArgMatches
@@ -35,7 +45,10 @@ use {
 async fn create_consumer(
     config: Arc<Config>,
     uploader_config: UploaderConfig,
-    cache_config: LedgerCacheConfig
+    cache_config: LedgerCacheConfig,
+    thrift_config: ThriftConfig,
+    zstd_dictionaries: Vec<String>,
+    upload_batch_size: usize,
 ) -> KafkaConsumer {
     info!("Connecting to kafka: {}", &config.kafka_brokers);
 
@@ -47,6 +60,8 @@ async fn create_consumer(
         address: config.hbase_address.clone(),
         uploader_config: uploader_config.clone(),
         cache_config: cache_config.clone(),
+        thrift_config,
+        zstd_dictionaries,
     };
     let storage = LedgerStorage::new_with_config(storage_config).await;
 
@@ -55,7 +70,8 @@ async fn create_consumer(
         &config.kafka_group_id,
         &[&config.kafka_consume_topic],
         Box::new(storage) as Box<dyn LedgerStorageAdapter>,
-        kproducer
+        kproducer,
+        upload_batch_size,
     ).await
 }
 
@@ -70,13 +86,19 @@ fn create_producer(config: Arc<Config>) -> KafkaProducer {
 async fn handle_message_receiving(
     config: Arc<Config>,
     uploader_config: UploaderConfig,
-    cache_config: LedgerCacheConfig) {
+    cache_config: LedgerCacheConfig,
+    thrift_config: ThriftConfig,
+    zstd_dictionaries: Vec<String>,
+    upload_batch_size: usize) {
     debug!("Started consuming messages");
 
     let kconsumer = create_consumer(
         config.clone(),
         uploader_config.clone(),
-        cache_config.clone()
+        cache_config.clone(),
+        thrift_config,
+        zstd_dictionaries,
+        upload_batch_size,
     ).await;
 
     kconsumer.consume().await;
@@ -96,6 +118,50 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
     let use_tx_by_addr_compression = !matches.is_present("disable_tx_by_addr_compression");
     let use_tx_full_compression = !matches.is_present("disable_tx_full_compression");
     let hbase_write_to_wal = !matches.is_present("hbase_skip_wal");
+    let use_cell_envelope = matches.is_present("use_cell_envelope");
+    let index_token_owners = matches.is_present("index_token_owners");
+    let index_token_mints = matches.is_present("index_token_mints");
+    let token_index_in_tx_by_addr = matches.is_present("token_index_in_tx_by_addr");
+    let index_programs = matches.is_present("index_programs");
+    let enable_block_time_index = matches.is_present("enable_block_time_index");
+    let enable_block_index = matches.is_present("enable_block_index");
+    let enable_rewards_index = matches.is_present("enable_rewards_index");
+    let slots_per_epoch = value_t_or_exit!(matches, "slots_per_epoch", u64);
+    let enable_leader_index = matches.is_present("enable_leader_index");
+    let enable_block_stats = matches.is_present("enable_block_stats");
+    let enable_memo_index = matches.is_present("enable_memo_index");
+    let enable_balance_history = matches.is_present("enable_balance_history");
+    let enable_token_balance_history = matches.is_present("enable_token_balance_history");
+    let hot_account_policy = matches.is_present("suppress_hot_accounts").then(|| {
+        let default_policy = HotAccountPolicy::default();
+        let max_transactions = value_t!(matches, "hot_account_max_transactions", u64)
+            .unwrap_or(default_policy.max_transactions);
+        HotAccountPolicy {
+            window_slots: value_t!(matches, "hot_account_window_slots", u64)
+                .unwrap_or(default_policy.window_slots),
+            max_transactions,
+            resume_transactions: value_t!(matches, "hot_account_resume_transactions", u64)
+                .unwrap_or(max_transactions / 2),
+        }
+    });
+    let tx_by_addr_salt_buckets = value_t!(matches, "tx_by_addr_salt_buckets", u16).ok();
+    let tx_routes = matches
+        .value_of("tx_routes")
+        .map(|path| load_tx_routes(path).expect("valid transaction routes"))
+        .unwrap_or_default();
+    let tx_filter_rules = matches
+        .value_of("tx_filter_rules")
+        .map(|path| TxFilterRules::from_file(path).expect("valid transaction filter rules"));
+
+    let compression_min_size = value_t_or_exit!(matches, "compression_min_size", usize);
+    let compression_policy = |name: &str| CompressionPolicy {
+        mode: value_t_or_exit!(matches, name, CompressionMode),
+        min_size: compression_min_size,
+    };
+    let blocks_compression = compression_policy("blocks_compression");
+    let tx_compression = compression_policy("tx_compression");
+    let tx_by_addr_compression = compression_policy("tx_by_addr_compression");
+    let tx_full_compression = compression_policy("tx_full_compression");
 
     let filter_tx_full_include_addrs: HashSet<Pubkey> =
         values_t!(matches, "filter_tx_full_include_addr", Pubkey)
@@ -125,23 +191,56 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
             .cloned()
             .collect();
 
+    let filter_tx_by_program_include_addrs: HashSet<Pubkey> =
+        values_t!(matches, "filter_tx_by_program_include_addr", Pubkey)
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .collect();
+
+    let filter_tx_by_program_exclude_addrs: HashSet<Pubkey> =
+        values_t!(matches, "filter_tx_by_program_exclude_addr", Pubkey)
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .collect();
+
     let tx_full_filter = create_filter(
         filter_tx_full_exclude_addrs,
-        filter_tx_full_include_addrs
+        filter_tx_full_include_addrs,
+        matches.value_of("filter_tx_full_exclude_addr_file"),
+        matches.value_of("filter_tx_full_include_addr_file"),
     );
     let tx_by_addr_filter = create_filter(
         filter_tx_by_addr_exclude_addrs,
-        filter_tx_by_addr_include_addrs
+        filter_tx_by_addr_include_addrs,
+        matches.value_of("filter_tx_by_addr_exclude_addr_file"),
+        matches.value_of("filter_tx_by_addr_include_addr_file"),
+    );
+    let address_filter_reload_interval = value_t!(matches, "address_filter_reload_interval", u64)
+        .ok()
+        .map(std::time::Duration::from_secs);
+    let reload_address_filters_on_sighup = matches.is_present("reload_address_filters_on_sighup");
+
+    let tx_by_program_filter = create_filter(
+        filter_tx_by_program_exclude_addrs,
+        filter_tx_by_program_include_addrs,
+        None,
+        None,
     );
 
     UploaderConfig {
         tx_full_filter,
         tx_by_addr_filter,
+        address_filter_reload_interval,
+        reload_address_filters_on_sighup,
+        tx_by_program_filter,
         disable_tx,
         disable_tx_by_addr,
         disable_blocks,
         enable_full_tx,
         use_md5_row_key_salt,
+        tx_by_addr_salt_buckets,
         filter_program_accounts,
         filter_voting_tx,
         filter_error_tx,
@@ -149,7 +248,28 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
         use_tx_compression,
         use_tx_by_addr_compression,
         use_tx_full_compression,
+        blocks_compression,
+        tx_compression,
+        tx_by_addr_compression,
+        tx_full_compression,
         hbase_write_to_wal,
+        use_cell_envelope,
+        index_token_owners,
+        index_token_mints,
+        token_index_in_tx_by_addr,
+        index_programs,
+        enable_block_time_index,
+        enable_block_index,
+        enable_rewards_index,
+        slots_per_epoch,
+        enable_leader_index,
+        enable_block_stats,
+        enable_memo_index,
+        enable_balance_history,
+        enable_token_balance_history,
+        hot_account_policy,
+        tx_filter_rules,
+        tx_routes,
         ..Default::default()
     }
 }
@@ -191,9 +311,11 @@ fn process_cache_arguments(matches: &ArgMatches) -> LedgerCacheConfig {
 fn create_filter(
     filter_tx_exclude_addrs: HashSet<Pubkey>,
     filter_tx_include_addrs: HashSet<Pubkey>,
+    filter_tx_exclude_file: Option<&str>,
+    filter_tx_include_file: Option<&str>,
 ) -> Option<FilterTxIncludeExclude> {
-    let exclude_tx_addrs = !filter_tx_exclude_addrs.is_empty();
-    let include_tx_addrs = !filter_tx_include_addrs.is_empty();
+    let exclude_tx_addrs = !filter_tx_exclude_addrs.is_empty() || filter_tx_exclude_file.is_some();
+    let include_tx_addrs = !filter_tx_include_addrs.is_empty() || filter_tx_include_file.is_some();
 
     if exclude_tx_addrs || include_tx_addrs {
         let filter_tx_addrs = FilterTxIncludeExclude {
@@ -203,6 +325,12 @@ fn create_filter(
             } else {
                 filter_tx_include_addrs
             },
+            file: if exclude_tx_addrs {
+                filter_tx_exclude_file
+            } else {
+                filter_tx_include_file
+            }
+            .map(PathBuf::from),
         };
         Some(filter_tx_addrs)
     } else {
@@ -219,6 +347,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let uploader_config = process_uploader_arguments(&matches);
     let cache_config = process_cache_arguments(&matches);
+    let thrift_config = process_thrift_arguments(&matches);
+    let zstd_dictionaries = values_t!(matches, "zstd_dictionary", String).unwrap_or_default();
 
     env_logger::init();
 
@@ -226,7 +356,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let app_config = Arc::new(Config::new());
 
-    handle_message_receiving(app_config, uploader_config, cache_config).await;
+    if let (subcommand, Some(subcommand_matches)) = matches.subcommand() {
+        let storage_config = LedgerStorageConfig {
+            read_only: false,
+            timeout: None,
+            address: app_config.hbase_address.clone(),
+            uploader_config,
+            cache_config: LedgerCacheConfig::default(),
+            thrift_config,
+            zstd_dictionaries,
+        };
+        return run_subcommand(subcommand, subcommand_matches, storage_config).await;
+    }
+
+    handle_message_receiving(
+        app_config,
+        uploader_config,
+        cache_config,
+        thrift_config,
+        zstd_dictionaries,
+        value_t_or_exit!(matches, "upload_batch_size", usize),
+    ).await;
 
     Ok(())
 }
\ No newline at end of file
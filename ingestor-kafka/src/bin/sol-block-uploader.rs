@@ -9,6 +9,7 @@ use {
     ingestor_kafka::{
         config::Config,
         cli::{DefaultBlockUploaderArgs, block_uploader_app},
+        commands::{process_thrift_arguments, run_subcommand},
     },
     solana_transaction_status::{
         BlockEncodingOptions,
@@ -22,21 +23,28 @@ use {
         storage_config::LedgerStorageConfig,
         uploader_config::FilterTxIncludeExclude,
         uploader_config::UploaderConfig,
+        tx_filter_rules::TxFilterRules,
+        tx_routes::load_tx_routes,
+        hot_accounts::HotAccountPolicy,
         cache_config::LedgerCacheConfig,
     },
     solana_storage_writer::{
         LedgerStorageAdapter,
     },
+    solana_storage_utils::{
+        compression::{CompressionMode, CompressionPolicy},
+    },
     std::{
         sync::Arc,
         collections::{HashSet},
+        path::PathBuf,
         io,
         io::{Read},
     },
     solana_pubkey::{
         Pubkey,
     },
-    clap::{value_t_or_exit, values_t, ArgMatches},
+    clap::{value_t, value_t_or_exit, values_t, ArgMatches},
     serde::{
         Deserialize,
         Serialize,
@@ -68,6 +76,50 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
     let use_tx_by_addr_compression = !matches.is_present("disable_tx_by_addr_compression");
     let use_tx_full_compression = !matches.is_present("disable_tx_full_compression");
     let hbase_write_to_wal = !matches.is_present("hbase_skip_wal");
+    let use_cell_envelope = matches.is_present("use_cell_envelope");
+    let index_token_owners = matches.is_present("index_token_owners");
+    let index_token_mints = matches.is_present("index_token_mints");
+    let token_index_in_tx_by_addr = matches.is_present("token_index_in_tx_by_addr");
+    let index_programs = matches.is_present("index_programs");
+    let enable_block_time_index = matches.is_present("enable_block_time_index");
+    let enable_block_index = matches.is_present("enable_block_index");
+    let enable_rewards_index = matches.is_present("enable_rewards_index");
+    let slots_per_epoch = value_t_or_exit!(matches, "slots_per_epoch", u64);
+    let enable_leader_index = matches.is_present("enable_leader_index");
+    let enable_block_stats = matches.is_present("enable_block_stats");
+    let enable_memo_index = matches.is_present("enable_memo_index");
+    let enable_balance_history = matches.is_present("enable_balance_history");
+    let enable_token_balance_history = matches.is_present("enable_token_balance_history");
+    let hot_account_policy = matches.is_present("suppress_hot_accounts").then(|| {
+        let default_policy = HotAccountPolicy::default();
+        let max_transactions = value_t!(matches, "hot_account_max_transactions", u64)
+            .unwrap_or(default_policy.max_transactions);
+        HotAccountPolicy {
+            window_slots: value_t!(matches, "hot_account_window_slots", u64)
+                .unwrap_or(default_policy.window_slots),
+            max_transactions,
+            resume_transactions: value_t!(matches, "hot_account_resume_transactions", u64)
+                .unwrap_or(max_transactions / 2),
+        }
+    });
+    let tx_by_addr_salt_buckets = value_t!(matches, "tx_by_addr_salt_buckets", u16).ok();
+    let tx_routes = matches
+        .value_of("tx_routes")
+        .map(|path| load_tx_routes(path).expect("valid transaction routes"))
+        .unwrap_or_default();
+    let tx_filter_rules = matches
+        .value_of("tx_filter_rules")
+        .map(|path| TxFilterRules::from_file(path).expect("valid transaction filter rules"));
+
+    let compression_min_size = value_t_or_exit!(matches, "compression_min_size", usize);
+    let compression_policy = |name: &str| CompressionPolicy {
+        mode: value_t_or_exit!(matches, name, CompressionMode),
+        min_size: compression_min_size,
+    };
+    let blocks_compression = compression_policy("blocks_compression");
+    let tx_compression = compression_policy("tx_compression");
+    let tx_by_addr_compression = compression_policy("tx_by_addr_compression");
+    let tx_full_compression = compression_policy("tx_full_compression");
 
     let filter_tx_full_include_addrs: HashSet<Pubkey> =
         values_t!(matches, "filter_tx_full_include_addr", Pubkey)
@@ -97,18 +149,50 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
             .cloned()
             .collect();
 
+    let filter_tx_by_program_include_addrs: HashSet<Pubkey> =
+        values_t!(matches, "filter_tx_by_program_include_addr", Pubkey)
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .collect();
+
+    let filter_tx_by_program_exclude_addrs: HashSet<Pubkey> =
+        values_t!(matches, "filter_tx_by_program_exclude_addr", Pubkey)
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .collect();
+
     let tx_full_filter = create_filter(
         filter_tx_full_exclude_addrs,
-        filter_tx_full_include_addrs
+        filter_tx_full_include_addrs,
+        matches.value_of("filter_tx_full_exclude_addr_file"),
+        matches.value_of("filter_tx_full_include_addr_file"),
     );
     let tx_by_addr_filter = create_filter(
         filter_tx_by_addr_exclude_addrs,
-        filter_tx_by_addr_include_addrs
+        filter_tx_by_addr_include_addrs,
+        matches.value_of("filter_tx_by_addr_exclude_addr_file"),
+        matches.value_of("filter_tx_by_addr_include_addr_file"),
+    );
+    let address_filter_reload_interval = value_t!(matches, "address_filter_reload_interval", u64)
+        .ok()
+        .map(std::time::Duration::from_secs);
+    let reload_address_filters_on_sighup = matches.is_present("reload_address_filters_on_sighup");
+
+    let tx_by_program_filter = create_filter(
+        filter_tx_by_program_exclude_addrs,
+        filter_tx_by_program_include_addrs,
+        None,
+        None,
     );
 
     UploaderConfig {
         tx_full_filter,
         tx_by_addr_filter,
+        address_filter_reload_interval,
+        reload_address_filters_on_sighup,
+        tx_by_program_filter,
         disable_tx,
         disable_tx_by_addr,
         disable_blocks,
@@ -117,7 +201,37 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
         tx_table_name: "tx_test".to_string(),
         tx_by_addr_table_name: "tx-by-addr_test".to_string(),
         full_tx_table_name: "tx_full_test".to_string(),
+        index_token_owners,
+        index_token_mints,
+        token_index_in_tx_by_addr,
+        tx_by_owner_table_name: "tx-by-owner_test".to_string(),
+        tx_by_mint_table_name: "tx-by-mint_test".to_string(),
+        index_programs,
+        tx_by_program_table_name: "tx-by-program_test".to_string(),
+        enable_block_time_index,
+        block_time_table_name: "block-time_test".to_string(),
+        enable_block_index,
+        block_index_table_name: "block-index_test".to_string(),
+        enable_rewards_index,
+        slots_per_epoch,
+        rewards_table_name: "rewards_test".to_string(),
+        enable_leader_index,
+        leader_index_table_name: "leader-index_test".to_string(),
+        enable_block_stats,
+        block_stats_table_name: "block-stats_test".to_string(),
+        enable_memo_index,
+        memo_index_table_name: "memo-index_test".to_string(),
+        enable_balance_history,
+        balance_history_table_name: "balance-history_test".to_string(),
+        enable_token_balance_history,
+        token_balance_history_table_name: "token-balance-history_test".to_string(),
+        hot_account_policy,
+        suppressed_addresses_table_name: "suppressed-addresses_test".to_string(),
+        tx_filter_rules,
+        tx_routes,
         use_md5_row_key_salt: false,
+        slot_index_table_name: "slot-index_test".to_string(),
+        tx_by_addr_salt_buckets,
         filter_program_accounts: false,
         filter_voting_tx: false,
         filter_error_tx: false,
@@ -125,7 +239,12 @@ fn process_uploader_arguments(matches: &ArgMatches) -> UploaderConfig {
         use_tx_compression,
         use_tx_by_addr_compression,
         use_tx_full_compression,
+        blocks_compression,
+        tx_compression,
+        tx_by_addr_compression,
+        tx_full_compression,
         hbase_write_to_wal,
+        use_cell_envelope,
     }
 }
 
@@ -166,9 +285,11 @@ fn process_cache_arguments(matches: &ArgMatches) -> LedgerCacheConfig {
 fn create_filter(
     filter_tx_exclude_addrs: HashSet<Pubkey>,
     filter_tx_include_addrs: HashSet<Pubkey>,
+    filter_tx_exclude_file: Option<&str>,
+    filter_tx_include_file: Option<&str>,
 ) -> Option<FilterTxIncludeExclude> {
-    let exclude_tx_addrs = !filter_tx_exclude_addrs.is_empty();
-    let include_tx_addrs = !filter_tx_include_addrs.is_empty();
+    let exclude_tx_addrs = !filter_tx_exclude_addrs.is_empty() || filter_tx_exclude_file.is_some();
+    let include_tx_addrs = !filter_tx_include_addrs.is_empty() || filter_tx_include_file.is_some();
 
     if exclude_tx_addrs || include_tx_addrs {
         let filter_tx_addrs = FilterTxIncludeExclude {
@@ -178,6 +299,12 @@ fn create_filter(
             } else {
                 filter_tx_include_addrs
             },
+            file: if exclude_tx_addrs {
+                filter_tx_exclude_file
+            } else {
+                filter_tx_include_file
+            }
+            .map(PathBuf::from),
         };
         Some(filter_tx_addrs)
     } else {
@@ -204,6 +331,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let app_config = Arc::new(Config::new());
 
+    if let (subcommand, Some(subcommand_matches)) = matches.subcommand() {
+        let storage_config = LedgerStorageConfig {
+            read_only: false,
+            timeout: None,
+            address: app_config.hbase_address.clone(),
+            uploader_config,
+            cache_config: LedgerCacheConfig::default(),
+            thrift_config: process_thrift_arguments(&matches),
+            zstd_dictionaries: values_t!(matches, "zstd_dictionary", String).unwrap_or_default(),
+        };
+        return run_subcommand(subcommand, subcommand_matches, storage_config).await;
+    }
+
     info!("Started encoder");
 
     let storage_config = LedgerStorageConfig {
@@ -212,6 +352,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         address: app_config.hbase_address.clone(),
         uploader_config: uploader_config.clone(),
         cache_config: cache_config.clone(),
+        thrift_config: process_thrift_arguments(&matches),
+        zstd_dictionaries: values_t!(matches, "zstd_dictionary", String).unwrap_or_default(),
     };
     let storage = LedgerStorage::new_with_config(storage_config).await;
 
@@ -248,7 +390,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // output_block(versioned_block).await?;
 
             match storage.upload_confirmed_block(slot, versioned_block).await {
-                Ok(_) => (),
+                Ok(report) => debug!("Uploaded block from {}", report),
                 Err(e) => panic!("Upload error: {}", e.to_string()),
             }
         }
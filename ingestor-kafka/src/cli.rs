@@ -2,6 +2,16 @@ use {
     clap::{
         App,
         Arg,
+        SubCommand,
+    },
+    solana_storage_utils::{
+        compression::CompressionMode,
+        MAX_TX_BY_ADDR_SALT_BUCKETS,
+    },
+    solana_hbase_writer::{
+        address_filter::load_address_file,
+        tx_filter_rules::TxFilterRules,
+        tx_routes::load_tx_routes,
     },
     solana_clap_utils::{
         input_validators::{
@@ -18,6 +28,15 @@ const INCLUDE_TX_FULL_ADDR: &str = "filter-tx-full-include-addr";
 const EXCLUDE_TX_BY_ADDR_ADDR: &str = "filter-tx-by-addr-exclude-addr";
 const INCLUDE_TX_BY_ADDR_ADDR: &str = "filter-tx-by-addr-include-addr";
 
+const EXCLUDE_TX_FULL_ADDR_FILE: &str = "filter-tx-full-exclude-addr-file";
+const INCLUDE_TX_FULL_ADDR_FILE: &str = "filter-tx-full-include-addr-file";
+
+const EXCLUDE_TX_BY_ADDR_ADDR_FILE: &str = "filter-tx-by-addr-exclude-addr-file";
+const INCLUDE_TX_BY_ADDR_ADDR_FILE: &str = "filter-tx-by-addr-include-addr-file";
+
+const EXCLUDE_TX_BY_PROGRAM_ADDR: &str = "filter-tx-by-program-exclude-addr";
+const INCLUDE_TX_BY_PROGRAM_ADDR: &str = "filter-tx-by-program-include-addr";
+
 pub fn block_uploader_app<'a>(version: &'a str, _default_args: &'a DefaultBlockUploaderArgs) -> App<'a, 'a> {
     App::new("solana-block-uploader-service")
         .about("Solana Block Uploader Service")
@@ -56,6 +75,15 @@ pub fn block_uploader_app<'a>(version: &'a str, _default_args: &'a DefaultBlockU
                 .takes_value(false)
                 .help("Add md5 salt to hbase row keys."),
         )
+        .arg(
+            Arg::with_name("tx_by_addr_salt_buckets")
+                .long("tx-by-addr-salt-buckets")
+                .value_name("BUCKETS")
+                .takes_value(true)
+                .validator(|v| is_within_range::<usize, _>(v, 1..=usize::from(MAX_TX_BY_ADDR_SALT_BUCKETS)))
+                .help("Prefix tx-by-addr row keys with one of this many salt buckets, so the rows \
+                       of busy addresses are spread over regions. Readers must use the same value."),
+        )
         .arg(
             Arg::with_name("filter_tx_by_addr_programs")
                 .long("filter-tx-by-addr-programs")
@@ -98,6 +126,69 @@ pub fn block_uploader_app<'a>(version: &'a str, _default_args: &'a DefaultBlockU
                 .takes_value(false)
                 .help("Disables tx-full table compression."),
         )
+        .arg(
+            Arg::with_name("blocks_compression")
+                .long("blocks-compression")
+                .value_name("MODE")
+                .takes_value(true)
+                .default_value("best")
+                .validator(is_parsable::<CompressionMode>)
+                .help("Compression of blocks table cells: none, best, or a fixed method \
+                       with an optional level, e.g. zstd, zstd:3, gzip:6, bzip2:9, lz4 or snappy, \
+                       or zstd-dict:<id>[:<level>] with a dictionary loaded by --zstd-dictionary."),
+        )
+        .arg(
+            Arg::with_name("tx_compression")
+                .long("tx-compression")
+                .value_name("MODE")
+                .takes_value(true)
+                .default_value("best")
+                .validator(is_parsable::<CompressionMode>)
+                .help("Compression of tx table cells: none, best, or a fixed method \
+                       with an optional level, e.g. zstd, zstd:3, gzip:6, bzip2:9, lz4 or snappy, \
+                       or zstd-dict:<id>[:<level>] with a dictionary loaded by --zstd-dictionary."),
+        )
+        .arg(
+            Arg::with_name("tx_by_addr_compression")
+                .long("tx-by-addr-compression")
+                .value_name("MODE")
+                .takes_value(true)
+                .default_value("best")
+                .validator(is_parsable::<CompressionMode>)
+                .help("Compression of tx-by-addr table cells: none, best, or a fixed method \
+                       with an optional level, e.g. zstd, zstd:3, gzip:6, bzip2:9, lz4 or snappy, \
+                       or zstd-dict:<id>[:<level>] with a dictionary loaded by --zstd-dictionary."),
+        )
+        .arg(
+            Arg::with_name("tx_full_compression")
+                .long("tx-full-compression")
+                .value_name("MODE")
+                .takes_value(true)
+                .default_value("best")
+                .validator(is_parsable::<CompressionMode>)
+                .help("Compression of tx-full table cells: none, best, or a fixed method \
+                       with an optional level, e.g. zstd, zstd:3, gzip:6, bzip2:9, lz4 or snappy, \
+                       or zstd-dict:<id>[:<level>] with a dictionary loaded by --zstd-dictionary."),
+        )
+        .arg(
+            Arg::with_name("upload_batch_size")
+                .long("upload-batch-size")
+                .value_name("BLOCKS")
+                .takes_value(true)
+                .default_value("1")
+                .validator(is_parsable::<usize>)
+                .help("Number of consumed blocks uploaded together. A partial batch is uploaded \
+                       once no message arrived for a second."),
+        )
+        .arg(
+            Arg::with_name("compression_min_size")
+                .long("compression-min-size")
+                .value_name("BYTES")
+                .takes_value(true)
+                .default_value("0")
+                .validator(is_parsable::<usize>)
+                .help("Cells smaller than this are stored uncompressed."),
+        )
         .arg(
             Arg::with_name("filter_tx_full_include_addr")
                 .long(INCLUDE_TX_FULL_ADDR)
@@ -136,6 +227,56 @@ pub fn block_uploader_app<'a>(version: &'a str, _default_args: &'a DefaultBlockU
                 .value_name("KEY")
                 .help("Store all transactions in tx-by-addr except the ones with this account key. Overrides filter_tx_by_addr_include_addr."),
         )
+        .arg(
+            Arg::with_name("filter_tx_full_include_addr_file")
+                .long(INCLUDE_TX_FULL_ADDR_FILE)
+                .takes_value(true)
+                .value_name("FILE")
+                .validator(is_address_file)
+                .conflicts_with_all(&["filter_tx_full_exclude_addr", "filter_tx_full_exclude_addr_file"])
+                .help("Like filter_tx_full_include_addr, with the account keys listed in this file, as a JSON array or one per line."),
+        )
+        .arg(
+            Arg::with_name("filter_tx_full_exclude_addr_file")
+                .long(EXCLUDE_TX_FULL_ADDR_FILE)
+                .takes_value(true)
+                .value_name("FILE")
+                .validator(is_address_file)
+                .conflicts_with_all(&["filter_tx_full_include_addr", "filter_tx_full_include_addr_file"])
+                .help("Like filter_tx_full_exclude_addr, with the account keys listed in this file, as a JSON array or one per line."),
+        )
+        .arg(
+            Arg::with_name("filter_tx_by_addr_include_addr_file")
+                .long(INCLUDE_TX_BY_ADDR_ADDR_FILE)
+                .takes_value(true)
+                .value_name("FILE")
+                .validator(is_address_file)
+                .conflicts_with_all(&["filter_tx_by_addr_exclude_addr", "filter_tx_by_addr_exclude_addr_file"])
+                .help("Like filter_tx_by_addr_include_addr, with the account keys listed in this file, as a JSON array or one per line."),
+        )
+        .arg(
+            Arg::with_name("filter_tx_by_addr_exclude_addr_file")
+                .long(EXCLUDE_TX_BY_ADDR_ADDR_FILE)
+                .takes_value(true)
+                .value_name("FILE")
+                .validator(is_address_file)
+                .conflicts_with_all(&["filter_tx_by_addr_include_addr", "filter_tx_by_addr_include_addr_file"])
+                .help("Like filter_tx_by_addr_exclude_addr, with the account keys listed in this file, as a JSON array or one per line."),
+        )
+        .arg(
+            Arg::with_name("address_filter_reload_interval")
+                .long("address-filter-reload-interval")
+                .takes_value(true)
+                .value_name("SECONDS")
+                .validator(is_parsable::<u64>)
+                .help("Reload the tx-full and tx-by-addr address filter files this often."),
+        )
+        .arg(
+            Arg::with_name("reload_address_filters_on_sighup")
+                .long("reload-address-filters-on-sighup")
+                .takes_value(false)
+                .help("Reload the tx-full and tx-by-addr address filter files on SIGHUP."),
+        )
         .arg(
             Arg::with_name("enable_full_tx_cache")
                 .long("enable-full-tx-cache")
@@ -174,6 +315,382 @@ pub fn block_uploader_app<'a>(version: &'a str, _default_args: &'a DefaultBlockU
                 .takes_value(false)
                 .help("If HBase should skip WAL when writing new data."),
         )
+        .arg(
+            Arg::with_name("enable_block_time_index")
+                .long("enable-block-time-index")
+                .takes_value(false)
+                .help("Index blocks by block time, to look up slots by time."),
+        )
+        .arg(
+            Arg::with_name("enable_block_index")
+                .long("enable-block-index")
+                .takes_value(false)
+                .help("Index blocks by blockhash and block height."),
+        )
+        .arg(
+            Arg::with_name("enable_rewards_index")
+                .long("enable-rewards-index")
+                .takes_value(false)
+                .help("Index block rewards by recipient and epoch."),
+        )
+        .arg(
+            Arg::with_name("slots_per_epoch")
+                .long("slots-per-epoch")
+                .value_name("SLOTS")
+                .takes_value(true)
+                .default_value("432000")
+                .validator(|v| is_within_range::<usize, _>(v, 1..))
+                .help("Slots per epoch of the cluster, to key the rewards index by epoch."),
+        )
+        .arg(
+            Arg::with_name("enable_leader_index")
+                .long("enable-leader-index")
+                .takes_value(false)
+                .help("Index blocks by their leader, the recipient of their fee reward."),
+        )
+        .arg(
+            Arg::with_name("enable_block_stats")
+                .long("enable-block-stats")
+                .takes_value(false)
+                .help("Summarize each block into a stats row: transaction counts, fees, compute units, signers and top programs."),
+        )
+        .arg(
+            Arg::with_name("enable_memo_index")
+                .long("enable-memo-index")
+                .takes_value(false)
+                .help("Index transactions by their memos, for exact and prefix lookups."),
+        )
+        .arg(
+            Arg::with_name("enable_balance_history")
+                .long("enable-balance-history")
+                .takes_value(false)
+                .help("Record the lamport balance changes of each address."),
+        )
+        .arg(
+            Arg::with_name("enable_token_balance_history")
+                .long("enable-token-balance-history")
+                .takes_value(false)
+                .help("Record the token balance changes of each owner, by mint."),
+        )
+        .arg(
+            Arg::with_name("tx_routes")
+                .long("tx-routes")
+                .takes_value(true)
+                .value_name("FILE")
+                .validator(|path| {
+                    load_tx_routes(&path)
+                        .map(|_| ())
+                        .map_err(|err| format!("{path}: {err}"))
+                })
+                .help("JSON file of routes writing the tx-full and tx-by-addr rows of matching \
+                       transactions to other tables."),
+        )
+        .arg(
+            Arg::with_name("suppress_hot_accounts")
+                .long("suppress-hot-accounts")
+                .takes_value(false)
+                .help("Stop indexing addresses in too many transactions in tx-by-addr, and record \
+                       when in suppressed-addresses."),
+        )
+        .arg(
+            Arg::with_name("hot_account_window_slots")
+                .long("hot-account-window-slots")
+                .takes_value(true)
+                .value_name("SLOTS")
+                .validator(is_parsable::<u64>)
+                .requires("suppress_hot_accounts")
+                .help("Slots the transactions of each address are counted over. [default: 150]"),
+        )
+        .arg(
+            Arg::with_name("hot_account_max_transactions")
+                .long("hot-account-max-transactions")
+                .takes_value(true)
+                .value_name("COUNT")
+                .validator(is_parsable::<u64>)
+                .requires("suppress_hot_accounts")
+                .help("Addresses in more transactions over the window stop being indexed. [default: 50000]"),
+        )
+        .arg(
+            Arg::with_name("hot_account_resume_transactions")
+                .long("hot-account-resume-transactions")
+                .takes_value(true)
+                .value_name("COUNT")
+                .validator(is_parsable::<u64>)
+                .requires("suppress_hot_accounts")
+                .help("Suppressed addresses in fewer transactions over the window are indexed again. \
+                       [default: half of the max transactions]"),
+        )
+        .arg(
+            Arg::with_name("index_programs")
+                .long("index-programs")
+                .takes_value(false)
+                .help("Index transactions under the programs they invoked, including through CPI."),
+        )
+        .arg(
+            Arg::with_name("filter_tx_by_program_include_addr")
+                .long(INCLUDE_TX_BY_PROGRAM_ADDR)
+                .takes_value(true)
+                .validator(is_pubkey)
+                .multiple(true)
+                .value_name("KEY")
+                .help("Store only this program in tx-by-program."),
+        )
+        .arg(
+            Arg::with_name("filter_tx_by_program_exclude_addr")
+                .long(EXCLUDE_TX_BY_PROGRAM_ADDR)
+                .takes_value(true)
+                .validator(is_pubkey)
+                .conflicts_with("filter_tx_by_program_include_addr")
+                .multiple(true)
+                .value_name("KEY")
+                .help("Store all programs in tx-by-program except this one. Overrides filter_tx_by_program_include_addr."),
+        )
+        .arg(
+            Arg::with_name("tx_filter_rules")
+                .long("tx-filter-rules")
+                .takes_value(true)
+                .value_name("FILE")
+                .validator(|path| {
+                    TxFilterRules::from_file(&path)
+                        .map(|_| ())
+                        .map_err(|err| format!("{path}: {err}"))
+                })
+                .help("JSON file of rules further limiting which transactions are stored in tx, \
+                       tx-by-addr, tx_full and the cache."),
+        )
+        .arg(
+            Arg::with_name("index_token_owners")
+                .long("index-token-owners")
+                .takes_value(false)
+                .help("Index transactions under the owners of the token accounts in their \
+                       token balances, so wallet history includes token transfers."),
+        )
+        .arg(
+            Arg::with_name("index_token_mints")
+                .long("index-token-mints")
+                .takes_value(false)
+                .help("Index transactions under the mints in their token balances."),
+        )
+        .arg(
+            Arg::with_name("token_index_in_tx_by_addr")
+                .long("token-index-in-tx-by-addr")
+                .takes_value(false)
+                .help("Write the token owner and mint index to the tx-by-addr table \
+                       instead of the tx-by-owner and tx-by-mint tables."),
+        )
+        .arg(
+            Arg::with_name("use_cell_envelope")
+                .long("use-cell-envelope")
+                .takes_value(false)
+                .help("Write cells in the checksummed envelope format. \
+                       Readers must be upgraded before enabling this."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_transport")
+                .long("hbase-thrift-transport")
+                .value_name("TRANSPORT")
+                .takes_value(true)
+                .possible_values(&["buffered", "framed"])
+                .default_value("buffered")
+                .help("Thrift transport used by the HBase Thrift server. Use framed when the server runs with -framed or -nonblocking."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_protocol")
+                .long("hbase-thrift-protocol")
+                .value_name("PROTOCOL")
+                .takes_value(true)
+                .possible_values(&["binary", "compact"])
+                .default_value("binary")
+                .help("Thrift protocol used by the HBase Thrift server. Use compact when the server runs with -compact."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_tls")
+                .long("hbase-thrift-tls")
+                .takes_value(false)
+                .help("Connect to the HBase Thrift server over TLS."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_tls_ca")
+                .long("hbase-thrift-tls-ca")
+                .value_name("PATH")
+                .takes_value(true)
+                .requires("hbase_thrift_tls")
+                .help("PEM file with CA certificates used to verify the HBase Thrift server."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_tls_cert")
+                .long("hbase-thrift-tls-cert")
+                .value_name("PATH")
+                .takes_value(true)
+                .requires_all(&["hbase_thrift_tls", "hbase_thrift_tls_key"])
+                .help("PEM client certificate chain for mutual TLS."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_tls_key")
+                .long("hbase-thrift-tls-key")
+                .value_name("PATH")
+                .takes_value(true)
+                .requires_all(&["hbase_thrift_tls", "hbase_thrift_tls_cert"])
+                .help("PEM private key for the client certificate."),
+        )
+        .arg(
+            Arg::with_name("hbase_thrift_tls_server_name")
+                .long("hbase-thrift-tls-server-name")
+                .value_name("NAME")
+                .takes_value(true)
+                .requires("hbase_thrift_tls")
+                .help("Server name used for TLS verification. Defaults to the host of the HBase address."),
+        )
+        .arg(
+            Arg::with_name("zstd_dictionary")
+                .long("zstd-dictionary")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .help("Trained zstd dictionary to load. Needed for the zstd-dict compression modes."),
+        )
+        .subcommand(
+            SubCommand::with_name("ensure-schema")
+                .about("Create the missing HBase tables and validate the existing ones, then exit.")
+                .arg(
+                    Arg::with_name("max_versions")
+                        .long("max-versions")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .default_value("1")
+                        .validator(is_parsable::<i32>)
+                        .help("Number of cell versions HBase keeps."),
+                )
+                .arg(
+                    Arg::with_name("ttl_days")
+                        .long("ttl-days")
+                        .value_name("DAYS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .help("Time to live of the cells in days. Cells are kept forever if not set."),
+                )
+                .arg(
+                    Arg::with_name("bloom_filter")
+                        .long("bloom-filter")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .possible_values(&["none", "row", "rowcol"])
+                        .default_value("row")
+                        .help("Bloom filter type of the column family."),
+                )
+                .arg(
+                    Arg::with_name("hbase_compression")
+                        .long("hbase-compression")
+                        .value_name("CODEC")
+                        .takes_value(true)
+                        .default_value("NONE")
+                        .help("HBase block compression codec, e.g. NONE, GZ, SNAPPY, LZ4 or ZSTD. \
+                               Cells are already compressed by the uploader."),
+                )
+                .arg(
+                    Arg::with_name("regions")
+                        .long("regions")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .default_value("1")
+                        .validator(is_parsable::<usize>)
+                        .help("Number of regions to pre-split the tables into."),
+                )
+                .arg(
+                    Arg::with_name("print_hbase_shell")
+                        .long("print-hbase-shell")
+                        .takes_value(false)
+                        .help("Only print the hbase shell statements creating the pre-split tables. \
+                               The Thrift API can not pre-split tables it creates."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("train-zstd-dictionary")
+                .about("Train a zstd dictionary on cells of an existing table and report the \
+                        expected savings, then exit.")
+                .arg(
+                    Arg::with_name("table")
+                        .long("table")
+                        .value_name("TABLE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Table to sample cells from, e.g. tx or tx-by-addr."),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("File to write the dictionary to."),
+                )
+                .arg(
+                    Arg::with_name("sample_cells")
+                        .long("sample-cells")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .default_value("20000")
+                        .validator(is_parsable::<usize>)
+                        .help("Number of cells to read. Half trains the dictionary, half evaluates it."),
+                )
+                .arg(
+                    Arg::with_name("dictionary_size")
+                        .long("dictionary-size")
+                        .value_name("BYTES")
+                        .takes_value(true)
+                        .default_value("16384")
+                        .validator(is_parsable::<usize>)
+                        .help("Maximum size of the dictionary."),
+                )
+                .arg(
+                    Arg::with_name("level")
+                        .long("level")
+                        .value_name("LEVEL")
+                        .takes_value(true)
+                        .default_value("3")
+                        .validator(is_parsable::<i32>)
+                        .help("Zstd level used to evaluate the dictionary."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("delete-blocks")
+                .about("Delete uploaded blocks together with their tx, tx-by-addr and tx_full rows, \
+                        e.g. to purge forked blocks, then exit.")
+                .arg(
+                    Arg::with_name("start_slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_parsable::<u64>)
+                        .help("First slot to delete."),
+                )
+                .arg(
+                    Arg::with_name("end_slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .help("Last slot to delete, inclusive [default: --start-slot]."),
+                )
+                .arg(
+                    Arg::with_name("dry_run")
+                        .long("dry-run")
+                        .takes_value(false)
+                        .help("Only list the rows that would be deleted."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("backfill-slot-index")
+                .about("Write the slot index rows of the blocks uploaded before it was, needed to \
+                        list blocks with --use-md5-row-key-salt, then exit."),
+        )
+}
+
+fn is_address_file(path: String) -> Result<(), String> {
+    load_address_file(&path)
+        .map(|_| ())
+        .map_err(|err| format!("{path}: {err}"))
 }
 
 pub struct DefaultBlockUploaderArgs {
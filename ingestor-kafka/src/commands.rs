@@ -0,0 +1,183 @@
+//! Maintenance subcommands shared by the uploader binaries, see `cli::block_uploader_app`.
+
+use {
+    clap::{value_t, value_t_or_exit, ArgMatches},
+    log::info,
+    solana_hbase_writer::{
+        ledger_storage::LedgerStorage,
+        schema::{hbase_shell_create_statement, uploader_tables},
+        schema_config::{BloomFilterType, SchemaConfig, TableSchema},
+        storage_config::LedgerStorageConfig,
+        thrift_config::{ThriftConfig, ThriftProtocol, ThriftTlsConfig, ThriftTransport},
+    },
+    solana_storage_utils::zstd_dictionary::train_dictionary_from_cells,
+    solana_storage_writer::LedgerStorageAdapter,
+};
+
+/// Run `subcommand` against the storage of `storage_config`.
+pub async fn run_subcommand(
+    subcommand: &str,
+    matches: &ArgMatches<'_>,
+    storage_config: LedgerStorageConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match subcommand {
+        "ensure-schema" => {
+            ensure_schema(
+                storage_config,
+                process_schema_arguments(matches),
+                matches.is_present("print_hbase_shell"),
+            )
+            .await
+        }
+        "train-zstd-dictionary" => train_zstd_dictionary(storage_config, matches).await,
+        "delete-blocks" => delete_blocks(storage_config, matches).await,
+        "backfill-slot-index" => backfill_slot_index(storage_config).await,
+        _ => unreachable!(),
+    }
+}
+
+pub fn process_thrift_arguments(matches: &ArgMatches<'_>) -> ThriftConfig {
+    let transport = value_t_or_exit!(matches, "hbase_thrift_transport", ThriftTransport);
+    let protocol = value_t_or_exit!(matches, "hbase_thrift_protocol", ThriftProtocol);
+
+    let tls = if matches.is_present("hbase_thrift_tls") {
+        Some(ThriftTlsConfig {
+            ca_cert_path: matches.value_of("hbase_thrift_tls_ca").map(str::to_string),
+            client_cert_path: matches.value_of("hbase_thrift_tls_cert").map(str::to_string),
+            client_key_path: matches.value_of("hbase_thrift_tls_key").map(str::to_string),
+            server_name: matches.value_of("hbase_thrift_tls_server_name").map(str::to_string),
+        })
+    } else {
+        None
+    };
+
+    ThriftConfig {
+        transport,
+        protocol,
+        tls,
+    }
+}
+
+pub fn process_schema_arguments(matches: &ArgMatches<'_>) -> SchemaConfig {
+    let time_to_live = if matches.is_present("ttl_days") {
+        Some(std::time::Duration::from_secs(
+            value_t_or_exit!(matches, "ttl_days", u64) * 24 * 60 * 60,
+        ))
+    } else {
+        None
+    };
+
+    SchemaConfig {
+        default_schema: TableSchema {
+            max_versions: value_t_or_exit!(matches, "max_versions", i32),
+            time_to_live,
+            bloom_filter: value_t_or_exit!(matches, "bloom_filter", BloomFilterType),
+            compression: value_t_or_exit!(matches, "hbase_compression", String).to_ascii_uppercase(),
+            regions: value_t_or_exit!(matches, "regions", usize),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub async fn ensure_schema(
+    storage_config: LedgerStorageConfig,
+    schema_config: SchemaConfig,
+    print_hbase_shell: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if print_hbase_shell {
+        for table in uploader_tables(&storage_config.uploader_config) {
+            let schema = schema_config.schema_for(&table.name);
+            println!("{}", hbase_shell_create_statement(&table, schema));
+        }
+        return Ok(());
+    }
+
+    let storage = LedgerStorage::new_with_config(storage_config).await;
+    let report = storage.ensure_schema(&schema_config).await?;
+
+    info!(
+        "Created tables: {:?}, existing tables: {:?}",
+        report.created, report.existing
+    );
+    if !report.unsplit.is_empty() {
+        info!(
+            "Tables not pre-split: {:?}, see --print-hbase-shell",
+            report.unsplit
+        );
+    }
+
+    if !report.is_valid() {
+        let mismatches = report
+            .mismatches
+            .iter()
+            .map(|mismatch| mismatch.to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(format!("HBase schema mismatch: {mismatches}").into());
+    }
+
+    Ok(())
+}
+
+pub async fn train_zstd_dictionary(
+    storage_config: LedgerStorageConfig,
+    matches: &ArgMatches<'_>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let table = value_t_or_exit!(matches, "table", String);
+    let sample_cells = value_t_or_exit!(matches, "sample_cells", usize);
+    let dictionary_size = value_t_or_exit!(matches, "dictionary_size", usize);
+    let level = value_t_or_exit!(matches, "level", i32);
+    let output = value_t_or_exit!(matches, "output", String);
+
+    let storage = LedgerStorage::new_with_config(storage_config).await;
+    let cells = storage.sample_cells(&table, sample_cells).await?;
+    info!("Sampled {} cells from {}", cells.len(), table);
+
+    let (dictionary, report) = train_dictionary_from_cells(&cells, dictionary_size, level)?;
+    std::fs::write(&output, dictionary)?;
+
+    println!("{}: {}", table, report);
+    println!(
+        "Dictionary written to {}, use it with --zstd-dictionary {} and compression mode zstd-dict:{}:{}",
+        output, output, report.dictionary_id, level
+    );
+
+    Ok(())
+}
+
+pub async fn delete_blocks(
+    storage_config: LedgerStorageConfig,
+    matches: &ArgMatches<'_>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let start_slot = value_t_or_exit!(matches, "start_slot", u64);
+    let end_slot = value_t!(matches, "end_slot", u64).unwrap_or(start_slot);
+    let dry_run = matches.is_present("dry_run");
+    if end_slot < start_slot {
+        return Err(format!("--end-slot {end_slot} is before --start-slot {start_slot}").into());
+    }
+
+    let storage = LedgerStorage::new_with_config(storage_config).await;
+    let report = storage.delete_confirmed_blocks(start_slot, end_slot, dry_run).await?;
+
+    if dry_run {
+        for (table, row_keys) in &report.rows {
+            for row_key in row_keys {
+                println!("{table}/{row_key}");
+            }
+        }
+    }
+    println!("Slots {}-{}: {}", start_slot, end_slot, report);
+
+    Ok(())
+}
+
+pub async fn backfill_slot_index(
+    storage_config: LedgerStorageConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let storage = LedgerStorage::new_with_config(storage_config).await;
+    let indexed = storage.backfill_slot_index().await?;
+    println!("Indexed {} blocks", indexed);
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use {
     },
     solana_transaction_status::{
         BlockEncodingOptions,
+        VersionedConfirmedBlock,
     },
     solana_transaction_status_client_types::{
         UiTransactionEncoding,
@@ -30,16 +31,41 @@ use {
             Message,
             BorrowedMessage,
         },
+        topic_partition_list::{Offset, TopicPartitionList},
     },
     std::time::{Duration, Instant},
     std::str,
 };
 
+/// How long a partially filled batch waits for more messages before it is uploaded.
+const UPLOAD_BATCH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A converted block waiting for the upload of its batch.
+struct PendingBlock {
+    slot: u64,
+    block: VersionedConfirmedBlock,
+    payload: String,
+}
+
+/// Decode the JSON block of a message.
+fn decode_block(buffer: &str) -> Result<VersionedConfirmedBlock, String> {
+    let block: EncodedConfirmedBlock = serde_json::from_str(buffer).map_err(|e| e.to_string())?;
+
+    let options = BlockEncodingOptions {
+        transaction_details: TransactionDetails::Full,
+        show_rewards: true,
+        max_supported_transaction_version: Some(0),
+    };
+
+    convert_block(block, UiTransactionEncoding::Json, options).map_err(|e| e.to_string())
+}
+
 pub struct KafkaConsumer {
     kafka_consumer: StreamConsumer,
     kafka_producer: KafkaProducer,
     storage: Box<dyn LedgerStorageAdapter>,
     // storage: LedgerStorage,
+    upload_batch_size: usize,
 }
 
 impl KafkaConsumer {
@@ -50,14 +76,16 @@ impl KafkaConsumer {
         topics: &[&str],
         // storage: LedgerStorage,
         storage: Box<dyn LedgerStorageAdapter>,
-        kproducer: KafkaProducer
+        kproducer: KafkaProducer,
+        upload_batch_size: usize,
     ) -> KafkaConsumer {
         let consumer: StreamConsumer = ClientConfig::new()
             .set("group.id", group_id)
             .set("bootstrap.servers", kafka_brokers)
             .set("enable.partition.eof", "false")
             .set("session.timeout.ms", "10000")
-            .set("enable.auto.commit", "true")
+            // Offsets are committed by `commit_offsets` once their blocks are uploaded
+            .set("enable.auto.commit", "false")
             .set("auto.offset.reset", "earliest")
             .set("max.partition.fetch.bytes", "10485760")
             .set("max.in.flight.requests.per.connection", "1")
@@ -73,12 +101,16 @@ impl KafkaConsumer {
             kafka_consumer: consumer,
             kafka_producer: kproducer,
             storage,
+            upload_batch_size: upload_batch_size.max(1),
         }
     }
 
     /// Consume the incoming topic and publishes the raw-payload to an internal
     /// mpsc channel to be consumed by another async-task which then writes the
     /// data to postgres.
+    ///
+    /// Blocks are uploaded in batches of `upload_batch_size`, and the offsets of the messages
+    /// are only committed once their batch was uploaded.
     pub async fn consume(&self) {
         info!("initiating data consumption from kafka-topic");
 
@@ -86,8 +118,27 @@ impl KafkaConsumer {
         let report_interval = 10;
         let mut batch_time = Instant::now();
 
+        let mut pending_blocks: Vec<PendingBlock> = Vec::with_capacity(self.upload_batch_size);
+        let mut pending_offsets = TopicPartitionList::new();
+
         let mut message_stream = self.kafka_consumer.stream();
-        while let Some(message) = message_stream.next().await {
+        loop {
+            let message = if pending_blocks.is_empty() {
+                message_stream.next().await
+            } else {
+                match tokio::time::timeout(UPLOAD_BATCH_TIMEOUT, message_stream.next()).await {
+                    Ok(message) => message,
+                    Err(_) => {
+                        self.upload_blocks(std::mem::take(&mut pending_blocks)).await;
+                        self.commit_offsets(&mut pending_offsets);
+                        continue;
+                    }
+                }
+            };
+            let Some(message) = message else {
+                break;
+            };
+
             match message {
                 Err(e) => warn!("Kafka error: {}", e),
                 Ok(m) => {
@@ -121,33 +172,25 @@ impl KafkaConsumer {
                             Some(s) => s,
                             None => {
                                 warn!("Invalid or missing 'blockID' field in message, skipping processing");
-                                self.commit_message(&m);
+                                self.store_offset(&mut pending_offsets, &m);
                                 continue;
                             }
                         };
 
                         info!("Parsing block with id {}", slot);
 
-                        let block: EncodedConfirmedBlock = serde_json::from_str(&buffer).unwrap();
-
-                        let options = BlockEncodingOptions {
-                            transaction_details: TransactionDetails::Full,
-                            show_rewards: true,
-                            max_supported_transaction_version: Some(0),
-                        };
-
-                        match convert_block(block, UiTransactionEncoding::Json, options) {
+                        match decode_block(buffer) {
                             Ok(versioned_block) => {
                                 // output_block(versioned_block).await?;
 
-                                // match self.storage.upload_confirmed_block(slot, convert_versioned_confirmed_block(&versioned_block)).await {
-                                match self.storage.upload_confirmed_block(slot, versioned_block).await {
-                                    Ok(_) => (),
-                                    Err(e) => self.handle_error(&m, &buffer, e.to_string()).await,
-                                }
+                                pending_blocks.push(PendingBlock {
+                                    slot,
+                                    block: versioned_block,
+                                    payload: buffer.to_string(),
+                                });
                             }
                             Err(e) => {
-                                self.handle_error(&m, &buffer, e.to_string()).await;
+                                self.handle_error(&buffer, e).await;
                             }
                         }
 
@@ -157,7 +200,11 @@ impl KafkaConsumer {
                         warn!("Failed to read raw data from kafka topic")
                     }
 
-                    self.commit_message(&m);
+                    self.store_offset(&mut pending_offsets, &m);
+                    if pending_blocks.len() >= self.upload_batch_size {
+                        self.upload_blocks(std::mem::take(&mut pending_blocks)).await;
+                        self.commit_offsets(&mut pending_offsets);
+                    }
                 }
             };
 
@@ -170,10 +217,60 @@ impl KafkaConsumer {
             }
         }
 
+        if !pending_blocks.is_empty() {
+            self.upload_blocks(pending_blocks).await;
+            self.commit_offsets(&mut pending_offsets);
+        }
+
         debug!("Returned from consumer");
     }
 
-    async fn handle_error(&self, _m: &BorrowedMessage<'_>, block: &str, error_string: String) {
+    /// Upload a batch of blocks. If the batch fails, its blocks are uploaded one by one so that
+    /// only the failing ones end up in the error topic.
+    async fn upload_blocks(&self, pending_blocks: Vec<PendingBlock>) {
+        let (blocks, payloads): (Vec<_>, Vec<_>) = pending_blocks
+            .into_iter()
+            .map(|pending| ((pending.slot, pending.block), (pending.slot, pending.payload)))
+            .unzip();
+
+        if let [(_, payload)] = payloads.as_slice() {
+            let (slot, block) = blocks.into_iter().next().unwrap();
+            // match self.storage.upload_confirmed_block(slot, convert_versioned_confirmed_block(&versioned_block)).await {
+            match self.storage.upload_confirmed_block(slot, block).await {
+                Ok(report) => debug!("Uploaded block from {}", report),
+                Err(e) => self.handle_error(payload, e.to_string()).await,
+            }
+            return;
+        }
+
+        match self.storage.upload_confirmed_blocks(blocks).await {
+            Ok(reports) => {
+                for report in reports {
+                    debug!("Uploaded block from {}", report);
+                }
+                return;
+            }
+            Err(e) => warn!(
+                "Failed to upload a batch of {} blocks, uploading them one by one: {}",
+                payloads.len(),
+                e
+            ),
+        }
+
+        // The blocks went to the failed batch, decode them again from their messages
+        for (slot, payload) in payloads {
+            let result = match decode_block(&payload) {
+                Ok(block) => self.storage.upload_confirmed_block(slot, block).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(report) => debug!("Uploaded block from {}", report),
+                Err(e) => self.handle_error(&payload, e).await,
+            }
+        }
+    }
+
+    async fn handle_error(&self, block: &str, error_string: String) {
         warn!("Failed to encode block: {}",
             error_string
         );
@@ -181,10 +278,27 @@ impl KafkaConsumer {
         self.kafka_producer.produce_with_headers(payload, None).await;
     }
 
-    fn commit_message(&self, m: &BorrowedMessage) {
-        if let Err(e) = self.kafka_consumer.commit_message(m, CommitMode::Async) {
-            error!("Failed to commit offset to kafka: {:?}", e);
+    /// Remember the offset to commit once the blocks received so far are uploaded.
+    fn store_offset(&self, offsets: &mut TopicPartitionList, m: &BorrowedMessage) {
+        let offset = Offset::Offset(m.offset() + 1);
+        let result = if offsets.find_partition(m.topic(), m.partition()).is_some() {
+            offsets.set_partition_offset(m.topic(), m.partition(), offset)
+        } else {
+            offsets.add_partition_offset(m.topic(), m.partition(), offset)
+        };
+        if let Err(e) = result {
+            error!("Failed to store offset {}: {:?}", m.offset(), e);
+        }
+    }
+
+    fn commit_offsets(&self, offsets: &mut TopicPartitionList) {
+        if offsets.count() == 0 {
+            return;
+        }
+        if let Err(e) = self.kafka_consumer.commit(offsets, CommitMode::Async) {
+            error!("Failed to commit offsets to kafka: {:?}", e);
         }
+        *offsets = TopicPartitionList::new();
     }
 }
 
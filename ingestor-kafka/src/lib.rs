@@ -1,5 +1,5 @@
-
 pub mod consumer;
 pub mod producer;
 pub mod config;
-pub mod cli;
\ No newline at end of file
+pub mod cli;
+pub mod commands;
@@ -3,6 +3,7 @@ use {
     solana_clock::{
         Slot,
     },
+    solana_pubkey::Pubkey,
     std::{
         boxed::Box,
     },
@@ -33,6 +34,9 @@ pub enum Error {
 
     #[error("Cache Error: {0}")]
     CacheError(String),
+
+    #[error("Transaction history of {0} is incomplete from slot {1}, it was not indexed for being in too many transactions")]
+    AddressHistorySuppressed(Pubkey, Slot),
 }
 
 impl From<std::io::Error> for Error {
@@ -4,7 +4,9 @@ use {
     },
     async_trait::async_trait,
     solana_clock::{
+        Epoch,
         Slot,
+        UnixTimestamp,
     },
     solana_pubkey::{
         Pubkey,
@@ -16,6 +18,7 @@ use {
         ConfirmedBlock,
         ConfirmedTransactionStatusWithSignature,
         ConfirmedTransactionWithStatusMeta,
+        Reward,
     },
     solana_transaction_status_client_types::{
         TransactionStatus,
@@ -27,6 +30,14 @@ use {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Error of the index lookups a backend does not provide.
+fn unsupported<T>() -> Result<T> {
+    Err(Error::StorageBackendError(Box::new(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "Method not supported",
+    ))))
+}
+
 #[async_trait]
 pub trait LedgerStorageAdapter: Send + Sync {
     async fn get_first_available_block(&self) -> Result<Option<Slot>>;
@@ -57,5 +68,70 @@ pub trait LedgerStorageAdapter: Send + Sync {
 
     async fn get_latest_stored_slot(&self) -> Result<Slot>;
 
+    /// Time of the block at `slot`, from the block time index.
+    async fn get_block_time(&self, _slot: Slot) -> Result<Option<UnixTimestamp>> {
+        unsupported()
+    }
+
+    /// Latest slot whose block time is at or before `timestamp`.
+    async fn get_slot_at_time(&self, _timestamp: UnixTimestamp) -> Result<Option<Slot>> {
+        unsupported()
+    }
+
+    /// Up to `limit` blocks with a block time between `start_time` and `end_time` inclusive,
+    /// newest first.
+    async fn get_blocks_in_time_range(
+        &self,
+        _start_time: UnixTimestamp,
+        _end_time: UnixTimestamp,
+        _limit: usize,
+    ) -> Result<Vec<(Slot, UnixTimestamp)>> {
+        unsupported()
+    }
+
+    /// Block with the given blockhash, and its slot, from the blockhash index.
+    async fn get_block_by_hash(&self, _blockhash: &str) -> Result<Option<(Slot, ConfirmedBlock)>> {
+        unsupported()
+    }
+
+    /// Slot of the block at `block_height`, from the block height index.
+    async fn get_slot_by_block_height(&self, _block_height: u64) -> Result<Option<Slot>> {
+        unsupported()
+    }
+
+    /// Height of the block at `slot`, from the block height index.
+    async fn get_block_height(&self, _slot: Slot) -> Result<Option<u64>> {
+        unsupported()
+    }
+
+    /// Staking and voting rewards of `address` earned in the epochs between `start_epoch` and
+    /// `end_epoch` inclusive, oldest first, with the epoch and the slot of the block that paid
+    /// them, from the rewards index.
+    async fn get_inflation_rewards(
+        &self,
+        _address: &Pubkey,
+        _start_epoch: Epoch,
+        _end_epoch: Epoch,
+    ) -> Result<Vec<(Epoch, Slot, Reward)>> {
+        unsupported()
+    }
+
+    /// Leader of the block at `slot`, from the leader index.
+    async fn get_block_leader(&self, _slot: Slot) -> Result<Option<Pubkey>> {
+        unsupported()
+    }
+
+    /// Up to `limit` slots between `start_slot` and `end_slot` inclusive whose block was
+    /// produced by `leader`, oldest first, from the leader index.
+    async fn get_blocks_by_leader(
+        &self,
+        _leader: &Pubkey,
+        _start_slot: Slot,
+        _end_slot: Slot,
+        _limit: usize,
+    ) -> Result<Vec<Slot>> {
+        unsupported()
+    }
+
     fn clone_box(&self) -> Box<dyn LedgerStorageAdapter>;
 }
@@ -0,0 +1,102 @@
+//! Lamport balance changes per address.
+//!
+//! The `balance-history` table has a row per address and slot, keyed like `tx-by-addr`, holding
+//! the bincode `Vec<BalanceChange>` of the transactions of that slot that changed the balance of
+//! the address.
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_transaction_status::VersionedTransactionWithStatusMeta,
+};
+
+pub const BALANCE_HISTORY_TABLE_NAME: &str = "balance-history";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// Where the transaction is located in the block
+    pub index: u32,
+    /// Lamports gained, negative if lost
+    pub delta: i64,
+    pub post_balance: u64,
+}
+
+/// Balance change of an address, as returned by the readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceHistoryEntry {
+    pub slot: Slot,
+    pub index: u32,
+    pub delta: i64,
+    pub post_balance: u64,
+}
+
+/// Accounts whose balance the transaction changed, with the change.
+pub fn balance_changes(
+    transaction_with_meta: &VersionedTransactionWithStatusMeta,
+    index: u32,
+) -> Vec<(Pubkey, BalanceChange)> {
+    let meta = &transaction_with_meta.meta;
+    transaction_with_meta
+        .account_keys()
+        .iter()
+        .zip(meta.pre_balances.iter().zip(&meta.post_balances))
+        .filter(|(_, (pre_balance, post_balance))| pre_balance != post_balance)
+        .map(|(address, (&pre_balance, &post_balance))| {
+            (
+                *address,
+                BalanceChange {
+                    index,
+                    delta: (post_balance as i128 - pre_balance as i128) as i64,
+                    post_balance,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_hash::Hash,
+        solana_message::{Message, VersionedMessage},
+        solana_signature::Signature,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    #[test]
+    fn test_balance_changes() {
+        let payer = Pubkey::new_unique();
+        let receiver = Pubkey::new_unique();
+        let unchanged = Pubkey::new_unique();
+        let message = Message::new_with_compiled_instructions(
+            1,
+            0,
+            0,
+            vec![payer, receiver, unchanged],
+            Hash::default(),
+            vec![],
+        );
+        let transaction_with_meta = VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta: TransactionStatusMeta {
+                pre_balances: vec![100, 0, 7],
+                post_balances: vec![45, 50, 7],
+                ..TransactionStatusMeta::default()
+            },
+        };
+
+        assert_eq!(
+            balance_changes(&transaction_with_meta, 3),
+            vec![
+                (payer, BalanceChange { index: 3, delta: -55, post_balance: 45 }),
+                (receiver, BalanceChange { index: 3, delta: 50, post_balance: 50 }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Row keys of the blockhash and block height index.
+//!
+//! The `block-index` table holds three kinds of rows, each with a bincode `u64` cell:
+//!
+//! - `h/{blockhash}`, the slot of the block with that hash
+//! - `b/{block_height}`, the slot of the block at that height
+//! - `s/{slot}`, the height of the block at that slot
+//!
+//! Numbers are formatted like `slot_to_key`. Blocks without a height only get a hash row.
+
+use {
+    crate::key::slot_to_key,
+    solana_clock::Slot,
+};
+
+pub const BLOCK_INDEX_TABLE_NAME: &str = "block-index";
+
+pub fn blockhash_to_key(blockhash: &str) -> String {
+    format!("h/{blockhash}")
+}
+
+pub fn block_height_to_key(block_height: u64) -> String {
+    format!("b/{}", slot_to_key(block_height))
+}
+
+pub fn slot_to_block_height_key(slot: Slot) -> String {
+    format!("s/{}", slot_to_key(slot))
+}
+
+/// Rows of the block at `slot`, as row key and value.
+pub fn block_index_cells(slot: Slot, blockhash: &str, block_height: Option<u64>) -> Vec<(String, u64)> {
+    let mut cells = vec![(blockhash_to_key(blockhash), slot)];
+    if let Some(block_height) = block_height {
+        cells.push((block_height_to_key(block_height), slot));
+        cells.push((slot_to_block_height_key(slot), block_height));
+    }
+    cells
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_index_cells() {
+        assert_eq!(
+            block_index_cells(42, "hash", Some(40)),
+            vec![
+                ("h/hash".to_string(), 42),
+                ("b/0000000000000028".to_string(), 42),
+                ("s/000000000000002a".to_string(), 40),
+            ]
+        );
+        assert_eq!(block_index_cells(42, "hash", None), vec![("h/hash".to_string(), 42)]);
+    }
+}
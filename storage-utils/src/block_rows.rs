@@ -0,0 +1,134 @@
+use {
+    crate::{
+        balance_history::balance_changes,
+        block_index::block_index_cells,
+        block_time::{block_time_to_key, slot_to_block_time_key},
+        key::{slot_to_key, slot_to_tx_by_addr_key},
+        leader_index::leader_index_cells,
+        memo_index::memo_index_cells,
+        program_index::invoked_programs,
+        rewards::rewards_cells,
+        token_balance_history::token_balance_changes,
+        token_index::token_balance_accounts,
+    },
+    solana_clock::Slot,
+    solana_transaction_status::{ConfirmedBlock, TransactionWithStatusMeta},
+    std::collections::BTreeSet,
+};
+
+/// Keys of the rows the uploader derives from a block, besides the block row itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockRowKeys {
+    /// Keys of the `tx` and `tx_full` rows.
+    pub signatures: Vec<String>,
+    pub tx_by_addr: Vec<String>,
+    /// Keys of the token owner and mint index rows, see `token_index`.
+    pub tx_by_owner: Vec<String>,
+    pub tx_by_mint: Vec<String>,
+    /// Keys of the program invocation index rows, see `program_index`.
+    pub tx_by_program: Vec<String>,
+    /// Keys of the block time index rows, see `block_time`.
+    pub block_time: Vec<String>,
+    /// Keys of the blockhash and block height index rows, see `block_index`.
+    pub block_index: Vec<String>,
+    /// Keys of the balance history rows, see `balance_history`.
+    pub balance_history: Vec<String>,
+    /// Keys of the token balance history rows, see `token_balance_history`.
+    pub token_balance_history: Vec<String>,
+    /// Keys of the rewards index rows, see `rewards`.
+    pub rewards: Vec<String>,
+    /// Keys of the block leader index rows, see `leader_index`.
+    pub leader_index: Vec<String>,
+    /// Key of the block stats row, see `block_stats`.
+    pub block_stats: Vec<String>,
+    /// Keys of the memo index rows, see `memo_index`.
+    pub memo_index: Vec<String>,
+    /// Key of the slot index row, see `slot_index`.
+    pub slot_index: Vec<String>,
+}
+
+/// Row keys derived from the stored contents of the block at `slot`, with the epochs of the
+/// rewards rows derived from `slots_per_epoch`. Uploader filters are not applied, so some of the
+/// rows may not exist.
+pub fn block_row_keys(slot: Slot, slots_per_epoch: u64, block: &ConfirmedBlock) -> BlockRowKeys {
+    let mut signatures = vec![];
+    let mut addresses = BTreeSet::new();
+    let mut owners = BTreeSet::new();
+    let mut mints = BTreeSet::new();
+    let mut programs = BTreeSet::new();
+    let mut balance_addresses = BTreeSet::new();
+    let mut token_balance_owners = BTreeSet::new();
+    let mut memo_index = vec![];
+
+    for (index, transaction_with_meta) in block.transactions.iter().enumerate() {
+        signatures.push(transaction_with_meta.transaction_signature().to_string());
+        match transaction_with_meta {
+            TransactionWithStatusMeta::Complete(transaction_with_meta) => {
+                addresses.extend(transaction_with_meta.account_keys().iter().copied());
+                let token_accounts = token_balance_accounts(&transaction_with_meta.meta);
+                owners.extend(token_accounts.owners);
+                mints.extend(token_accounts.mints);
+                programs.extend(invoked_programs(transaction_with_meta));
+                balance_addresses.extend(
+                    balance_changes(transaction_with_meta, index as u32)
+                        .into_iter()
+                        .map(|(address, _)| address),
+                );
+                token_balance_owners.extend(
+                    token_balance_changes(&transaction_with_meta.meta, index as u32)
+                        .into_iter()
+                        .map(|(owner, _)| owner),
+                );
+                memo_index.extend(
+                    memo_index_cells(slot, index as u32, transaction_with_meta)
+                        .into_iter()
+                        .map(|(row_key, _)| row_key),
+                );
+            }
+            TransactionWithStatusMeta::MissingMetadata(transaction) => {
+                addresses.extend(transaction.message.account_keys.iter().copied());
+            }
+        }
+    }
+
+    let tx_by_addr_key = slot_to_tx_by_addr_key(slot);
+    let row_keys = |addresses: BTreeSet<_>| {
+        addresses
+            .into_iter()
+            .map(|address| format!("{address}/{tx_by_addr_key}"))
+            .collect()
+    };
+    BlockRowKeys {
+        signatures,
+        tx_by_addr: row_keys(addresses),
+        tx_by_owner: row_keys(owners),
+        tx_by_mint: row_keys(mints),
+        tx_by_program: row_keys(programs),
+        balance_history: row_keys(balance_addresses),
+        token_balance_history: row_keys(token_balance_owners),
+        block_time: block
+            .block_time
+            .map(|block_time| {
+                vec![
+                    slot_to_block_time_key(slot),
+                    block_time_to_key(block_time, slot),
+                ]
+            })
+            .unwrap_or_default(),
+        block_index: block_index_cells(slot, &block.blockhash, block.block_height)
+            .into_iter()
+            .map(|(row_key, _)| row_key)
+            .collect(),
+        rewards: rewards_cells(slot, slots_per_epoch, &block.rewards)
+            .into_iter()
+            .map(|(row_key, _)| row_key)
+            .collect(),
+        leader_index: leader_index_cells(slot, &block.rewards)
+            .into_iter()
+            .map(|(row_key, _)| row_key)
+            .collect(),
+        block_stats: vec![slot_to_key(slot)],
+        memo_index,
+        slot_index: vec![slot_to_key(slot)],
+    }
+}
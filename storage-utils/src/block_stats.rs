@@ -0,0 +1,175 @@
+//! Aggregate statistics of a block.
+//!
+//! The `block-stats` table has a row per block, keyed by `slot_to_key`, holding the bincode
+//! `BlockStats` computed while the block was uploaded.
+
+use {
+    crate::program_index::program_invocations,
+    serde::{Deserialize, Serialize},
+    solana_pubkey::Pubkey,
+    solana_transaction_status::VersionedTransactionWithStatusMeta,
+    std::collections::{HashMap, HashSet},
+};
+
+pub const BLOCK_STATS_TABLE_NAME: &str = "block-stats";
+
+/// Number of programs kept in `BlockStats::top_programs`.
+pub const TOP_PROGRAMS_LEN: usize = 10;
+
+/// Base fee charged per signature, the rest of a transaction fee is its priority fee.
+const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockStats {
+    pub vote_transactions: u64,
+    pub non_vote_transactions: u64,
+    /// Failed transactions, vote ones included
+    pub failed_transactions: u64,
+    /// Fees of all the transactions, in lamports
+    pub total_fees: u64,
+    /// Medians are of the non-vote transactions, the upper one for an even count
+    pub median_fee: u64,
+    pub total_priority_fees: u64,
+    pub median_priority_fee: u64,
+    pub compute_units_consumed: u64,
+    pub distinct_signers: u64,
+    /// Most invoked programs with their invocation count, cross-program invocations included,
+    /// most invoked first
+    pub top_programs: Vec<(Pubkey, u64)>,
+}
+
+/// Accumulates the stats of a block one transaction at a time.
+#[derive(Default)]
+pub struct BlockStatsBuilder {
+    stats: BlockStats,
+    non_vote_fees: Vec<u64>,
+    non_vote_priority_fees: Vec<u64>,
+    signers: HashSet<Pubkey>,
+    program_invocations: HashMap<Pubkey, u64>,
+}
+
+impl BlockStatsBuilder {
+    pub fn add_transaction(
+        &mut self,
+        transaction_with_meta: &VersionedTransactionWithStatusMeta,
+        is_voting: bool,
+    ) {
+        let meta = &transaction_with_meta.meta;
+        let message = &transaction_with_meta.transaction.message;
+        let num_signatures = transaction_with_meta.transaction.signatures.len() as u64;
+        let priority_fee = meta
+            .fee
+            .saturating_sub(num_signatures.saturating_mul(LAMPORTS_PER_SIGNATURE));
+
+        if is_voting {
+            self.stats.vote_transactions += 1;
+        } else {
+            self.stats.non_vote_transactions += 1;
+            self.non_vote_fees.push(meta.fee);
+            self.non_vote_priority_fees.push(priority_fee);
+        }
+        if meta.status.is_err() {
+            self.stats.failed_transactions += 1;
+        }
+        self.stats.total_fees = self.stats.total_fees.saturating_add(meta.fee);
+        self.stats.total_priority_fees = self.stats.total_priority_fees.saturating_add(priority_fee);
+        self.stats.compute_units_consumed = self
+            .stats
+            .compute_units_consumed
+            .saturating_add(meta.compute_units_consumed.unwrap_or_default());
+
+        let num_signers = message.header().num_required_signatures as usize;
+        self.signers
+            .extend(message.static_account_keys().iter().take(num_signers).copied());
+        for program in program_invocations(transaction_with_meta) {
+            *self.program_invocations.entry(program).or_default() += 1;
+        }
+    }
+
+    pub fn build(self) -> BlockStats {
+        let mut top_programs: Vec<_> = self.program_invocations.into_iter().collect();
+        top_programs.sort_by(|(program, count), (other_program, other_count)| {
+            other_count.cmp(count).then(program.cmp(other_program))
+        });
+        top_programs.truncate(TOP_PROGRAMS_LEN);
+
+        BlockStats {
+            median_fee: median(self.non_vote_fees),
+            median_priority_fee: median(self.non_vote_priority_fees),
+            distinct_signers: self.signers.len() as u64,
+            top_programs,
+            ..self.stats
+        }
+    }
+}
+
+fn median(mut values: Vec<u64>) -> u64 {
+    values.sort_unstable();
+    values.get(values.len() / 2).copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_hash::Hash,
+        solana_message::{compiled_instruction::CompiledInstruction, Message, VersionedMessage},
+        solana_signature::Signature,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_error::TransactionError,
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    fn transaction(payer: Pubkey, program: Pubkey, fee: u64, failed: bool) -> VersionedTransactionWithStatusMeta {
+        let message = Message::new_with_compiled_instructions(
+            1,
+            0,
+            1,
+            vec![payer, program],
+            Hash::default(),
+            vec![CompiledInstruction::new_from_raw_parts(1, vec![], vec![0])],
+        );
+        VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta: TransactionStatusMeta {
+                status: if failed { Err(TransactionError::AccountInUse) } else { Ok(()) },
+                fee,
+                compute_units_consumed: Some(100),
+                ..TransactionStatusMeta::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_block_stats() {
+        let payer = Pubkey::new_unique();
+        let vote_program = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+
+        let mut builder = BlockStatsBuilder::default();
+        builder.add_transaction(&transaction(Pubkey::new_unique(), vote_program, 5000, false), true);
+        builder.add_transaction(&transaction(payer, program, 5000, false), false);
+        builder.add_transaction(&transaction(payer, program, 7000, true), false);
+        builder.add_transaction(&transaction(payer, program, 9000, false), false);
+
+        assert_eq!(
+            builder.build(),
+            BlockStats {
+                vote_transactions: 1,
+                non_vote_transactions: 3,
+                failed_transactions: 1,
+                total_fees: 26_000,
+                median_fee: 7000,
+                total_priority_fees: 6000,
+                median_priority_fee: 2000,
+                compute_units_consumed: 400,
+                distinct_signers: 2,
+                top_programs: vec![(program, 3), (vote_program, 1)],
+            }
+        );
+        assert_eq!(BlockStatsBuilder::default().build(), BlockStats::default());
+    }
+}
@@ -0,0 +1,82 @@
+//! Row keys of the block time index.
+//!
+//! The `block-time` table holds two kinds of rows, both with the block time as a bincode
+//! `UnixTimestamp` cell:
+//!
+//! - `s/{slot}`, to look up the time of a block by slot
+//! - `t/{!block_time}/{!slot}`, ordered newest first, to look up blocks by time
+//!
+//! Numbers are formatted like `slot_to_key`. Blocks without a block time are not indexed.
+
+use {
+    crate::key::{key_to_slot, slot_to_key},
+    solana_clock::{Slot, UnixTimestamp},
+};
+
+pub const BLOCK_TIME_TABLE_NAME: &str = "block-time";
+
+const SLOT_PREFIX: &str = "s/";
+const TIME_PREFIX: &str = "t/";
+
+pub fn slot_to_block_time_key(slot: Slot) -> String {
+    format!("{SLOT_PREFIX}{}", slot_to_key(slot))
+}
+
+pub fn block_time_to_key(block_time: UnixTimestamp, slot: Slot) -> String {
+    format!("{}/{}", block_time_prefix(block_time), slot_to_key(!slot))
+}
+
+/// Key before the time rows of `block_time`, and after those of any later time.
+pub fn block_time_prefix(block_time: UnixTimestamp) -> String {
+    format!("{TIME_PREFIX}{}", slot_to_key(!timestamp_to_u64(block_time)))
+}
+
+/// Key after the time rows of `block_time`, and before those of any earlier time.
+pub fn block_time_prefix_end(block_time: UnixTimestamp) -> String {
+    format!("{}0", block_time_prefix(block_time))
+}
+
+/// Block time and slot of a time row key.
+pub fn key_to_block_time(key: &str) -> Option<(UnixTimestamp, Slot)> {
+    let (block_time, slot) = key.strip_prefix(TIME_PREFIX)?.split_once('/')?;
+    let block_time = UnixTimestamp::try_from(!key_to_slot(block_time)?).ok()?;
+    Some((block_time, !key_to_slot(slot)?))
+}
+
+/// Times before the epoch are indexed as the epoch, so `block_time_prefix_end(0)` is after all
+/// the time rows.
+fn timestamp_to_u64(block_time: UnixTimestamp) -> u64 {
+    u64::try_from(block_time).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_time_keys() {
+        let key = block_time_to_key(1_700_000_000, 42);
+        assert_eq!(key_to_block_time(&key), Some((1_700_000_000, 42)));
+        assert_eq!(key_to_block_time(&slot_to_block_time_key(42)), None);
+
+        // Newest first, then highest slot first
+        let mut keys = vec![
+            block_time_to_key(10, 1),
+            block_time_to_key(11, 3),
+            block_time_to_key(11, 2),
+        ];
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                block_time_to_key(11, 3),
+                block_time_to_key(11, 2),
+                block_time_to_key(10, 1),
+            ]
+        );
+
+        assert!(block_time_prefix(11) < keys[0]);
+        assert!(keys[1] < block_time_prefix_end(11));
+        assert!(block_time_prefix_end(11) < keys[2]);
+    }
+}
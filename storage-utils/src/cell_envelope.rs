@@ -0,0 +1,150 @@
+//! Versioned envelope around compressed cell values.
+//!
+//! Layout of a version 1 cell:
+//!
+//! | bytes   | field                                                       |
+//! |---------|-------------------------------------------------------------|
+//! | 0..4    | magic `SLCE`                                                |
+//! | 4       | format version                                              |
+//! | 5       | payload encoding, see `PayloadEncoding`                     |
+//! | 6..14   | xxHash64 of the version, encoding and payload, little endian |
+//! | 14..    | payload as produced by `compress*`, starting with the `CompressionMethod` |
+//!
+//! Cells written without the envelope start with the bincode `CompressionMethod` index, whose
+//! upper bytes are always zero, so they can not be mistaken for the magic.
+
+use {
+    crate::compression::decompress,
+    std::{hash::Hasher, io},
+    thiserror::Error,
+    twox_hash::XxHash64,
+};
+
+pub const CELL_ENVELOPE_MAGIC: [u8; 4] = *b"SLCE";
+pub const CELL_ENVELOPE_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadEncoding {
+    Bincode,
+    Protobuf,
+}
+
+impl PayloadEncoding {
+    fn to_byte(self) -> u8 {
+        match self {
+            PayloadEncoding::Bincode => 0,
+            PayloadEncoding::Protobuf => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(PayloadEncoding::Bincode),
+            1 => Some(PayloadEncoding::Protobuf),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CellError {
+    #[error("I/O: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Unsupported cell format version: {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("Unknown cell payload encoding: {0}")]
+    UnknownPayloadEncoding(u8),
+
+    #[error("Cell is truncated: {0} bytes")]
+    Truncated(usize),
+
+    #[error("Cell checksum mismatch: stored {stored:016x}, computed {computed:016x}")]
+    ChecksumMismatch { stored: u64, computed: u64 },
+}
+
+pub fn is_enveloped(data: &[u8]) -> bool {
+    data.starts_with(&CELL_ENVELOPE_MAGIC)
+}
+
+/// Wrap a compressed cell value in the envelope.
+pub fn wrap_cell(encoding: PayloadEncoding, compressed: &[u8]) -> Vec<u8> {
+    let version_and_encoding = [CELL_ENVELOPE_VERSION, encoding.to_byte()];
+
+    let mut data = Vec::with_capacity(HEADER_LEN + compressed.len());
+    data.extend_from_slice(&CELL_ENVELOPE_MAGIC);
+    data.extend_from_slice(&version_and_encoding);
+    data.extend_from_slice(&checksum(&version_and_encoding, compressed).to_le_bytes());
+    data.extend_from_slice(compressed);
+    data
+}
+
+/// Verify and decompress a cell value, with or without the envelope. The payload encoding is
+/// only known for enveloped cells.
+pub fn unwrap_cell(data: &[u8]) -> Result<(Option<PayloadEncoding>, Vec<u8>), CellError> {
+    if !is_enveloped(data) {
+        return Ok((None, decompress(data)?));
+    }
+    if data.len() < HEADER_LEN {
+        return Err(CellError::Truncated(data.len()));
+    }
+
+    let version = data[4];
+    if version != CELL_ENVELOPE_VERSION {
+        return Err(CellError::UnsupportedVersion(version));
+    }
+    let encoding =
+        PayloadEncoding::from_byte(data[5]).ok_or(CellError::UnknownPayloadEncoding(data[5]))?;
+
+    let mut stored = [0u8; 8];
+    stored.copy_from_slice(&data[6..HEADER_LEN]);
+    let stored = u64::from_le_bytes(stored);
+    let payload = &data[HEADER_LEN..];
+    let computed = checksum(&data[4..6], payload);
+    if stored != computed {
+        return Err(CellError::ChecksumMismatch { stored, computed });
+    }
+
+    Ok((Some(encoding), decompress(payload)?))
+}
+
+fn checksum(version_and_encoding: &[u8], payload: &[u8]) -> u64 {
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(version_and_encoding);
+    hasher.write(payload);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::compression::{compress, CompressionMethod},
+    };
+
+    #[test]
+    fn test_unwrap_cell() {
+        let data = b"some cell data".to_vec();
+        let compressed = compress(CompressionMethod::Zstd, &data).unwrap();
+
+        let (encoding, unwrapped) = unwrap_cell(&compressed).unwrap();
+        assert_eq!(encoding, None);
+        assert_eq!(unwrapped, data);
+
+        let wrapped = wrap_cell(PayloadEncoding::Protobuf, &compressed);
+        let (encoding, unwrapped) = unwrap_cell(&wrapped).unwrap();
+        assert_eq!(encoding, Some(PayloadEncoding::Protobuf));
+        assert_eq!(unwrapped, data);
+
+        let mut corrupt = wrapped;
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0xff;
+        assert!(matches!(
+            unwrap_cell(&corrupt),
+            Err(CellError::ChecksumMismatch { .. })
+        ));
+    }
+}
@@ -1,36 +1,242 @@
 use {
+    crate::zstd_dictionary::{get_dictionary, Dictionary},
     enum_iterator::{all, Sequence},
-    std::io::{self, BufReader, Read, Write},
+    std::{
+        fmt,
+        io::{self, BufReader, Read, Write},
+        str::FromStr,
+        sync::Arc,
+    },
 };
 
-#[derive(Debug, Serialize, Deserialize, Sequence)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Sequence)]
 pub enum CompressionMethod {
     NoCompression,
     Bzip2,
     Gzip,
     Zstd,
+    // New methods must be appended, the variant index is the cell header.
+    Lz4,
+    Snappy,
+    /// Zstd with a trained dictionary, the dictionary id follows the header as a little endian
+    /// u32. See `compress_with_dictionary`.
+    ZstdDictionary,
+}
+
+impl CompressionMethod {
+    /// Range of the levels accepted by `compress_with_level`.
+    pub fn level_range(&self) -> std::ops::RangeInclusive<i32> {
+        match self {
+            CompressionMethod::NoCompression => 0..=0,
+            CompressionMethod::Bzip2 => 1..=9,
+            CompressionMethod::Gzip => 0..=9,
+            CompressionMethod::Zstd | CompressionMethod::ZstdDictionary => {
+                zstd::compression_level_range()
+            }
+            CompressionMethod::Lz4 | CompressionMethod::Snappy => 0..=0,
+        }
+    }
+
+    /// Check that `level` is in `level_range`, if set.
+    pub fn check_level(&self, level: Option<i32>) -> Result<(), String> {
+        match level {
+            Some(level) if !self.level_range().contains(&level) => Err(format!(
+                "compression level {level} is out of range {:?} for {self}",
+                self.level_range()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for CompressionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressionMethod::NoCompression => write!(f, "none"),
+            CompressionMethod::Bzip2 => write!(f, "bzip2"),
+            CompressionMethod::Gzip => write!(f, "gzip"),
+            CompressionMethod::Zstd => write!(f, "zstd"),
+            CompressionMethod::Lz4 => write!(f, "lz4"),
+            CompressionMethod::Snappy => write!(f, "snappy"),
+            CompressionMethod::ZstdDictionary => write!(f, "zstd-dict"),
+        }
+    }
+}
+
+impl FromStr for CompressionMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(CompressionMethod::NoCompression),
+            "bzip2" => Ok(CompressionMethod::Bzip2),
+            "gzip" => Ok(CompressionMethod::Gzip),
+            "zstd" => Ok(CompressionMethod::Zstd),
+            "lz4" => Ok(CompressionMethod::Lz4),
+            "snappy" => Ok(CompressionMethod::Snappy),
+            _ => Err(format!("unknown compression method: {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMode {
+    NoCompression,
+    /// Compress with every method and keep the smallest output.
+    Best,
+    /// Compress with `method`, at `level` or the method's default level.
+    Fixed {
+        method: CompressionMethod,
+        level: Option<i32>,
+    },
+    /// Compress with zstd and the registered dictionary `id`.
+    ZstdDictionary {
+        id: u32,
+        level: Option<i32>,
+    },
+}
+
+impl fmt::Display for CompressionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompressionMode::NoCompression => write!(f, "none"),
+            CompressionMode::Best => write!(f, "best"),
+            CompressionMode::Fixed { method, level: None } => write!(f, "{method}"),
+            CompressionMode::Fixed { method, level: Some(level) } => write!(f, "{method}:{level}"),
+            CompressionMode::ZstdDictionary { id, level: None } => write!(f, "zstd-dict:{id}"),
+            CompressionMode::ZstdDictionary { id, level: Some(level) } => {
+                write!(f, "zstd-dict:{id}:{level}")
+            }
+        }
+    }
+}
+
+/// Parses `none`, `best`, `<method>`, `<method>:<level>`, e.g. `zstd:3`, or
+/// `zstd-dict:<id>[:<level>]`.
+impl FromStr for CompressionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => return Ok(CompressionMode::NoCompression),
+            "best" => return Ok(CompressionMode::Best),
+            _ => {}
+        }
+
+        if let Some(dictionary) = s.strip_prefix("zstd-dict:") {
+            let (id, level) = match dictionary.split_once(':') {
+                Some((id, level)) => (id, Some(level)),
+                None => (dictionary, None),
+            };
+            let id = id
+                .parse::<u32>()
+                .map_err(|err| format!("invalid zstd dictionary id {id}: {err}"))?;
+            let level = level
+                .map(|level| level.parse::<i32>())
+                .transpose()
+                .map_err(|err| format!("invalid compression level: {err}"))?;
+            CompressionMethod::ZstdDictionary.check_level(level)?;
+            return Ok(CompressionMode::ZstdDictionary { id, level });
+        }
+
+        let (method, level) = match s.split_once(':') {
+            Some((method, level)) => {
+                let level = level
+                    .parse::<i32>()
+                    .map_err(|err| format!("invalid compression level {level}: {err}"))?;
+                (method.parse::<CompressionMethod>()?, Some(level))
+            }
+            None => (s.parse::<CompressionMethod>()?, None),
+        };
+
+        method.check_level(level)?;
+
+        Ok(CompressionMode::Fixed { method, level })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionPolicy {
+    pub mode: CompressionMode,
+    /// Data shorter than this many bytes is stored uncompressed.
+    pub min_size: usize,
+}
+
+impl Default for CompressionPolicy {
+    fn default() -> Self {
+        Self {
+            mode: CompressionMode::Best,
+            min_size: 0,
+        }
+    }
+}
+
+impl CompressionPolicy {
+    pub fn none() -> Self {
+        Self {
+            mode: CompressionMode::NoCompression,
+            min_size: 0,
+        }
+    }
+}
+
+impl fmt::Display for CompressionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, min size: {}", self.mode, self.min_size)
+    }
 }
 
 pub fn compress(method: CompressionMethod, data: &[u8]) -> Result<Vec<u8>, io::Error> {
+    compress_with_level(method, None, data)
+}
+
+pub fn compress_with_level(
+    method: CompressionMethod,
+    level: Option<i32>,
+    data: &[u8],
+) -> Result<Vec<u8>, io::Error> {
+    // Policies built in code skip the check of `FromStr`, and the codecs take unsigned levels
+    check_level(method, level)?;
     let mut compressed_data = bincode::serialize(&method).unwrap();
     compressed_data.extend(
         match method {
             CompressionMethod::Bzip2 => {
-                let mut e = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::best());
+                let level = level
+                    .map(|level| bzip2::Compression::new(level as u32))
+                    .unwrap_or_else(bzip2::Compression::best);
+                let mut e = bzip2::write::BzEncoder::new(Vec::new(), level);
                 e.write_all(data)?;
                 e.finish()?
             }
             CompressionMethod::Gzip => {
-                let mut e =
-                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                let level = level
+                    .map(|level| flate2::Compression::new(level as u32))
+                    .unwrap_or_default();
+                let mut e = flate2::write::GzEncoder::new(Vec::new(), level);
                 e.write_all(data)?;
                 e.finish()?
             }
             CompressionMethod::Zstd => {
-                let mut e = zstd::stream::write::Encoder::new(Vec::new(), 0).unwrap();
+                let mut e = zstd::stream::write::Encoder::new(Vec::new(), level.unwrap_or(0))?;
                 e.write_all(data)?;
                 e.finish()?
             }
+            CompressionMethod::Lz4 => {
+                let mut e = lz4_flex::frame::FrameEncoder::new(Vec::new());
+                e.write_all(data)?;
+                e.finish().map_err(io::Error::from)?
+            }
+            CompressionMethod::Snappy => {
+                let mut e = snap::write::FrameEncoder::new(Vec::new());
+                e.write_all(data)?;
+                e.into_inner().map_err(|err| err.into_error())?
+            }
+            CompressionMethod::ZstdDictionary => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "zstd dictionary compression needs a dictionary id",
+                ));
+            }
             CompressionMethod::NoCompression => data.to_vec(),
         }
             .into_iter(),
@@ -39,9 +245,37 @@ pub fn compress(method: CompressionMethod, data: &[u8]) -> Result<Vec<u8>, io::E
     Ok(compressed_data)
 }
 
+pub fn compress_with_dictionary(
+    dictionary_id: u32,
+    level: Option<i32>,
+    data: &[u8],
+) -> Result<Vec<u8>, io::Error> {
+    check_level(CompressionMethod::ZstdDictionary, level)?;
+    let dictionary = get_dictionary(dictionary_id)?.encoder(level.unwrap_or(0));
+
+    let mut compressed_data = bincode::serialize(&CompressionMethod::ZstdDictionary).unwrap();
+    compressed_data.extend(dictionary_id.to_le_bytes());
+
+    // The dictionary id is already in our header, no need to repeat it in the frame.
+    let mut e = zstd::stream::write::Encoder::with_prepared_dictionary(compressed_data, &dictionary)?;
+    e.include_dictid(false)?;
+    e.write_all(data)?;
+    e.finish()
+}
+
+fn check_level(method: CompressionMethod, level: Option<i32>) -> Result<(), io::Error> {
+    method
+        .check_level(level)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
 pub fn compress_best(data: &[u8]) -> Result<Vec<u8>, io::Error> {
     let mut candidates = vec![];
     for method in all::<CompressionMethod>() {
+        // Dictionaries are opt-in per table
+        if method == CompressionMethod::ZstdDictionary {
+            continue;
+        }
         candidates.push(compress(method, data)?);
     }
 
@@ -51,20 +285,65 @@ pub fn compress_best(data: &[u8]) -> Result<Vec<u8>, io::Error> {
         .unwrap())
 }
 
+/// Compress `data` as configured by `policy`. The output is readable by `decompress` whatever
+/// the policy.
+pub fn compress_with_policy(policy: &CompressionPolicy, data: &[u8]) -> Result<Vec<u8>, io::Error> {
+    if data.len() < policy.min_size {
+        return compress(CompressionMethod::NoCompression, data);
+    }
+
+    match policy.mode {
+        CompressionMode::NoCompression => compress(CompressionMethod::NoCompression, data),
+        CompressionMode::Best => compress_best(data),
+        CompressionMode::Fixed { method, level } => compress_with_level(method, level, data),
+        CompressionMode::ZstdDictionary { id, level } => compress_with_dictionary(id, level, data),
+    }
+}
+
 fn decompress_reader<'a, R: Read + 'a>(
     method: CompressionMethod,
-    stream: R,
+    mut stream: R,
 ) -> Result<Box<dyn Read + 'a>, io::Error> {
+    if method == CompressionMethod::ZstdDictionary {
+        let mut dictionary_id = [0u8; 4];
+        stream.read_exact(&mut dictionary_id)?;
+        let dictionary = get_dictionary(u32::from_le_bytes(dictionary_id))?;
+        let decoder = zstd::stream::read::Decoder::with_prepared_dictionary(
+            BufReader::new(stream),
+            dictionary.decoder(),
+        )?;
+        return Ok(Box::new(DictionaryReader {
+            decoder,
+            _dictionary: dictionary,
+        }));
+    }
+
     let buf_reader = BufReader::new(stream);
     let decompress_reader: Box<dyn Read> = match method {
         CompressionMethod::Bzip2 => Box::new(bzip2::bufread::BzDecoder::new(buf_reader)),
         CompressionMethod::Gzip => Box::new(flate2::read::GzDecoder::new(buf_reader)),
         CompressionMethod::Zstd => Box::new(zstd::stream::read::Decoder::new(buf_reader)?),
+        CompressionMethod::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(buf_reader)),
+        CompressionMethod::Snappy => Box::new(snap::read::FrameDecoder::new(buf_reader)),
+        CompressionMethod::ZstdDictionary => unreachable!(),
         CompressionMethod::NoCompression => Box::new(buf_reader),
     };
     Ok(decompress_reader)
 }
 
+/// Zstd decoder keeping its prepared dictionary alive, in case the dictionary is registered again
+/// while decoding.
+struct DictionaryReader<'a, R: Read> {
+    decoder: zstd::stream::read::Decoder<'a, BufReader<R>>,
+    _dictionary: Arc<Dictionary>,
+}
+
+impl<R: Read> Read for DictionaryReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.decoder.read(buf)
+    }
+}
+
 pub fn decompress(data: &[u8]) -> Result<Vec<u8>, io::Error> {
     let method_size = bincode::serialized_size(&CompressionMethod::NoCompression).unwrap();
     if (data.len() as u64) < method_size {
@@ -73,10 +352,16 @@ pub fn decompress(data: &[u8]) -> Result<Vec<u8>, io::Error> {
             format!("data len too small: {}", data.len()),
         ));
     }
-    let method = bincode::deserialize(&data[..method_size as usize]).map_err(|err| {
+    let method = bincode::deserialize(&data[..method_size as usize]).map_err(|_| {
+        // The header is the bincode variant index, a little endian u32
+        let mut index = [0u8; 4];
+        index.copy_from_slice(&data[..4]);
         io::Error::new(
-            io::ErrorKind::Other,
-            format!("method deserialize failed: {err}"),
+            io::ErrorKind::InvalidData,
+            format!(
+                "unknown compression method: {}, the data was written by a newer version",
+                u32::from_le_bytes(index)
+            ),
         )
     })?;
 
@@ -95,4 +380,96 @@ mod test {
         let data = vec![0; 256];
         assert!(compress_best(&data).expect("compress_best").len() < data.len());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_compress_with_policy() {
+        let data = vec![0; 256];
+
+        for mode in ["none", "best", "bzip2:1", "gzip", "zstd:19", "lz4", "snappy"] {
+            let policy = CompressionPolicy {
+                mode: mode.parse().unwrap(),
+                min_size: 0,
+            };
+            let compressed = compress_with_policy(&policy, &data).expect("compress_with_policy");
+            assert_eq!(decompress(&compressed).expect("decompress"), data);
+        }
+
+        let policy = CompressionPolicy {
+            mode: CompressionMode::Best,
+            min_size: 512,
+        };
+        assert_eq!(
+            compress_with_policy(&policy, &data).unwrap(),
+            compress(CompressionMethod::NoCompression, &data).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_compression_mode() {
+        assert_eq!(
+            "zstd:3".parse::<CompressionMode>(),
+            Ok(CompressionMode::Fixed {
+                method: CompressionMethod::Zstd,
+                level: Some(3),
+            })
+        );
+        assert!("gzip:10".parse::<CompressionMode>().is_err());
+        assert!("zstd-dict:1:100".parse::<CompressionMode>().is_err());
+        assert!("brotli".parse::<CompressionMode>().is_err());
+    }
+
+    #[test]
+    fn test_compress_out_of_range_level() {
+        let data = vec![0; 256];
+        for (method, level) in [
+            (CompressionMethod::Bzip2, -1),
+            (CompressionMethod::Gzip, 10),
+            (CompressionMethod::Zstd, 100),
+        ] {
+            let policy = CompressionPolicy {
+                mode: CompressionMode::Fixed { method, level: Some(level) },
+                min_size: 0,
+            };
+            let err = compress_with_policy(&policy, &data).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(
+            compress_with_dictionary(1, Some(100), &data).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_decompress_unknown_method() {
+        let mut data = vec![0; 256];
+        data[..4].copy_from_slice(&42u32.to_le_bytes());
+
+        let err = decompress(&data).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("unknown compression method: 42"));
+    }
+
+    #[test]
+    fn test_compress_with_dictionary() {
+        let samples = (0..1000u32)
+            .map(|i| format!("{{\"slot\":{},\"index\":{},\"err\":null}}", 1000 + i, i % 7).into_bytes())
+            .collect::<Vec<_>>();
+        let dictionary = crate::zstd_dictionary::train_dictionary(&samples, 1024).unwrap();
+        let id = crate::zstd_dictionary::register_dictionary(dictionary).unwrap();
+
+        let data = samples[42].clone();
+        let compressed = compress_with_dictionary(id, None, &data).unwrap();
+        assert!(compressed.len() < compress(CompressionMethod::Zstd, &data).unwrap().len());
+        assert_eq!(decompress(&compressed).unwrap(), data);
+
+        // Dictionaries are prepared once per level
+        let compressed_19 = compress_with_dictionary(id, Some(19), &data).unwrap();
+        assert_eq!(decompress(&compressed_19).unwrap(), data);
+        let dictionary = get_dictionary(id).unwrap();
+        assert!(Arc::ptr_eq(&dictionary.encoder(19), &dictionary.encoder(19)));
+
+        let mut unknown = compressed;
+        unknown[4..8].copy_from_slice(&id.wrapping_add(1).to_le_bytes());
+        assert_eq!(decompress(&unknown).unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
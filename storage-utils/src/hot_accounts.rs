@@ -0,0 +1,102 @@
+//! History of the addresses left out of `tx-by-addr` for being in too many transactions.
+//!
+//! The `suppressed-addresses` table has a row per change of the suppression of an address,
+//! `{address}/{slot}` with the slot formatted like `slot_to_key`, so oldest first, holding the
+//! bincode `SuppressionChange` made from the block at that slot on. The transactions of an
+//! address are missing from `tx-by-addr` over its `SuppressionPeriod`s.
+
+use {
+    crate::key::{key_to_slot, slot_to_key},
+    serde::{Deserialize, Serialize},
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+};
+
+pub const SUPPRESSED_ADDRESSES_TABLE_NAME: &str = "suppressed-addresses";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuppressionChange {
+    /// Whether the address stopped being indexed, otherwise it is indexed again
+    pub suppressed: bool,
+    /// Transactions of the address over the window ending at the block
+    pub window_transactions: u64,
+}
+
+/// Slots over which the transactions of an address are missing from `tx-by-addr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuppressionPeriod {
+    pub start_slot: Slot,
+    /// First slot indexed again, `None` while the address is still suppressed
+    pub end_slot: Option<Slot>,
+}
+
+impl SuppressionPeriod {
+    pub fn contains(&self, slot: Slot) -> bool {
+        self.start_slot <= slot && self.end_slot.is_none_or(|end_slot| slot < end_slot)
+    }
+}
+
+pub fn suppression_key(address: &Pubkey, slot: Slot) -> String {
+    format!("{address}/{}", slot_to_key(slot))
+}
+
+/// Slot of a `suppressed-addresses` row key.
+pub fn key_to_suppression_slot(key: &str) -> Option<Slot> {
+    let (_, slot) = key.rsplit_once('/')?;
+    key_to_slot(slot)
+}
+
+/// Periods described by the changes of an address, ordered by slot.
+pub fn suppression_periods(
+    changes: impl IntoIterator<Item = (Slot, SuppressionChange)>,
+) -> Vec<SuppressionPeriod> {
+    let mut periods: Vec<SuppressionPeriod> = vec![];
+    for (slot, change) in changes {
+        let is_open = periods.last().is_some_and(|period| period.end_slot.is_none());
+        if change.suppressed && !is_open {
+            periods.push(SuppressionPeriod {
+                start_slot: slot,
+                end_slot: None,
+            });
+        } else if !change.suppressed && is_open {
+            if let Some(period) = periods.last_mut() {
+                period.end_slot = Some(slot);
+            }
+        }
+    }
+    periods
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_suppression_periods() {
+        let change = |suppressed| SuppressionChange {
+            suppressed,
+            window_transactions: 0,
+        };
+        let address = Pubkey::new_unique();
+        let key = suppression_key(&address, 42);
+        assert_eq!(key, format!("{address}/000000000000002a"));
+        assert_eq!(key_to_suppression_slot(&key), Some(42));
+
+        let periods = suppression_periods(vec![
+            (10, change(true)),
+            (12, change(true)),
+            (20, change(false)),
+            (30, change(true)),
+        ]);
+        assert_eq!(
+            periods,
+            vec![
+                SuppressionPeriod { start_slot: 10, end_slot: Some(20) },
+                SuppressionPeriod { start_slot: 30, end_slot: None },
+            ]
+        );
+        assert!(periods[0].contains(19) && !periods[0].contains(20));
+        assert!(periods[1].contains(u64::MAX) && !periods[1].contains(29));
+        assert!(suppression_periods(vec![(5, change(false))]).is_empty());
+    }
+}
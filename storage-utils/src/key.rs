@@ -3,6 +3,7 @@ use {
         Slot,
     },
     log::*,
+    std::fmt,
 };
 
 // Convert a slot to its bucket representation whereby lower slots are always lexically ordered
@@ -25,10 +26,56 @@ pub fn slot_to_blocks_key(slot: Slot, use_md5: bool) -> String {
     }
 }
 
+/// Slot of a `blocks` row key made by `slot_to_blocks_key`, salted or not.
+pub fn blocks_key_to_slot(key: &str) -> Option<Slot> {
+    let slot_hex = key.get(key.len().checked_sub(16)?..)?;
+    key_to_slot(slot_hex)
+}
+
 pub fn slot_to_tx_by_addr_key(slot: Slot) -> String {
     slot_to_key(!slot)
 }
 
+/// Most salt buckets `tx-by-addr` row keys can be spread over.
+pub const MAX_TX_BY_ADDR_SALT_BUCKETS: u16 = 256;
+
+/// Salt bucket of the `tx-by-addr` row of `address` at `slot`. The address picks the bucket of
+/// its first slot and each following slot moves to the next one, so the rows of a busy address
+/// are spread over all the buckets instead of a single region.
+pub fn tx_by_addr_salt_bucket(address: &str, slot: Slot, buckets: u16) -> u16 {
+    let buckets = u64::from(buckets.clamp(1, MAX_TX_BY_ADDR_SALT_BUCKETS));
+    let hash = md5::compute(address);
+    let offset = u64::from_be_bytes(hash[..8].try_into().unwrap());
+    ((offset % buckets + slot % buckets) % buckets) as u16
+}
+
+/// Prefix of the `tx-by-addr` row keys in `bucket`. Prefixes are spread evenly over `00`-`ff`,
+/// so tables can be pre-split on the hex alphabet whatever the number of buckets.
+pub fn tx_by_addr_salt_prefix(bucket: u16, buckets: u16) -> String {
+    let buckets = u32::from(buckets.clamp(1, MAX_TX_BY_ADDR_SALT_BUCKETS));
+    format!("{:02x}/", u32::from(bucket) * 256 / buckets)
+}
+
+/// Row key of the `tx-by-addr` row of `address` at `slot`, `{address}/{!slot}` with the slot
+/// formatted like `slot_to_key`, prefixed by its salt bucket if `salt_buckets` is set.
+pub fn tx_by_addr_key(address: impl fmt::Display, slot: Slot, salt_buckets: Option<u16>) -> String {
+    let address = address.to_string();
+    let key = format!("{address}/{}", slot_to_tx_by_addr_key(slot));
+    match salt_buckets {
+        Some(buckets) => {
+            let bucket = tx_by_addr_salt_bucket(&address, slot, buckets);
+            format!("{}{key}", tx_by_addr_salt_prefix(bucket, buckets))
+        }
+        None => key,
+    }
+}
+
+/// Salted form of an unsalted `tx-by-addr` row key, see `tx_by_addr_key`.
+pub fn salt_tx_by_addr_key(key: &str, salt_buckets: u16) -> Option<String> {
+    let (address, slot) = key.split_once('/')?;
+    Some(tx_by_addr_key(address, !key_to_slot(slot)?, Some(salt_buckets)))
+}
+
 pub fn key_to_slot(key: &str) -> Option<Slot> {
     match Slot::from_str_radix(key, 16) {
         Ok(slot) => Some(slot),
@@ -49,4 +96,35 @@ mod test {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    #[test]
+    fn test_blocks_key_to_slot() {
+        assert_eq!(blocks_key_to_slot(&slot_to_blocks_key(42, false)), Some(42));
+        assert_eq!(blocks_key_to_slot(&slot_to_blocks_key(42, true)), Some(42));
+        assert_eq!(blocks_key_to_slot("2a"), None);
+    }
+
+    #[test]
+    fn test_tx_by_addr_key() {
+        let address = "Vote111111111111111111111111111111111111111";
+        let key = tx_by_addr_key(address, 42, None);
+        assert_eq!(key, format!("{address}/ffffffffffffffd5"));
+
+        let buckets = 16;
+        let salted = tx_by_addr_key(address, 42, Some(buckets));
+        assert_eq!(salt_tx_by_addr_key(&key, buckets), Some(salted.clone()));
+        let bucket = tx_by_addr_salt_bucket(address, 42, buckets);
+        assert_eq!(salted, format!("{}{key}", tx_by_addr_salt_prefix(bucket, buckets)));
+
+        // Consecutive slots of an address go through every bucket
+        let mut prefixes: Vec<_> = (42..42 + u64::from(buckets))
+            .map(|slot| tx_by_addr_salt_prefix(tx_by_addr_salt_bucket(address, slot, buckets), buckets))
+            .collect();
+        prefixes.sort();
+        prefixes.dedup();
+        assert_eq!(prefixes.len(), buckets as usize);
+        assert_eq!(prefixes.first().unwrap(), "00/");
+        assert_eq!(prefixes.last().unwrap(), "f0/");
+        assert_eq!(tx_by_addr_salt_prefix(255, MAX_TX_BY_ADDR_SALT_BUCKETS), "ff/");
+    }
 }
\ No newline at end of file
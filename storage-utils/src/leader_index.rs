@@ -0,0 +1,93 @@
+//! Row keys of the block leader index.
+//!
+//! The leader of a block is the recipient of its fee reward. The `leader-index` table holds two
+//! kinds of rows, each with the leader as a bincode `Pubkey` cell:
+//!
+//! - `s/{slot}`, to look up the leader of a block
+//! - `l/{leader}/{slot}`, ordered by slot, to list the blocks of a leader
+//!
+//! Numbers are formatted like `slot_to_key`. Blocks without a fee reward are not indexed.
+
+use {
+    crate::key::{key_to_slot, slot_to_key},
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_transaction_status::{Reward, RewardType},
+    std::str::FromStr,
+};
+
+pub const LEADER_INDEX_TABLE_NAME: &str = "leader-index";
+
+pub fn slot_to_leader_key(slot: Slot) -> String {
+    format!("s/{}", slot_to_key(slot))
+}
+
+pub fn leader_slot_key(leader: &Pubkey, slot: Slot) -> String {
+    format!("l/{leader}/{}", slot_to_key(slot))
+}
+
+/// Key after the row of `leader` at `slot`, and before the one at any later slot.
+pub fn leader_slot_key_end(leader: &Pubkey, slot: Slot) -> String {
+    format!("{}0", leader_slot_key(leader, slot))
+}
+
+/// Slot of a leader row key.
+pub fn key_to_leader_slot(key: &str) -> Option<Slot> {
+    let (_, slot) = key.strip_prefix("l/")?.rsplit_once('/')?;
+    key_to_slot(slot)
+}
+
+/// Leader of a block, from its rewards.
+pub fn block_leader(rewards: &[Reward]) -> Option<Pubkey> {
+    rewards
+        .iter()
+        .find(|reward| reward.reward_type == Some(RewardType::Fee))
+        .and_then(|reward| Pubkey::from_str(&reward.pubkey).ok())
+}
+
+/// Rows of the block at `slot`, as row key and value.
+pub fn leader_index_cells(slot: Slot, rewards: &[Reward]) -> Vec<(String, Pubkey)> {
+    block_leader(rewards)
+        .map(|leader| {
+            vec![
+                (slot_to_leader_key(slot), leader),
+                (leader_slot_key(&leader, slot), leader),
+            ]
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_leader_index_cells() {
+        let leader = Pubkey::new_unique();
+        let reward = |pubkey: &Pubkey, reward_type| Reward {
+            pubkey: pubkey.to_string(),
+            lamports: 5000,
+            post_balance: 10_000,
+            reward_type: Some(reward_type),
+            commission: None,
+        };
+        let rewards = vec![
+            reward(&Pubkey::new_unique(), RewardType::Rent),
+            reward(&leader, RewardType::Fee),
+        ];
+
+        let cells = leader_index_cells(42, &rewards);
+        assert_eq!(
+            cells,
+            vec![
+                ("s/000000000000002a".to_string(), leader),
+                (format!("l/{leader}/000000000000002a"), leader),
+            ]
+        );
+        assert_eq!(key_to_leader_slot(&cells[1].0), Some(42));
+        assert_eq!(key_to_leader_slot(&cells[0].0), None);
+        assert!(cells[1].0 < leader_slot_key_end(&leader, 42));
+        assert!(leader_slot_key_end(&leader, 42) < leader_slot_key(&leader, 43));
+        assert!(leader_index_cells(42, &rewards[..1]).is_empty());
+    }
+}
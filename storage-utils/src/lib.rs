@@ -10,4 +10,34 @@ pub mod key;
 
 pub mod compression;
 
+pub mod zstd_dictionary;
+
+pub mod cell_envelope;
+
+pub mod block_rows;
+
+pub mod token_index;
+
+pub mod program_index;
+
+pub mod block_time;
+
+pub mod block_index;
+
+pub mod balance_history;
+
+pub mod token_balance_history;
+
+pub mod rewards;
+
+pub mod leader_index;
+
+pub mod block_stats;
+
+pub mod memo_index;
+
+pub mod hot_accounts;
+
+pub mod slot_index;
+
 pub use crate::key::*;
\ No newline at end of file
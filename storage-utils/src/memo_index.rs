@@ -0,0 +1,136 @@
+//! Row keys of the memo index.
+//!
+//! The `memo-index` table has a row per memo of a transaction, `{memo}/{!slot}/{index}`, holding
+//! the transaction signature as a bincode `Signature` cell. The memo is normalized and hex
+//! encoded, which keeps the order and prefixes of the normalized memos, and the slot and the
+//! transaction index are formatted like `slot_to_key`, so the transactions with the same memo are
+//! listed newest first. Unparseable and empty memos are not indexed.
+
+use {
+    crate::key::{key_to_slot, slot_to_key},
+    solana_clock::Slot,
+    solana_signature::Signature,
+    solana_transaction_status::{extract_memos::ExtractMemos, VersionedTransactionWithStatusMeta},
+    std::{collections::BTreeSet, fmt::Write},
+};
+
+pub const MEMO_INDEX_TABLE_NAME: &str = "memo-index";
+
+/// Longest normalized memo indexed, in bytes. Longer memos are indexed by their beginning.
+pub const MAX_MEMO_KEY_LEN: usize = 128;
+
+/// A transaction found by memo, as returned by the readers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoMatch {
+    /// Normalized memo
+    pub memo: String,
+    pub slot: Slot,
+    pub index: u32,
+    pub signature: Signature,
+}
+
+/// Memo as it is indexed and looked up: trimmed, lowercase and at most `MAX_MEMO_KEY_LEN` bytes.
+pub fn normalize_memo(memo: &str) -> String {
+    let mut memo = memo.trim().to_lowercase();
+    if memo.len() > MAX_MEMO_KEY_LEN {
+        let mut len = MAX_MEMO_KEY_LEN;
+        while !memo.is_char_boundary(len) {
+            len -= 1;
+        }
+        memo.truncate(len);
+    }
+    memo
+}
+
+/// First key of the rows of the normalized `memo` and of the longer memos it is a prefix of.
+pub fn memo_prefix_key(memo: &str) -> String {
+    memo.bytes().fold(String::new(), |mut key, byte| {
+        let _ = write!(key, "{byte:02x}");
+        key
+    })
+}
+
+/// Key after the rows of the normalized `memo` and of the longer memos it is a prefix of.
+pub fn memo_prefix_key_end(memo: &str) -> String {
+    format!("{}g", memo_prefix_key(memo))
+}
+
+/// First key of the rows of exactly the normalized `memo`.
+pub fn memo_key(memo: &str) -> String {
+    format!("{}/", memo_prefix_key(memo))
+}
+
+/// Key after the rows of exactly the normalized `memo`.
+pub fn memo_key_end(memo: &str) -> String {
+    format!("{}0", memo_prefix_key(memo))
+}
+
+pub fn memo_to_row_key(memo: &str, slot: Slot, index: u32) -> String {
+    format!("{}{}/{}", memo_key(memo), slot_to_key(!slot), slot_to_key(index as u64))
+}
+
+/// Normalized memo, slot and transaction index of a row key.
+pub fn row_key_to_memo(key: &str) -> Option<(String, Slot, u32)> {
+    let mut parts = key.split('/');
+    let memo = parts.next()?;
+    let slot = !key_to_slot(parts.next()?)?;
+    let index = u32::try_from(key_to_slot(parts.next()?)?).ok()?;
+    if memo.len() % 2 != 0 || !memo.is_ascii() {
+        return None;
+    }
+    let bytes = (0..memo.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&memo[i..i + 2], 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some((String::from_utf8(bytes).ok()?, slot, index))
+}
+
+/// Distinct normalized memos of a transaction.
+pub fn transaction_memos(transaction_with_meta: &VersionedTransactionWithStatusMeta) -> BTreeSet<String> {
+    transaction_with_meta
+        .extract_memos()
+        .iter()
+        // Memos are formatted as `[{len}] {memo}`
+        .filter_map(|memo| memo.split_once("] ").map(|(_, memo)| memo))
+        .filter(|memo| *memo != "(unparseable)")
+        .map(normalize_memo)
+        .filter(|memo| !memo.is_empty())
+        .collect()
+}
+
+/// Rows of a transaction, as row key and value.
+pub fn memo_index_cells(
+    slot: Slot,
+    index: u32,
+    transaction_with_meta: &VersionedTransactionWithStatusMeta,
+) -> Vec<(String, Signature)> {
+    let signature = transaction_with_meta.transaction.signatures[0];
+    transaction_memos(transaction_with_meta)
+        .iter()
+        .map(|memo| (memo_to_row_key(memo, slot, index), signature))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_memo_keys() {
+        assert_eq!(normalize_memo("  Deposit-42 \n"), "deposit-42");
+        assert_eq!(normalize_memo(&"é".repeat(100)).len(), MAX_MEMO_KEY_LEN);
+
+        let key = memo_to_row_key("dep", 42, 7);
+        assert_eq!(key, format!("646570/{}/0000000000000007", slot_to_key(!42)));
+        assert_eq!(row_key_to_memo(&key), Some(("dep".to_string(), 42, 7)));
+        assert_eq!(row_key_to_memo("64657/0/0"), None);
+
+        let longer_key = memo_to_row_key("deposit", 1, 0);
+        let other_key = memo_to_row_key("deq", 1, 0);
+        assert!(memo_key("dep") < key && key < memo_key_end("dep"));
+        assert!(!(memo_key("dep") < longer_key && longer_key < memo_key_end("dep")));
+        assert!(memo_prefix_key("dep") < longer_key && longer_key < memo_prefix_key_end("dep"));
+        assert!(!(memo_prefix_key("dep") < other_key && other_key < memo_prefix_key_end("dep")));
+        assert!(memo_to_row_key("dep", 43, 0) < key);
+    }
+}
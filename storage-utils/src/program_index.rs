@@ -0,0 +1,85 @@
+use {
+    solana_pubkey::Pubkey,
+    solana_transaction_status::VersionedTransactionWithStatusMeta,
+    std::collections::BTreeSet,
+};
+
+pub const TX_BY_PROGRAM_TABLE_NAME: &str = "tx-by-program";
+
+/// Programs a transaction invoked, from its top-level instructions and the inner instructions
+/// recorded in its meta, which include cross-program invocations.
+pub fn invoked_programs(transaction_with_meta: &VersionedTransactionWithStatusMeta) -> BTreeSet<Pubkey> {
+    program_invocations(transaction_with_meta).into_iter().collect()
+}
+
+/// Program of each instruction of a transaction, top-level ones first, so a program appears
+/// once per invocation.
+pub fn program_invocations(transaction_with_meta: &VersionedTransactionWithStatusMeta) -> Vec<Pubkey> {
+    let account_keys = transaction_with_meta.account_keys();
+    let top_level_instructions = transaction_with_meta.transaction.message.instructions().iter();
+    let inner_instructions = transaction_with_meta
+        .meta
+        .inner_instructions
+        .iter()
+        .flatten()
+        .flat_map(|inner_instructions| &inner_instructions.instructions)
+        .map(|inner_instruction| &inner_instruction.instruction);
+
+    top_level_instructions
+        .chain(inner_instructions)
+        .filter_map(|instruction| account_keys.get(instruction.program_id_index as usize))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_hash::Hash,
+        solana_message::{compiled_instruction::CompiledInstruction, Message, VersionedMessage},
+        solana_signature::Signature,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::{InnerInstruction, InnerInstructions, TransactionStatusMeta},
+    };
+
+    #[test]
+    fn test_invoked_programs() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let cpi_program = Pubkey::new_unique();
+        let message = Message::new_with_compiled_instructions(
+            1,
+            0,
+            2,
+            vec![payer, program, cpi_program],
+            Hash::default(),
+            vec![CompiledInstruction::new_from_raw_parts(1, vec![], vec![0, 2])],
+        );
+        let inner_instructions = InnerInstructions {
+            index: 0,
+            instructions: vec![InnerInstruction {
+                instruction: CompiledInstruction::new_from_raw_parts(2, vec![], vec![0]),
+                stack_height: Some(2),
+            }],
+        };
+        let mut transaction_with_meta = VersionedTransactionWithStatusMeta {
+            transaction: VersionedTransaction {
+                signatures: vec![Signature::default()],
+                message: VersionedMessage::Legacy(message),
+            },
+            meta: TransactionStatusMeta::default(),
+        };
+
+        assert_eq!(
+            invoked_programs(&transaction_with_meta),
+            BTreeSet::from([program])
+        );
+
+        transaction_with_meta.meta.inner_instructions = Some(vec![inner_instructions]);
+        assert_eq!(
+            invoked_programs(&transaction_with_meta),
+            BTreeSet::from([program, cpi_program])
+        );
+    }
+}
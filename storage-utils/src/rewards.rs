@@ -0,0 +1,144 @@
+//! Block rewards by recipient and epoch.
+//!
+//! The `rewards` table has a row per recipient, epoch and slot, `{pubkey}/{epoch}/{slot}` with
+//! both numbers as 16 digit hex, holding the bincode `Vec<RewardCell>` the block at that slot
+//! paid to the recipient. Staking and voting rewards are paid in the epoch after the one they
+//! are earned in, possibly over several blocks when they are partitioned, and are keyed by the
+//! epoch they were earned in, like `getInflationReward` reports them. Fee and rent rewards are
+//! keyed by the epoch of their block.
+//!
+//! Epochs are derived from the writer's `slots_per_epoch`, without warmup, which is
+//! `DEFAULT_SLOTS_PER_EPOCH` on mainnet.
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_clock::{Epoch, Slot},
+    solana_transaction_status::{Reward, RewardType},
+    std::collections::BTreeMap,
+};
+
+pub const REWARDS_TABLE_NAME: &str = "rewards";
+
+/// A reward as stored in the `rewards` table, without the recipient which is in the row key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RewardCell {
+    pub lamports: i64,
+    pub post_balance: u64,
+    pub reward_type: Option<RewardType>,
+    pub commission: Option<u8>,
+}
+
+impl RewardCell {
+    pub fn into_reward(self, pubkey: String) -> Reward {
+        Reward {
+            pubkey,
+            lamports: self.lamports,
+            post_balance: self.post_balance,
+            reward_type: self.reward_type,
+            commission: self.commission,
+        }
+    }
+}
+
+impl From<&Reward> for RewardCell {
+    fn from(reward: &Reward) -> Self {
+        Self {
+            lamports: reward.lamports,
+            post_balance: reward.post_balance,
+            reward_type: reward.reward_type,
+            commission: reward.commission,
+        }
+    }
+}
+
+/// Epoch a reward paid in the block at `slot` was earned in.
+pub fn reward_epoch(slot: Slot, slots_per_epoch: u64, reward_type: Option<RewardType>) -> Epoch {
+    let epoch = slot / slots_per_epoch.max(1);
+    match reward_type {
+        Some(RewardType::Staking) | Some(RewardType::Voting) => epoch.saturating_sub(1),
+        _ => epoch,
+    }
+}
+
+/// Whether `reward_type` is an inflation reward, as returned by `getInflationReward`.
+pub fn is_inflation_reward(reward_type: Option<RewardType>) -> bool {
+    matches!(reward_type, Some(RewardType::Staking) | Some(RewardType::Voting))
+}
+
+/// First key of the rows of `pubkey` for `epoch`.
+pub fn rewards_epoch_prefix(pubkey: &str, epoch: Epoch) -> String {
+    format!("{pubkey}/{epoch:016x}/")
+}
+
+pub fn rewards_key(pubkey: &str, epoch: Epoch, slot: Slot) -> String {
+    format!("{}{slot:016x}", rewards_epoch_prefix(pubkey, epoch))
+}
+
+/// Recipient, epoch and slot of a `rewards` row key.
+pub fn key_to_rewards(key: &str) -> Option<(&str, Epoch, Slot)> {
+    let mut parts = key.rsplitn(3, '/');
+    let slot = Slot::from_str_radix(parts.next()?, 16).ok()?;
+    let epoch = Epoch::from_str_radix(parts.next()?, 16).ok()?;
+    Some((parts.next()?, epoch, slot))
+}
+
+/// `rewards` rows of the block at `slot`.
+pub fn rewards_cells(
+    slot: Slot,
+    slots_per_epoch: u64,
+    rewards: &[Reward],
+) -> Vec<(String, Vec<RewardCell>)> {
+    let mut cells: BTreeMap<String, Vec<RewardCell>> = BTreeMap::new();
+    for reward in rewards {
+        cells
+            .entry(rewards_key(
+                &reward.pubkey,
+                reward_epoch(slot, slots_per_epoch, reward.reward_type),
+                slot,
+            ))
+            .or_default()
+            .push(reward.into());
+    }
+    cells.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, solana_clock::DEFAULT_SLOTS_PER_EPOCH};
+
+    fn reward(pubkey: &str, reward_type: RewardType) -> Reward {
+        Reward {
+            pubkey: pubkey.to_string(),
+            lamports: 10,
+            post_balance: 100,
+            reward_type: Some(reward_type),
+            commission: None,
+        }
+    }
+
+    #[test]
+    fn test_rewards_cells() {
+        let slot = 3 * DEFAULT_SLOTS_PER_EPOCH + 5;
+        let rewards = vec![
+            reward("validator", RewardType::Fee),
+            reward("validator", RewardType::Rent),
+            reward("vote", RewardType::Voting),
+        ];
+
+        let cells = rewards_cells(slot, DEFAULT_SLOTS_PER_EPOCH, &rewards);
+        assert_eq!(
+            cells.iter().map(|(key, cells)| (key.as_str(), cells.len())).collect::<Vec<_>>(),
+            vec![
+                ("validator/0000000000000003/000000000013c685", 2),
+                ("vote/0000000000000002/000000000013c685", 1),
+            ]
+        );
+        assert_eq!(key_to_rewards(&cells[1].0), Some(("vote", 2, slot)));
+        assert_eq!(cells[1].1[0].clone().into_reward("vote".to_string()), rewards[2]);
+        assert_eq!(key_to_rewards("vote/2"), None);
+
+        // Clusters with shorter epochs
+        let cells = rewards_cells(slot, 8192, &rewards);
+        assert_eq!(key_to_rewards(&cells[1].0), Some(("vote", slot / 8192 - 1, slot)));
+    }
+}
@@ -0,0 +1,45 @@
+//! Index of the slots with a stored block, for when the `blocks` row keys are salted by
+//! `slot_to_blocks_key` and no longer sorted by slot.
+//!
+//! The `slot-index` table has a row per block, keyed by `slot_to_key`, holding the slot as a
+//! bincode `u64`. The writer adds the row after the block itself, so listed slots can be read.
+//! Blocks uploaded before the index existed are added by scanning the `blocks` row keys, see
+//! `blocks_key_to_slot`.
+
+use {
+    crate::key::slot_to_key,
+    solana_clock::Slot,
+};
+
+pub const SLOT_INDEX_TABLE_NAME: &str = "slot-index";
+
+/// Row key and cell of the `slot-index` row of the block at `slot`.
+pub fn slot_index_cell(slot: Slot) -> (String, Slot) {
+    (slot_to_key(slot), slot)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::key::{blocks_key_to_slot, key_to_slot, slot_to_blocks_key},
+    };
+
+    #[test]
+    fn test_slot_index_cell() {
+        let (key, slot) = slot_index_cell(42);
+        assert_eq!(key, "000000000000002a");
+        assert_eq!(slot, 42);
+        assert_eq!(key_to_slot(&key), Some(42));
+
+        // Sorted by slot, unlike the salted block keys they are made from
+        let slots = [9, 10, 4096];
+        let mut keys: Vec<_> = slots
+            .iter()
+            .map(|slot| slot_index_cell(blocks_key_to_slot(&slot_to_blocks_key(*slot, true)).unwrap()).0)
+            .collect();
+        keys.sort();
+        let sorted: Vec<_> = keys.iter().filter_map(|key| key_to_slot(key)).collect();
+        assert_eq!(sorted, slots);
+    }
+}
@@ -0,0 +1,148 @@
+//! Token balance changes per owner and mint.
+//!
+//! The `token-balance-history` table has a row per owner and slot, keyed like `tx-by-addr`,
+//! holding the bincode `Vec<TokenBalanceChange>` of the transactions of that slot that changed
+//! the balance the owner holds of any mint. Balances of several token accounts of the same owner
+//! and mint are summed.
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_transaction_status::TransactionStatusMeta,
+    std::{collections::BTreeMap, str::FromStr},
+};
+
+pub const TOKEN_BALANCE_HISTORY_TABLE_NAME: &str = "token-balance-history";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalanceChange {
+    /// Where the transaction is located in the block
+    pub index: u32,
+    pub mint: Pubkey,
+    /// Raw amounts, in units of `10^-decimals` tokens
+    pub pre_amount: u64,
+    pub post_amount: u64,
+    pub decimals: u8,
+}
+
+impl TokenBalanceChange {
+    /// Raw amount gained, negative if lost
+    pub fn delta(&self) -> i128 {
+        self.post_amount as i128 - self.pre_amount as i128
+    }
+}
+
+/// Token balance change of an owner, as returned by the readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalanceHistoryEntry {
+    pub slot: Slot,
+    pub change: TokenBalanceChange,
+}
+
+/// Owners whose token balances the transaction changed, with the change, in owner and mint
+/// order. Balances recorded before owners were, and unparsable amounts, are skipped.
+pub fn token_balance_changes(
+    meta: &TransactionStatusMeta,
+    index: u32,
+) -> Vec<(Pubkey, TokenBalanceChange)> {
+    // (owner, mint) -> (pre amount, post amount, decimals)
+    let mut balances: BTreeMap<(Pubkey, Pubkey), (u64, u64, u8)> = BTreeMap::new();
+
+    let pre_balances = meta.pre_token_balances.iter().flatten().map(|balance| (balance, true));
+    let post_balances = meta.post_token_balances.iter().flatten().map(|balance| (balance, false));
+    for (balance, is_pre) in pre_balances.chain(post_balances) {
+        let (Ok(owner), Ok(mint), Ok(amount)) = (
+            Pubkey::from_str(&balance.owner),
+            Pubkey::from_str(&balance.mint),
+            balance.ui_token_amount.amount.parse::<u64>(),
+        ) else {
+            continue;
+        };
+        let entry = balances
+            .entry((owner, mint))
+            .or_insert((0, 0, balance.ui_token_amount.decimals));
+        if is_pre {
+            entry.0 = entry.0.saturating_add(amount);
+        } else {
+            entry.1 = entry.1.saturating_add(amount);
+        }
+    }
+
+    balances
+        .into_iter()
+        .filter(|(_, (pre_amount, post_amount, _))| pre_amount != post_amount)
+        .map(|((owner, mint), (pre_amount, post_amount, decimals))| {
+            (
+                owner,
+                TokenBalanceChange {
+                    index,
+                    mint,
+                    pre_amount,
+                    post_amount,
+                    decimals,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_account_decoder_client_types::token::UiTokenAmount,
+        solana_transaction_status::TransactionTokenBalance,
+    };
+
+    fn token_balance(account_index: u8, mint: &Pubkey, owner: &Pubkey, amount: u64) -> TransactionTokenBalance {
+        TransactionTokenBalance {
+            account_index,
+            mint: mint.to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: Some(amount as f64 / 100.0),
+                decimals: 2,
+                amount: amount.to_string(),
+                ui_amount_string: (amount as f64 / 100.0).to_string(),
+            },
+            owner: owner.to_string(),
+            program_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_token_balance_changes() {
+        let mint = Pubkey::new_unique();
+        let sender = Pubkey::new_unique();
+        let receiver = Pubkey::new_unique();
+        let meta = TransactionStatusMeta {
+            pre_token_balances: Some(vec![
+                token_balance(1, &mint, &sender, 500),
+                token_balance(2, &mint, &sender, 100),
+            ]),
+            post_token_balances: Some(vec![
+                token_balance(1, &mint, &sender, 300),
+                token_balance(2, &mint, &sender, 100),
+                // The receiver's token account was created by the transaction
+                token_balance(3, &mint, &receiver, 200),
+            ]),
+            ..TransactionStatusMeta::default()
+        };
+
+        let changes = token_balance_changes(&meta, 4);
+        let expected_change = |pre_amount, post_amount| TokenBalanceChange {
+            index: 4,
+            mint,
+            pre_amount,
+            post_amount,
+            decimals: 2,
+        };
+        let mut expected = vec![
+            (sender, expected_change(600, 400)),
+            (receiver, expected_change(0, 200)),
+        ];
+        expected.sort_by_key(|(owner, _)| *owner);
+        assert_eq!(changes, expected);
+        assert_eq!(changes.iter().map(|(_, change)| change.delta()).sum::<i128>(), 0);
+    }
+}
@@ -0,0 +1,87 @@
+use {
+    solana_pubkey::Pubkey,
+    solana_transaction_status::TransactionStatusMeta,
+    std::{collections::BTreeSet, str::FromStr},
+};
+
+pub const TX_BY_OWNER_TABLE_NAME: &str = "tx-by-owner";
+pub const TX_BY_MINT_TABLE_NAME: &str = "tx-by-mint";
+
+/// Owners and mints of the token accounts whose balances a transaction recorded.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TokenBalanceAccounts {
+    pub owners: BTreeSet<Pubkey>,
+    pub mints: BTreeSet<Pubkey>,
+}
+
+/// Owners and mints from the pre and post token balances of `meta`. Balances recorded before
+/// owners were, which have an empty owner, only contribute their mint.
+pub fn token_balance_accounts(meta: &TransactionStatusMeta) -> TokenBalanceAccounts {
+    let mut accounts = TokenBalanceAccounts::default();
+
+    let balances = meta
+        .pre_token_balances
+        .iter()
+        .chain(meta.post_token_balances.iter())
+        .flatten();
+    for balance in balances {
+        if let Ok(owner) = Pubkey::from_str(&balance.owner) {
+            accounts.owners.insert(owner);
+        }
+        if let Ok(mint) = Pubkey::from_str(&balance.mint) {
+            accounts.mints.insert(mint);
+        }
+    }
+
+    accounts
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_account_decoder_client_types::token::UiTokenAmount,
+        solana_transaction_status::TransactionTokenBalance,
+    };
+
+    fn token_balance(mint: &Pubkey, owner: &str) -> TransactionTokenBalance {
+        TransactionTokenBalance {
+            account_index: 1,
+            mint: mint.to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: Some(1.0),
+                decimals: 0,
+                amount: "1".to_string(),
+                ui_amount_string: "1".to_string(),
+            },
+            owner: owner.to_string(),
+            program_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_token_balance_accounts() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let receiver = Pubkey::new_unique();
+        let meta = TransactionStatusMeta {
+            pre_token_balances: Some(vec![
+                token_balance(&mint, &owner.to_string()),
+                token_balance(&mint, ""),
+            ]),
+            post_token_balances: Some(vec![
+                token_balance(&mint, &owner.to_string()),
+                token_balance(&mint, &receiver.to_string()),
+            ]),
+            ..TransactionStatusMeta::default()
+        };
+
+        let accounts = token_balance_accounts(&meta);
+        assert_eq!(accounts.owners, BTreeSet::from([owner, receiver]));
+        assert_eq!(accounts.mints, BTreeSet::from([mint]));
+        assert_eq!(
+            token_balance_accounts(&TransactionStatusMeta::default()),
+            TokenBalanceAccounts::default()
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use {
+    crate::{
+        cell_envelope::{unwrap_cell, CellError},
+        compression::{compress_with_dictionary, compress_with_level, CompressionMethod},
+    },
+    std::{
+        collections::HashMap,
+        fmt,
+        fs,
+        io,
+        path::Path,
+        sync::{Arc, RwLock},
+    },
+    zstd::dict::{DecoderDictionary, EncoderDictionary},
+};
+
+/// Magic number at the start of a dictionary produced by the zstd trainer.
+const ZSTD_DICTIONARY_MAGIC: u32 = 0xEC30_A437;
+
+// Dictionaries known to this process, keyed by id. Cells only carry the id, so writers and
+// readers must load the same dictionary files.
+lazy_static::lazy_static! {
+    static ref DICTIONARIES: RwLock<HashMap<u32, Arc<Dictionary>>> = RwLock::new(HashMap::new());
+}
+
+/// A registered dictionary, digested once for decompression and once per compression level
+/// rather than for every cell.
+pub struct Dictionary {
+    dictionary: Vec<u8>,
+    decoder: DecoderDictionary<'static>,
+    encoders: RwLock<HashMap<i32, Arc<EncoderDictionary<'static>>>>,
+}
+
+impl Dictionary {
+    fn new(dictionary: Vec<u8>) -> Self {
+        Self {
+            decoder: DecoderDictionary::copy(&dictionary),
+            dictionary,
+            encoders: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn decoder(&self) -> &DecoderDictionary<'static> {
+        &self.decoder
+    }
+
+    pub fn encoder(&self, level: i32) -> Arc<EncoderDictionary<'static>> {
+        if let Some(encoder) = self.encoders.read().unwrap().get(&level) {
+            return encoder.clone();
+        }
+        self.encoders
+            .write()
+            .unwrap()
+            .entry(level)
+            .or_insert_with(|| Arc::new(EncoderDictionary::copy(&self.dictionary, level)))
+            .clone()
+    }
+}
+
+/// Id the zstd trainer stores in the dictionary header.
+pub fn dictionary_id(dictionary: &[u8]) -> io::Result<u32> {
+    if dictionary.len() < 8 || read_u32(&dictionary[..4]) != ZSTD_DICTIONARY_MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a trained zstd dictionary",
+        ));
+    }
+    Ok(read_u32(&dictionary[4..8]))
+}
+
+/// Make `dictionary` available to `compress_with_dictionary` and `decompress`. Returns its id.
+pub fn register_dictionary(dictionary: Vec<u8>) -> io::Result<u32> {
+    let id = dictionary_id(&dictionary)?;
+    DICTIONARIES
+        .write()
+        .unwrap()
+        .insert(id, Arc::new(Dictionary::new(dictionary)));
+    Ok(id)
+}
+
+pub fn load_dictionary<P: AsRef<Path>>(path: P) -> io::Result<u32> {
+    register_dictionary(fs::read(path)?)
+}
+
+pub fn get_dictionary(id: u32) -> io::Result<Arc<Dictionary>> {
+    DICTIONARIES
+        .read()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("zstd dictionary {id} is not loaded"),
+            )
+        })
+}
+
+pub fn train_dictionary(samples: &[Vec<u8>], max_size: usize) -> io::Result<Vec<u8>> {
+    zstd::dict::from_samples(samples, max_size)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DictionaryReport {
+    pub dictionary_id: u32,
+    pub dictionary_size: usize,
+    /// Cells used to evaluate the dictionary, disjoint from the ones it was trained on.
+    pub cells: usize,
+    /// Size of the cells as currently stored.
+    pub stored_bytes: usize,
+    pub uncompressed_bytes: usize,
+    pub zstd_bytes: usize,
+    pub dictionary_bytes: usize,
+}
+
+impl fmt::Display for DictionaryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |bytes: usize| bytes as f64 * 100.0 / self.stored_bytes.max(1) as f64;
+        write!(
+            f,
+            "dictionary {} ({} bytes), evaluated on {} cells: stored {} bytes, uncompressed {} bytes \
+             ({:.1}%), zstd {} bytes ({:.1}%), zstd with dictionary {} bytes ({:.1}%)",
+            self.dictionary_id,
+            self.dictionary_size,
+            self.cells,
+            self.stored_bytes,
+            self.uncompressed_bytes,
+            percent(self.uncompressed_bytes),
+            self.zstd_bytes,
+            percent(self.zstd_bytes),
+            self.dictionary_bytes,
+            percent(self.dictionary_bytes),
+        )
+    }
+}
+
+/// Train a dictionary on half of `stored_cells`, as read from a table with or without the cell
+/// envelope, and measure on the other
+/// half how it compares with the current encoding and with plain zstd at `level`.
+///
+/// The dictionary is registered so it can be used right away.
+pub fn train_dictionary_from_cells(
+    stored_cells: &[Vec<u8>],
+    max_size: usize,
+    level: i32,
+) -> io::Result<(Vec<u8>, DictionaryReport)> {
+    let mut training_samples = vec![];
+    let mut evaluation_cells = vec![];
+    for (index, stored_cell) in stored_cells.iter().enumerate() {
+        let (_, sample) = unwrap_cell(stored_cell).map_err(|err| match err {
+            CellError::Io(err) => err,
+            err => io::Error::new(io::ErrorKind::InvalidData, err),
+        })?;
+        if index % 2 == 0 {
+            training_samples.push(sample);
+        } else {
+            evaluation_cells.push((stored_cell.len(), sample));
+        }
+    }
+
+    let dictionary = train_dictionary(&training_samples, max_size)?;
+    let dictionary_id = register_dictionary(dictionary.clone())?;
+
+    let mut report = DictionaryReport {
+        dictionary_id,
+        dictionary_size: dictionary.len(),
+        cells: evaluation_cells.len(),
+        ..DictionaryReport::default()
+    };
+    for (stored_len, sample) in &evaluation_cells {
+        report.stored_bytes += stored_len;
+        report.uncompressed_bytes +=
+            compress_with_level(CompressionMethod::NoCompression, None, sample)?.len();
+        report.zstd_bytes += compress_with_level(CompressionMethod::Zstd, Some(level), sample)?.len();
+        report.dictionary_bytes += compress_with_dictionary(dictionary_id, Some(level), sample)?.len();
+    }
+
+    Ok((dictionary, report))
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(bytes);
+    u32::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{
+            cell_envelope::{wrap_cell, PayloadEncoding},
+            compression::compress,
+        },
+    };
+
+    #[test]
+    fn test_train_dictionary_from_cells() {
+        let stored_cells = (0..1000u32)
+            .map(|i| {
+                let sample = format!("{{\"slot\":{},\"index\":{},\"err\":null}}", 1000 + i, i % 7);
+                let compressed = compress(CompressionMethod::Zstd, sample.as_bytes()).unwrap();
+                // Tables written before and after enabling the envelope mix both kinds of cells
+                if i % 3 == 0 {
+                    compressed
+                } else {
+                    wrap_cell(PayloadEncoding::Bincode, &compressed)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let (dictionary, report) = train_dictionary_from_cells(&stored_cells, 1024, 3).unwrap();
+        assert_eq!(dictionary_id(&dictionary).unwrap(), report.dictionary_id);
+        assert_eq!(report.cells, 500);
+        assert!(report.dictionary_bytes < report.zstd_bytes);
+
+        let mut corrupt = stored_cells[1].clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        let err = train_dictionary_from_cells(&[corrupt], 1024, 3).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -24,6 +24,9 @@ pub enum Error {
     #[error("Block not found: {0}")]
     BlockNotFound(Slot),
 
+    #[error("Not supported by this storage backend: {0}")]
+    Unsupported(&'static str),
+
     #[error("Signature not found")]
     SignatureNotFound,
 
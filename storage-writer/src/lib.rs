@@ -3,5 +3,8 @@ pub mod error;
 
 pub mod storage_adapter;
 
+pub mod upload_report;
+
 pub use crate::error::*;
-pub use crate::storage_adapter::*;
\ No newline at end of file
+pub use crate::storage_adapter::*;
+pub use crate::upload_report::*;
\ No newline at end of file
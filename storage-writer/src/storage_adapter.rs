@@ -1,6 +1,7 @@
 use {
     crate::{
         error::Error,
+        upload_report::UploadReport,
     },
     async_trait::async_trait,
     solana_clock::{
@@ -14,11 +15,66 @@ use {
     },
     std::{
         boxed::Box,
+        collections::BTreeMap,
+        fmt,
     },
 };
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Rows removed by `delete_confirmed_block`, or that would be removed in a dry run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeleteReport {
+    pub dry_run: bool,
+    /// Slots whose block was found.
+    pub slots: Vec<Slot>,
+    /// Slots of the requested range without a block.
+    pub missing_slots: Vec<Slot>,
+    /// Deleted row keys, keyed by table name.
+    pub rows: BTreeMap<String, Vec<String>>,
+    /// `tx` and `tx_full` rows of the block's transactions that belong to another slot, e.g.
+    /// because the transaction also landed in the block that replaced a fork. They are kept.
+    pub kept_rows: BTreeMap<String, Vec<String>>,
+}
+
+impl DeleteReport {
+    pub fn merge(&mut self, other: DeleteReport) {
+        self.slots.extend(other.slots);
+        self.missing_slots.extend(other.missing_slots);
+        for (table, row_keys) in other.rows {
+            self.rows.entry(table).or_default().extend(row_keys);
+        }
+        for (table, row_keys) in other.kept_rows {
+            self.kept_rows.entry(table).or_default().extend(row_keys);
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.values().map(Vec::len).sum()
+    }
+}
+
+impl fmt::Display for DeleteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run { "would delete" } else { "deleted" };
+        write!(
+            f,
+            "{} {} rows of {} blocks ({} slots without a block)",
+            verb,
+            self.row_count(),
+            self.slots.len(),
+            self.missing_slots.len(),
+        )?;
+        for (table, row_keys) in &self.rows {
+            write!(f, ", {}: {}", table, row_keys.len())?;
+        }
+        let kept = self.kept_rows.values().map(Vec::len).sum::<usize>();
+        if kept > 0 {
+            write!(f, ", kept {kept} rows belonging to other slots")?;
+        }
+        Ok(())
+    }
+}
 
 #[async_trait]
 pub trait LedgerStorageAdapter: Send + Sync {
@@ -26,7 +82,65 @@ pub trait LedgerStorageAdapter: Send + Sync {
         &self,
         slot: Slot,
         confirmed_block: VersionedConfirmedBlock,
-    ) -> Result<()>;
+    ) -> Result<UploadReport>;
+
+    /// Upload several confirmed blocks, in the order given. Backends may coalesce the writes of
+    /// the blocks, the default uploads them one by one.
+    ///
+    /// Returns a report per block, in the same order.
+    async fn upload_confirmed_blocks(
+        &self,
+        blocks: Vec<(Slot, VersionedConfirmedBlock)>,
+    ) -> Result<Vec<UploadReport>> {
+        let mut reports = Vec::with_capacity(blocks.len());
+        for (slot, confirmed_block) in blocks {
+            reports.push(self.upload_confirmed_block(slot, confirmed_block).await?);
+        }
+        Ok(reports)
+    }
+
+    /// Write out anything the backend buffered. The current backends write synchronously: the
+    /// rows of a block are written by the time its upload returns, so there is nothing to
+    /// flush. Backends that buffer writes across uploads must override it.
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Delete the block at `slot` together with the `tx`, `tx-by-addr` and `tx_full` rows
+    /// derived from its stored contents. With `dry_run` nothing is deleted, and the report lists
+    /// the rows that would be.
+    ///
+    /// Returns `Error::BlockNotFound` if there is no block at `slot`, and `Error::Unsupported`
+    /// for backends that can not delete blocks.
+    async fn delete_confirmed_block(&self, _slot: Slot, _dry_run: bool) -> Result<DeleteReport> {
+        Err(Error::Unsupported("deleting blocks"))
+    }
+
+    /// Delete the blocks of `start_slot..=end_slot`, see `delete_confirmed_block`. Slots without
+    /// a block are skipped.
+    ///
+    /// The default reads the block of every slot of the range one after the other, including
+    /// the slots without a block, so it costs a request per slot. Backends that can list their
+    /// stored slots should override it to only visit those.
+    async fn delete_confirmed_blocks(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        dry_run: bool,
+    ) -> Result<DeleteReport> {
+        let mut report = DeleteReport {
+            dry_run,
+            ..DeleteReport::default()
+        };
+        for slot in start_slot..=end_slot {
+            match self.delete_confirmed_block(slot, dry_run).await {
+                Ok(slot_report) => report.merge(slot_report),
+                Err(Error::BlockNotFound(slot)) => report.missing_slots.push(slot),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(report)
+    }
 
     fn should_include_in_tx_full(&self, address: &Pubkey) -> bool;
     fn should_include_in_tx_by_addr(&self, address: &Pubkey) -> bool;
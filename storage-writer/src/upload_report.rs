@@ -0,0 +1,97 @@
+use {
+    solana_clock::{
+        Slot,
+    },
+    std::{
+        collections::BTreeMap,
+        fmt,
+        time::Duration,
+    },
+};
+
+/// What an upload wrote to one table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TableUploadStats {
+    pub rows: usize,
+    pub bytes: usize,
+    /// Time spent writing the rows, including retries. When blocks are uploaded in a batch, this
+    /// is the time spent writing the rows of the whole batch.
+    pub latency: Duration,
+}
+
+/// Data left out of the indexes by the uploader filters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SkippedCounts {
+    /// Vote transactions left out of `tx-by-addr` and `tx_full`.
+    pub voting_tx: usize,
+    /// Failed transactions left out of `tx_full`.
+    pub error_tx: usize,
+    /// Transactions left out of `tx_full` by its include or exclude list.
+    pub tx_full_filter_tx: usize,
+    /// Program account entries left out of `tx-by-addr`.
+    pub program_accounts: usize,
+    /// Address entries left out of `tx-by-addr` by its include or exclude list.
+    pub tx_by_addr_filter: usize,
+    /// Program entries left out of `tx-by-program` by its include or exclude list.
+    pub tx_by_program_filter: usize,
+    /// Transactions left out of at least one table by the filter rules.
+    pub filter_rules: usize,
+    /// Address entries left out of `tx-by-addr` while their address is suppressed as too hot.
+    pub hot_accounts: usize,
+}
+
+/// Outcome of `upload_confirmed_block`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UploadReport {
+    pub slot: Slot,
+    pub transactions: usize,
+    /// Keyed by table name. Tables nothing was written to are left out.
+    pub tables: BTreeMap<String, TableUploadStats>,
+    pub skipped: SkippedCounts,
+    pub cached_transactions: usize,
+}
+
+impl UploadReport {
+    pub fn new(slot: Slot, transactions: usize) -> Self {
+        Self {
+            slot,
+            transactions,
+            ..Self::default()
+        }
+    }
+
+    pub fn bytes_written(&self) -> usize {
+        self.tables.values().map(|stats| stats.bytes).sum()
+    }
+
+    pub fn rows_written(&self) -> usize {
+        self.tables.values().map(|stats| stats.rows).sum()
+    }
+}
+
+impl fmt::Display for UploadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "slot {} [transactions: {}, rows: {}, bytes: {}",
+            self.slot,
+            self.transactions,
+            self.rows_written(),
+            self.bytes_written(),
+        )?;
+        for (table, stats) in &self.tables {
+            write!(
+                f,
+                ", {}: {} rows/{} bytes in {:?}",
+                table, stats.rows, stats.bytes, stats.latency
+            )?;
+        }
+        if self.skipped != SkippedCounts::default() {
+            write!(f, ", skipped: {:?}", self.skipped)?;
+        }
+        if self.cached_transactions > 0 {
+            write!(f, ", cached: {}", self.cached_transactions)?;
+        }
+        write!(f, "]")
+    }
+}